@@ -0,0 +1,123 @@
+//! Fire spread and shockwave target lookups, scanning every body against the spatial index
+//!
+//! Bodies are laid out at a constant density, as a growing pile of boxes and
+//! ragdolls would be, with a quarter of them on fire. Scanning checks every
+//! burning body against every body, so its cost grows with the square of the
+//! body count; the index cost grows linearly. Run with `cargo bench`.
+
+use bevy::prelude::*;
+use bevy_sandbox_ragdoll::systems::physics::SpatialIndex;
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+
+/// Matches the default fire tuning
+const SPREAD_RADIUS: f32 = 50.0;
+/// Distance between neighbouring bodies
+const SPACING: f32 = 40.0;
+const BODY_COUNTS: [u32; 4] = [100, 400, 1600, 6400];
+
+fn body_positions(count: u32) -> Vec<(Entity, Vec2)> {
+    let columns = (count as f32).sqrt().ceil() as u32;
+    (0..count)
+        .map(|index| {
+            let position = Vec2::new((index % columns) as f32, (index / columns) as f32) * SPACING;
+            (Entity::from_raw(index), position)
+        })
+        .collect()
+}
+
+/// Every fourth body is burning
+fn fire_positions(bodies: &[(Entity, Vec2)]) -> Vec<Vec2> {
+    bodies.iter().step_by(4).map(|&(_, position)| position).collect()
+}
+
+fn bench_fire_spread(c: &mut Criterion) {
+    let mut group = c.benchmark_group("fire_spread");
+
+    for count in BODY_COUNTS {
+        let bodies = body_positions(count);
+        let fires = fire_positions(&bodies);
+
+        group.bench_with_input(BenchmarkId::new("scan", count), &count, |b, _| {
+            b.iter(|| {
+                let mut in_reach = 0;
+                for &fire in &fires {
+                    for &(_, position) in &bodies {
+                        if fire.distance(position) < SPREAD_RADIUS {
+                            in_reach += 1;
+                        }
+                    }
+                }
+                black_box(in_reach)
+            });
+        });
+
+        // Rebuilding is part of every frame, so it is measured with the lookups
+        let mut index = SpatialIndex::default();
+        group.bench_with_input(BenchmarkId::new("index", count), &count, |b, _| {
+            b.iter(|| {
+                index.clear();
+                for &(entity, position) in &bodies {
+                    index.insert(entity, position);
+                }
+                index.drop_empty_cells();
+
+                let mut in_reach = 0;
+                for &fire in &fires {
+                    for (_, position) in index.candidates_in_radius(fire, SPREAD_RADIUS) {
+                        if fire.distance(position) < SPREAD_RADIUS {
+                            in_reach += 1;
+                        }
+                    }
+                }
+                black_box(in_reach)
+            });
+        });
+    }
+
+    group.finish();
+}
+
+fn bench_shockwave_ring(c: &mut Criterion) {
+    let mut group = c.benchmark_group("shockwave_ring");
+
+    for count in BODY_COUNTS {
+        let bodies = body_positions(count);
+        let mut index = SpatialIndex::default();
+        for &(entity, position) in &bodies {
+            index.insert(entity, position);
+        }
+
+        // One frame of a wave halfway through its expansion from the middle of the pile
+        let origin = bodies[bodies.len() / 2].1;
+        let (inner, outer) = (300.0, 400.0);
+
+        group.bench_with_input(BenchmarkId::new("scan", count), &count, |b, _| {
+            b.iter(|| {
+                bodies
+                    .iter()
+                    .filter(|&&(_, position)| {
+                        let distance = position.distance(origin);
+                        distance >= inner && distance < outer
+                    })
+                    .count()
+            });
+        });
+
+        group.bench_with_input(BenchmarkId::new("index", count), &count, |b, _| {
+            b.iter(|| {
+                index
+                    .candidates_in_ring(origin, inner, outer)
+                    .filter(|&(_, position)| {
+                        let distance = position.distance(origin);
+                        distance >= inner && distance < outer
+                    })
+                    .count()
+            });
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_fire_spread, bench_shockwave_ring);
+criterion_main!(benches);
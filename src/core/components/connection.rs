@@ -1,14 +1,15 @@
 //! Connection system components
 
 use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
 pub enum ConnectionKind {
     Fixed,
     Hinge,
 }
 
-#[derive(Component)]
+#[derive(Component, Clone)]
 pub struct Connection {
     pub a: Entity,
     pub b: Entity,
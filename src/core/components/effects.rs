@@ -2,6 +2,8 @@
 
 use bevy::prelude::*;
 
+use crate::core::constants::FIRE_EMIT_RATE;
+
 #[derive(Component)]
 pub struct ExplosionFlash {
     pub timer: Timer,
@@ -15,26 +17,20 @@ pub struct ExplosionShockwave {
     pub start_radius: f32,
 }
 
-#[derive(Component)]
-pub struct SmokeParticle {
-    pub lifetime: Timer,
-    pub velocity: Vec2,
-}
-
-#[derive(Component)]
-pub struct BloodParticle {
-    pub lifetime: Timer,
-    pub velocity: Vec2,
-}
-
 #[derive(Component)]
 pub struct OnFire {
     pub intensity: f32,
     pub duration: Timer,
+    /// Paces the flame particles, so their rate does not depend on the frame rate
+    pub emit_timer: Timer,
 }
 
-#[derive(Component)]
-pub struct FireParticle {
-    pub lifetime: Timer,
-    pub velocity: Vec2,
+impl OnFire {
+    pub fn new(intensity: f32, duration_secs: f32) -> Self {
+        Self {
+            intensity,
+            duration: Timer::from_seconds(duration_secs, TimerMode::Once),
+            emit_timer: Timer::from_seconds(1.0 / FIRE_EMIT_RATE, TimerMode::Repeating),
+        }
+    }
 }
@@ -11,8 +11,12 @@ pub struct Health {
     pub max: f32,
 }
 
-#[derive(Component)]
-pub struct Debris;
+/// Loose fragment or explosion debris, subject to the debris policy
+#[derive(Component, Default)]
+pub struct Debris {
+    /// Seconds the piece has been at rest
+    pub resting_secs: f32,
+}
 
 #[derive(Component)]
 pub struct Flammable {
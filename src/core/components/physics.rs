@@ -18,6 +18,9 @@ pub struct Explosion {
 pub struct ShockwaveRing {
     pub origin: Vec2,
     pub current_radius: f32,
+    /// Radius before the last step; bodies between it and the outer edge of
+    /// the wave are hit in that step
+    pub previous_radius: f32,
     pub max_radius: f32,
     pub peak_pressure: f32,
     pub wave_speed: f32,
@@ -1,11 +1,19 @@
-use bevy::prelude::KeyCode;
-
-pub const EXPLOSION_RADIUS: f32 = 400.0;
-pub const RAGDOLL_SPAWN_KEY: KeyCode = KeyCode::KeyR;
-pub const BOMB_SPAWN_KEY: KeyCode = KeyCode::KeyB;
-pub const FIRE_SPAWN_KEY: KeyCode = KeyCode::KeyF;
-pub const WOODEN_BOX_SPAWN_KEY: KeyCode = KeyCode::KeyW;
-pub const IRON_BLOCK_SPAWN_KEY: KeyCode = KeyCode::KeyI;
-pub const FIRE_DAMAGE_PER_SEC: f32 = 15.0;
-pub const FIRE_SPREAD_RADIUS: f32 = 50.0;
-pub const FIRE_DURATION: f32 = 8.0;
+pub const SCENE_FILE_PATH: &str = "sandbox_scene.ron";
+/// Tuning file, relative to the `assets` directory
+pub const TUNING_ASSET_PATH: &str = "sandbox.tuning.ron";
+/// Directory holding one scene file per named prefab
+pub const PREFAB_DIR: &str = "prefabs";
+/// Directory holding one file per saved level
+pub const LEVEL_DIR: &str = "levels";
+pub const REPLAY_FILE_PATH: &str = "sandbox_replay.ron";
+pub const REPLAY_TIMESTEP: f32 = 1.0 / 60.0;
+/// Key and mouse bindings, written whenever a control is rebound
+pub const BINDINGS_FILE_PATH: &str = "sandbox_bindings.ron";
+/// Summary of the last session, written on exit
+pub const STATS_JSON_PATH: &str = "sandbox_session.json";
+/// One row per session, appended on exit
+pub const STATS_CSV_PATH: &str = "sandbox_sessions.csv";
+/// Side of a spatial index cell, about the reach of fire spread and ignition
+pub const SPATIAL_CELL_SIZE: f32 = 100.0;
+/// Flame particle bursts per second from each burning body
+pub const FIRE_EMIT_RATE: f32 = 30.0;
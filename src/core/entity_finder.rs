@@ -1,4 +1,5 @@
 use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
 
 pub fn find_closest_entity<'a, T>(
     query: impl Iterator<Item = (Entity, &'a Transform, T)>,
@@ -19,3 +20,16 @@ pub fn find_closest_entity<'a, T>(
 
     closest_entity
 }
+
+/// Find the collider containing `point`, if any
+pub fn find_body_at_point(context: &RapierContext, point: Vec2) -> Option<Entity> {
+    context
+        .cast_ray(
+            point,
+            Vec2::new(0.0, -1.0), // Direction doesn't matter for point detection
+            0.1,                  // Very small distance, essentially a point query
+            true,
+            QueryFilter::default(),
+        )
+        .map(|(entity, _toi)| entity)
+}
@@ -0,0 +1,84 @@
+//! Gameplay events reported by the damage, fire and explosion systems
+//!
+//! The systems that destroy bodies, break joints, set fires and detonate bombs
+//! send these as it happens, so effects, statistics and tests can react to the
+//! outcome without repeating the damage logic. Each event is sent once, by the
+//! system that deals the final blow.
+
+use bevy::prelude::*;
+
+/// What dealt the damage that destroyed a body or broke a joint
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum DamageCause {
+    /// An `Explosion`, or the impulse it gave a ragdoll part
+    Explosion,
+    /// The expanding shockwave of a bomb
+    Shockwave,
+    Fire,
+    /// Connected ragdoll parts moving apart too violently
+    Stress,
+    /// A sudden change of velocity, such as landing from a fall
+    Impact,
+    /// Hitting another body
+    Collision,
+}
+
+/// A body lost all its health and was despawned
+#[derive(Event, Clone, Debug)]
+pub struct BodyDestroyed {
+    pub entity: Entity,
+    pub position: Vec2,
+    pub cause: DamageCause,
+}
+
+/// A ragdoll joint broke and `entity` came off `parent`
+#[derive(Event, Clone, Debug)]
+pub struct JointBroken {
+    pub entity: Entity,
+    pub parent: Entity,
+    pub position: Vec2,
+    pub cause: DamageCause,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum IgnitionCause {
+    /// The fire tool, the ignite hotkey, or igniting a selection
+    User,
+    /// Flames spreading from a nearby burning body
+    Spread,
+}
+
+/// A flammable body caught fire
+#[derive(Event, Clone, Debug)]
+pub struct Ignited {
+    pub entity: Entity,
+    pub intensity: f32,
+    pub cause: IgnitionCause,
+}
+
+/// A bomb went off or an `Explosion` was applied
+#[derive(Event, Clone, Debug)]
+pub struct Exploded {
+    pub position: Vec2,
+    pub radius: f32,
+    /// Peak pressure of the shockwave, or the force of an `Explosion`
+    pub pressure: f32,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ConnectionBreakCause {
+    /// The force its joint carried in the last physics step, as measured by
+    /// `measure_connection_forces`, exceeded what its material can hold
+    Overload,
+    /// One of the connected bodies is gone
+    BodyRemoved,
+}
+
+/// A user-made connection between bodies `a` and `b` was removed
+#[derive(Event, Clone, Debug)]
+pub struct ConnectionBroken {
+    pub entity: Entity,
+    pub a: Entity,
+    pub b: Entity,
+    pub cause: ConnectionBreakCause,
+}
@@ -3,5 +3,9 @@
 pub mod components;
 pub mod constants;
 pub mod entity_finder;
+pub mod events;
+pub mod polygon;
+pub mod rng;
 pub mod setup;
+pub mod tuning;
 pub mod utils;
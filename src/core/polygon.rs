@@ -0,0 +1,384 @@
+//! Outline geometry for user-drawn polygons
+//!
+//! A drawn outline is simplified, checked to be a simple polygon, wound
+//! counter-clockwise, triangulated by ear clipping, and the triangles merged
+//! back into as few convex pieces as the Hertel-Mehlhorn rule allows, since
+//! colliders and meshes are built from convex pieces and triangles.
+
+use bevy::prelude::*;
+
+/// Cross products below this count as collinear
+const EPSILON: f32 = 1e-4;
+
+fn cross(o: Vec2, a: Vec2, b: Vec2) -> f32 {
+    (a - o).perp_dot(b - o)
+}
+
+/// Twice the area enclosed by the outline, positive when counter-clockwise
+fn doubled_signed_area(points: &[Vec2]) -> f32 {
+    points
+        .iter()
+        .zip(points.iter().cycle().skip(1))
+        .map(|(a, b)| a.perp_dot(*b))
+        .sum()
+}
+
+/// Area enclosed by the outline, positive when it runs counter-clockwise
+pub fn signed_area(points: &[Vec2]) -> f32 {
+    doubled_signed_area(points) / 2.0
+}
+
+/// Centre of mass of the enclosed area, or the average point if there is none
+pub fn centroid(points: &[Vec2]) -> Vec2 {
+    let doubled_area = doubled_signed_area(points);
+    if doubled_area.abs() < EPSILON {
+        return points.iter().sum::<Vec2>() / points.len().max(1) as f32;
+    }
+
+    let weighted: Vec2 = points
+        .iter()
+        .zip(points.iter().cycle().skip(1))
+        .map(|(a, b)| (*a + *b) * a.perp_dot(*b))
+        .sum();
+    weighted / (3.0 * doubled_area)
+}
+
+/// Size of the axis-aligned box around the points
+pub fn extent(points: &[Vec2]) -> Vec2 {
+    let min = points.iter().copied().reduce(Vec2::min).unwrap_or_default();
+    let max = points.iter().copied().reduce(Vec2::max).unwrap_or_default();
+    max - min
+}
+
+fn distance_to_segment(point: Vec2, start: Vec2, end: Vec2) -> f32 {
+    let segment = end - start;
+    let length_squared = segment.length_squared();
+    if length_squared < EPSILON {
+        return point.distance(start);
+    }
+    let t = ((point - start).dot(segment) / length_squared).clamp(0.0, 1.0);
+    point.distance(start + segment * t)
+}
+
+/// Ramer-Douglas-Peucker on an open run of points, keeping both ends
+fn simplify_run(points: &[Vec2], tolerance: f32, kept: &mut Vec<Vec2>) {
+    let (first, last) = (points[0], points[points.len() - 1]);
+    let farthest = points[1..points.len() - 1]
+        .iter()
+        .enumerate()
+        .map(|(index, point)| (index + 1, distance_to_segment(*point, first, last)))
+        .max_by(|a, b| a.1.total_cmp(&b.1));
+
+    match farthest {
+        Some((index, distance)) if distance > tolerance => {
+            simplify_run(&points[..=index], tolerance, kept);
+            simplify_run(&points[index..], tolerance, kept);
+        }
+        _ => {
+            if kept.last() != Some(&first) {
+                kept.push(first);
+            }
+            kept.push(last);
+        }
+    }
+}
+
+/// Drop points that stray less than `tolerance` from the closed outline
+/// through their neighbours, along with repeated points
+pub fn simplify_outline(points: &[Vec2], tolerance: f32) -> Vec<Vec2> {
+    let mut deduplicated: Vec<Vec2> = Vec::with_capacity(points.len());
+    for &point in points {
+        if deduplicated.last().is_none_or(|last| last.distance(point) > tolerance) {
+            deduplicated.push(point);
+        }
+    }
+    // The outline closes on itself, so a last point on top of the first is repeated too
+    while deduplicated.len() > 1
+        && deduplicated[0].distance(deduplicated[deduplicated.len() - 1]) <= tolerance
+    {
+        deduplicated.pop();
+    }
+    if deduplicated.len() < 4 {
+        return deduplicated;
+    }
+
+    // Split the loop at the point farthest from the first, so both halves are open runs
+    let far = (1..deduplicated.len())
+        .max_by(|&a, &b| {
+            let distance_a = deduplicated[a].distance_squared(deduplicated[0]);
+            let distance_b = deduplicated[b].distance_squared(deduplicated[0]);
+            distance_a.total_cmp(&distance_b)
+        })
+        .unwrap_or(1);
+    let mut closed = deduplicated.clone();
+    closed.push(deduplicated[0]);
+
+    let mut kept = Vec::new();
+    simplify_run(&closed[..=far], tolerance, &mut kept);
+    simplify_run(&closed[far..], tolerance, &mut kept);
+    // The loop ends where it started
+    kept.pop();
+    kept
+}
+
+fn segments_cross(a: Vec2, b: Vec2, c: Vec2, d: Vec2) -> bool {
+    let (d1, d2) = (cross(a, b, c), cross(a, b, d));
+    let (d3, d4) = (cross(c, d, a), cross(c, d, b));
+    d1 * d2 < 0.0 && d3 * d4 < 0.0
+}
+
+/// Whether no two edges of the closed outline cross each other
+pub fn is_simple(points: &[Vec2]) -> bool {
+    let count = points.len();
+    for i in 0..count {
+        let (a, b) = (points[i], points[(i + 1) % count]);
+        // Neighbouring edges share a vertex, so only later, non-adjacent edges are checked
+        for j in i + 2..count {
+            if i == 0 && j == count - 1 {
+                continue;
+            }
+            if segments_cross(a, b, points[j], points[(j + 1) % count]) {
+                return false;
+            }
+        }
+    }
+    true
+}
+
+fn is_convex_corner(prev: Vec2, corner: Vec2, next: Vec2) -> bool {
+    cross(prev, corner, next) > EPSILON
+}
+
+fn in_triangle(point: Vec2, a: Vec2, b: Vec2, c: Vec2) -> bool {
+    cross(a, b, point) >= -EPSILON
+        && cross(b, c, point) >= -EPSILON
+        && cross(c, a, point) >= -EPSILON
+}
+
+/// Triangles covering a simple counter-clockwise outline, as indices into it
+pub fn triangulate(points: &[Vec2]) -> Vec<[usize; 3]> {
+    let mut remaining: Vec<usize> = (0..points.len()).collect();
+    let mut triangles = Vec::with_capacity(points.len().saturating_sub(2));
+
+    while remaining.len() > 3 {
+        let count = remaining.len();
+        let is_ear = |i: usize| {
+            let (prev, corner, next) = (
+                remaining[(i + count - 1) % count],
+                remaining[i],
+                remaining[(i + 1) % count],
+            );
+            let (a, b, c) = (points[prev], points[corner], points[next]);
+            is_convex_corner(a, b, c)
+                && remaining
+                    .iter()
+                    .filter(|&&other| other != prev && other != corner && other != next)
+                    .all(|&other| !in_triangle(points[other], a, b, c))
+        };
+
+        // Fall back to any corner if rounding left no clean ear, so clipping always ends
+        let ear = (0..count).find(|&i| is_ear(i)).unwrap_or(0);
+        triangles.push([
+            remaining[(ear + count - 1) % count],
+            remaining[ear],
+            remaining[(ear + 1) % count],
+        ]);
+        remaining.remove(ear);
+    }
+
+    if let [a, b, c] = remaining[..] {
+        triangles.push([a, b, c]);
+    }
+    triangles
+}
+
+fn is_convex(points: &[Vec2], piece: &[usize]) -> bool {
+    let count = piece.len();
+    (0..count).all(|i| {
+        let prev = points[piece[(i + count - 1) % count]];
+        let next = points[piece[(i + 1) % count]];
+        cross(prev, points[piece[i]], next) >= -EPSILON
+    })
+}
+
+/// Join two pieces along their shared edge from `a` to `b`, as seen from `first`
+fn merge_pieces(first: &[usize], second: &[usize], a: usize, b: usize) -> Vec<usize> {
+    // `first` runs b .. a once rotated, and `second` runs a .. b
+    let start = first.iter().position(|&vertex| vertex == b).unwrap_or(0);
+    let mut merged: Vec<usize> = first[start..].iter().chain(&first[..start]).copied().collect();
+
+    let start = second.iter().position(|&vertex| vertex == a).unwrap_or(0);
+    let second_run = second[start..].iter().chain(&second[..start]);
+    merged.extend(second_run.skip(1).take(second.len() - 2));
+    merged
+}
+
+/// Convex pieces covering a simple counter-clockwise outline, each
+/// counter-clockwise, found by merging triangles while the result stays convex
+pub fn convex_decomposition(points: &[Vec2]) -> Vec<Vec<Vec2>> {
+    let mut pieces: Vec<Vec<usize>> = triangulate(points)
+        .into_iter()
+        .map(|triangle| triangle.to_vec())
+        .collect();
+
+    'merge: loop {
+        for i in 0..pieces.len() {
+            for j in i + 1..pieces.len() {
+                let shared = (0..pieces[i].len()).find_map(|k| {
+                    let a = pieces[i][k];
+                    let b = pieces[i][(k + 1) % pieces[i].len()];
+                    let second = &pieces[j];
+                    (0..second.len())
+                        .any(|m| second[m] == b && second[(m + 1) % second.len()] == a)
+                        .then_some((a, b))
+                });
+                let Some((a, b)) = shared else {
+                    continue;
+                };
+
+                let merged = merge_pieces(&pieces[i], &pieces[j], a, b);
+                if is_convex(points, &merged) {
+                    pieces[i] = merged;
+                    pieces.swap_remove(j);
+                    continue 'merge;
+                }
+            }
+        }
+        break;
+    }
+
+    pieces
+        .into_iter()
+        .map(|piece| piece.into_iter().map(|index| points[index]).collect())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn square() -> Vec<Vec2> {
+        vec![
+            Vec2::new(0.0, 0.0),
+            Vec2::new(10.0, 0.0),
+            Vec2::new(10.0, 10.0),
+            Vec2::new(0.0, 10.0),
+        ]
+    }
+
+    /// Counter-clockwise L with its inner corner at (10, 10), area 300
+    fn l_shape() -> Vec<Vec2> {
+        vec![
+            Vec2::new(0.0, 0.0),
+            Vec2::new(20.0, 0.0),
+            Vec2::new(20.0, 10.0),
+            Vec2::new(10.0, 10.0),
+            Vec2::new(10.0, 20.0),
+            Vec2::new(0.0, 20.0),
+        ]
+    }
+
+    fn assert_convex_cover(points: &[Vec2], pieces: &[Vec<Vec2>]) {
+        for piece in pieces {
+            let count = piece.len();
+            assert!(count >= 3, "piece {piece:?} has fewer than 3 corners");
+            for i in 0..count {
+                let turn = cross(
+                    piece[(i + count - 1) % count],
+                    piece[i],
+                    piece[(i + 1) % count],
+                );
+                assert!(
+                    turn >= -EPSILON,
+                    "piece {piece:?} is not convex and counter-clockwise"
+                );
+            }
+        }
+
+        let covered: f32 = pieces.iter().map(|piece| signed_area(piece)).sum();
+        assert!((covered - signed_area(points)).abs() < 1e-3);
+    }
+
+    #[test]
+    fn concave_l_decomposes_into_convex_pieces_covering_its_area() {
+        let l_shape = l_shape();
+        assert!((signed_area(&l_shape) - 300.0).abs() < 1e-3);
+
+        let pieces = convex_decomposition(&l_shape);
+        assert!(
+            pieces.len() >= 2,
+            "an L is not convex, so cannot be one piece"
+        );
+        assert_convex_cover(&l_shape, &pieces);
+    }
+
+    #[test]
+    fn clockwise_outline_has_negative_area_and_decomposes_once_reversed() {
+        let mut clockwise = l_shape();
+        clockwise.reverse();
+        assert!((signed_area(&clockwise) + 300.0).abs() < 1e-3);
+        assert!(is_simple(&clockwise));
+
+        // The polygon tool turns outlines counter-clockwise before decomposing them
+        clockwise.reverse();
+        assert_convex_cover(&clockwise, &convex_decomposition(&clockwise));
+    }
+
+    #[test]
+    fn bow_tie_is_not_simple() {
+        let bow_tie = [
+            Vec2::new(0.0, 0.0),
+            Vec2::new(10.0, 10.0),
+            Vec2::new(10.0, 0.0),
+            Vec2::new(0.0, 10.0),
+        ];
+        assert!(!is_simple(&bow_tie));
+        assert!(is_simple(&square()));
+        assert!(is_simple(&l_shape()));
+    }
+
+    #[test]
+    fn collinear_and_duplicate_points_are_simplified_away() {
+        let outline = [
+            Vec2::new(0.0, 0.0),
+            Vec2::new(0.0, 0.0),
+            Vec2::new(5.0, 0.0),
+            Vec2::new(10.0, 0.0),
+            Vec2::new(10.0, 5.0),
+            Vec2::new(10.0, 10.0),
+            Vec2::new(10.0, 10.0),
+            Vec2::new(0.0, 10.0),
+            Vec2::new(0.0, 0.0),
+        ];
+        let simplified = simplify_outline(&outline, 0.5);
+        assert_eq!(simplified.len(), 4);
+        for corner in square() {
+            assert!(simplified.contains(&corner), "{corner} was dropped");
+        }
+    }
+
+    #[test]
+    fn collinear_corners_still_decompose() {
+        let outline = [
+            Vec2::new(0.0, 0.0),
+            Vec2::new(5.0, 0.0),
+            Vec2::new(10.0, 0.0),
+            Vec2::new(10.0, 10.0),
+            Vec2::new(5.0, 10.0),
+            Vec2::new(0.0, 10.0),
+        ];
+        assert_eq!(triangulate(&outline).len(), outline.len() - 2);
+        assert_convex_cover(&outline, &convex_decomposition(&outline));
+    }
+
+    #[test]
+    fn fewer_than_three_points_give_no_pieces() {
+        let segment = [Vec2::new(0.0, 0.0), Vec2::new(10.0, 0.0)];
+        for points in [&segment[..0], &segment[..1], &segment[..]] {
+            assert!(triangulate(points).is_empty());
+            assert!(convex_decomposition(points).is_empty());
+            assert_eq!(signed_area(points), 0.0);
+        }
+        assert_eq!(simplify_outline(&segment, 0.5), segment);
+    }
+}
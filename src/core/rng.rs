@@ -0,0 +1,77 @@
+//! Seeded random number generation for the simulation
+//!
+//! Anything random that affects physics or damage must draw from `SimRng` so a
+//! scene plus a seed always produces the same destruction. Purely cosmetic
+//! effects (blood, smoke and fire particles) may keep using `rand::rng()`.
+
+use bevy::prelude::*;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+/// Independent random streams, one per system that consumes randomness.
+///
+/// Each stream must only be drawn from by a single system: systems sharing
+/// `SimRng` may run in either order, but a stream's sequence stays the same.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum RngStream {
+    /// `apply_explosion` impulses and the fragments it spawns
+    Explosion,
+    /// `update_shockwave` torque and the fragments it spawns
+    Shockwave,
+    /// `bomb_timer_system` debris
+    Bomb,
+    /// `spread_fire` ignition rolls
+    Fire,
+}
+
+impl RngStream {
+    pub const ALL: [RngStream; 4] = [
+        RngStream::Explosion,
+        RngStream::Shockwave,
+        RngStream::Bomb,
+        RngStream::Fire,
+    ];
+}
+
+#[derive(Resource)]
+pub struct SimRng {
+    seed: u64,
+    streams: [ChaCha8Rng; RngStream::ALL.len()],
+}
+
+impl SimRng {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            streams: RngStream::ALL.map(|stream| {
+                let mut rng = ChaCha8Rng::seed_from_u64(seed);
+                rng.set_stream(stream as u64);
+                rng
+            }),
+        }
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Restart every stream from `seed`
+    pub fn reseed(&mut self, seed: u64) {
+        *self = Self::new(seed);
+    }
+
+    pub fn stream(&mut self, stream: RngStream) -> &mut ChaCha8Rng {
+        &mut self.streams[stream as usize]
+    }
+}
+
+impl Default for SimRng {
+    /// A fresh random seed; it is logged at startup so a run can be reproduced
+    fn default() -> Self {
+        Self::new(rand::random())
+    }
+}
+
+pub fn log_simulation_seed(sim_rng: Res<SimRng>) {
+    info!("Simulation seed: {}", sim_rng.seed());
+}
@@ -1,64 +1,52 @@
 use bevy::prelude::*;
-use bevy_rapier2d::prelude::*;
+use serde::{Deserialize, Serialize};
 
-pub fn setup(mut commands: Commands) {
-    commands.spawn(Camera2d);
+use crate::systems::camera::SandboxCamera;
+use crate::ui::ui_stats::StatsHud;
 
-    spawn_ground(&mut commands);
+pub fn setup(mut commands: Commands) {
+    commands.spawn((Camera2d, SandboxCamera));
 
+    // Statistics, below the top bar and the connection material panel
     commands.spawn((
+        Text::default(),
         Node {
             position_type: PositionType::Absolute,
-            top: Val::Px(10.0),
+            top: Val::Px(130.0),
             left: Val::Px(10.0),
             ..default()
         },
         TextFont {
-            font_size: 20.0,
+            font_size: 16.0,
             ..default()
         },
         TextColor(Color::WHITE),
+        StatsHud,
     ));
 }
 
-fn spawn_ground(commands: &mut Commands) {
-    let ground_thickness = 20.0;
-    let ground_width = 2000.0;
-    let ground_y = -300.0;
-
-    commands.spawn((
-        Sprite {
-            color: Color::srgb(0.3, 0.3, 0.3),
-            custom_size: Some(Vec2::new(ground_width, ground_thickness)),
-            ..default()
-        },
-        Transform::from_xyz(0.0, ground_y, 0.0),
-        RigidBody::Fixed,
-        Collider::cuboid(ground_width / 2.0, ground_thickness / 2.0),
-    ));
-
-    let wall_thickness = 20.0;
-    let wall_height = 1000.0;
+/// The arena the simulation runs in. For the built-in arena: the top of the
+/// floor, the inner faces of the side walls, and the top of the walls. Levels
+/// store their own bounds.
+#[derive(Resource, Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+pub struct WorldBounds {
+    pub min: Vec2,
+    pub max: Vec2,
+}
 
-    commands.spawn((
-        Sprite {
-            color: Color::srgb(0.3, 0.3, 0.3),
-            custom_size: Some(Vec2::new(wall_thickness, wall_height)),
-            ..default()
-        },
-        Transform::from_xyz(-640.0, 0.0, 0.0),
-        RigidBody::Fixed,
-        Collider::cuboid(wall_thickness / 2.0, wall_height / 2.0),
-    ));
+impl Default for WorldBounds {
+    fn default() -> Self {
+        Self {
+            min: Vec2::new(-630.0, -290.0),
+            max: Vec2::new(630.0, 500.0),
+        }
+    }
+}
 
-    commands.spawn((
-        Sprite {
-            color: Color::srgb(0.3, 0.3, 0.3),
-            custom_size: Some(Vec2::new(wall_thickness, wall_height)),
-            ..default()
-        },
-        Transform::from_xyz(640.0, 0.0, 0.0),
-        RigidBody::Fixed,
-        Collider::cuboid(wall_thickness / 2.0, wall_height / 2.0),
-    ));
+impl WorldBounds {
+    /// Grow the bounds just enough to contain `point`
+    pub fn include(&mut self, point: Vec2) {
+        self.min = self.min.min(point);
+        self.max = self.max.max(point);
+    }
 }
@@ -0,0 +1,302 @@
+//! Data-driven balance values
+//!
+//! Damage, fire, explosion, connection, particle and debris numbers are read
+//! from a RON tuning asset (`assets/sandbox.tuning.ron`) into one resource per
+//! domain, and copied again whenever the file changes on disk. Every field has
+//! a default matching the original hard-coded value, so the file only needs the
+//! values being tuned and the sandbox still runs when the asset system is not
+//! available.
+
+use std::fmt;
+
+use bevy::asset::io::Reader;
+use bevy::asset::{AssetLoader, LoadContext};
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::core::constants::TUNING_ASSET_PATH;
+use crate::systems::damage::connection::ConnectionMaterial;
+
+#[derive(Asset, TypePath, Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct Tuning {
+    pub explosion: ExplosionTuning,
+    pub fire: FireTuning,
+    pub joint_damage: JointDamageTuning,
+    pub connections: ConnectionTuning,
+    pub particles: ParticleTuning,
+    pub debris: DebrisTuning,
+}
+
+#[derive(Resource, Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct ExplosionTuning {
+    /// Radius reached by a bomb's shockwave
+    pub radius: f32,
+    /// Pressure at the shockwave front when a bomb goes off
+    pub peak_pressure: f32,
+}
+
+impl Default for ExplosionTuning {
+    fn default() -> Self {
+        Self {
+            radius: 400.0,
+            peak_pressure: 80000.0,
+        }
+    }
+}
+
+#[derive(Resource, Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct FireTuning {
+    pub damage_per_sec: f32,
+    /// Burning objects can ignite flammable objects closer than this
+    pub spread_radius: f32,
+    /// Seconds an object keeps burning
+    pub duration: f32,
+}
+
+impl Default for FireTuning {
+    fn default() -> Self {
+        Self {
+            damage_per_sec: 15.0,
+            spread_radius: 50.0,
+            duration: 8.0,
+        }
+    }
+}
+
+/// Stress model used by `check_joint_damage`:
+/// `stress = linear_stress_factor * |Δv| + angular_stress_factor * |Δω|`
+#[derive(Resource, Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct JointDamageTuning {
+    pub linear_stress_factor: f32,
+    pub angular_stress_factor: f32,
+    /// Stress below this does no damage
+    pub stress_threshold: f32,
+    /// Damage per unit of stress above the threshold
+    pub damage_per_stress: f32,
+    /// Stress above this counts as extreme and multiplies the damage
+    pub extreme_stress: f32,
+    pub max_extreme_multiplier: f32,
+}
+
+impl Default for JointDamageTuning {
+    fn default() -> Self {
+        Self {
+            linear_stress_factor: 0.015,
+            angular_stress_factor: 0.15,
+            stress_threshold: 3.0,
+            damage_per_stress: 0.7,
+            extreme_stress: 15.0,
+            max_extreme_multiplier: 2.0,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+pub struct MaterialTuning {
+    /// Damping coefficient. Damping > 2.5 greatly improves stability,
+    /// rubber/rope materials need damping > 3
+    pub damping: f32,
+    /// Force the joint may carry before it snaps, in the sandbox's pixel units:
+    /// a default 60x60 wooden box weighs about 2.8 million
+    pub break_force: f32,
+}
+
+#[derive(Resource, Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct ConnectionTuning {
+    pub wood: MaterialTuning,
+    pub metal: MaterialTuning,
+    pub rope: MaterialTuning,
+    pub plastic: MaterialTuning,
+}
+
+impl ConnectionTuning {
+    pub fn material(&self, material: ConnectionMaterial) -> &MaterialTuning {
+        match material {
+            ConnectionMaterial::Wood => &self.wood,
+            ConnectionMaterial::Metal => &self.metal,
+            ConnectionMaterial::Rope => &self.rope,
+            ConnectionMaterial::Plastic => &self.plastic,
+        }
+    }
+}
+
+impl Default for ConnectionTuning {
+    fn default() -> Self {
+        Self {
+            // Soft material, needs high damping
+            wood: MaterialTuning {
+                damping: 3.5,
+                break_force: 25_000_000.0,
+            },
+            // Rigid material, moderate damping
+            metal: MaterialTuning {
+                damping: 2.5,
+                break_force: 75_000_000.0,
+            },
+            // Very soft, needs very high damping
+            rope: MaterialTuning {
+                damping: 5.0,
+                break_force: 10_000_000.0,
+            },
+            // Soft material, needs high damping
+            plastic: MaterialTuning {
+                damping: 3.0,
+                break_force: 35_000_000.0,
+            },
+        }
+    }
+}
+
+#[derive(Resource, Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct ParticleTuning {
+    /// Most effect particles alive at once; bursts are thinned out from half of this
+    pub max_particles: usize,
+}
+
+impl Default for ParticleTuning {
+    fn default() -> Self {
+        Self {
+            max_particles: 2000,
+        }
+    }
+}
+
+/// What happens to a debris piece once it has come to rest
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SettledDebris {
+    /// Stays a body, put to sleep until something hits it
+    Sleep,
+    /// Loses its body and collider, and only fades out
+    Visual,
+}
+
+#[derive(Resource, Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct DebrisTuning {
+    /// Most debris pieces at once; the oldest are removed to make room
+    pub max_count: usize,
+    /// Alpha lost per second, until the piece is gone
+    pub fade_per_sec: f32,
+    /// Pieces moving slower than this in pixels per second, and turning slower
+    /// than a tenth of it in radians per second, count as resting
+    pub settle_speed: f32,
+    /// Seconds a piece has to rest before it settles
+    pub settle_secs: f32,
+    pub settled: SettledDebris,
+    /// Depth below the bottom of the world at which pieces that fell out are removed
+    pub kill_depth: f32,
+}
+
+impl Default for DebrisTuning {
+    fn default() -> Self {
+        Self {
+            max_count: 300,
+            fade_per_sec: 0.3,
+            settle_speed: 5.0,
+            settle_secs: 0.5,
+            settled: SettledDebris::Visual,
+            kill_depth: 110.0,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum TuningLoadError {
+    Io(std::io::Error),
+    Parse(ron::error::SpannedError),
+}
+
+impl fmt::Display for TuningLoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TuningLoadError::Io(err) => write!(f, "i/o error: {err}"),
+            TuningLoadError::Parse(err) => write!(f, "could not parse tuning: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for TuningLoadError {}
+
+#[derive(Default)]
+pub struct TuningLoader;
+
+impl AssetLoader for TuningLoader {
+    type Asset = Tuning;
+    type Settings = ();
+    type Error = TuningLoadError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Tuning, TuningLoadError> {
+        let mut bytes = Vec::new();
+        reader
+            .read_to_end(&mut bytes)
+            .await
+            .map_err(TuningLoadError::Io)?;
+        ron::de::from_bytes(&bytes).map_err(TuningLoadError::Parse)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["tuning.ron"]
+    }
+}
+
+/// Keeps the tuning asset loaded so changes to the file are picked up
+#[derive(Resource)]
+pub struct TuningHandle(pub Handle<Tuning>);
+
+pub fn load_tuning(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(TuningHandle(asset_server.load(TUNING_ASSET_PATH)));
+}
+
+/// The per-domain resources a tuning asset is copied into
+#[derive(SystemParam)]
+pub struct TuningResources<'w> {
+    explosion: ResMut<'w, ExplosionTuning>,
+    fire: ResMut<'w, FireTuning>,
+    joint_damage: ResMut<'w, JointDamageTuning>,
+    connections: ResMut<'w, ConnectionTuning>,
+    particles: ResMut<'w, ParticleTuning>,
+    debris: ResMut<'w, DebrisTuning>,
+}
+
+impl TuningResources<'_> {
+    fn apply(&mut self, tuning: &Tuning) {
+        *self.explosion = tuning.explosion.clone();
+        *self.fire = tuning.fire.clone();
+        *self.joint_damage = tuning.joint_damage.clone();
+        *self.connections = tuning.connections.clone();
+        *self.particles = tuning.particles.clone();
+        *self.debris = tuning.debris.clone();
+    }
+}
+
+/// Copy the tuning asset into the per-domain resources when it loads or changes
+pub fn apply_tuning_changes(
+    mut events: EventReader<AssetEvent<Tuning>>,
+    assets: Res<Assets<Tuning>>,
+    handle: Res<TuningHandle>,
+    mut resources: TuningResources,
+) {
+    for event in events.read() {
+        if !event.is_loaded_with_dependencies(&handle.0) && !event.is_modified(&handle.0) {
+            continue;
+        }
+        let Some(tuning) = assets.get(&handle.0) else {
+            continue;
+        };
+
+        resources.apply(tuning);
+        info!("Applied tuning from {TUNING_ASSET_PATH}");
+    }
+}
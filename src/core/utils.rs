@@ -1,3 +1,4 @@
+use bevy::ecs::system::SystemParam;
 use bevy::prelude::*;
 
 pub fn get_cursor_world_position(
@@ -16,6 +17,19 @@ pub fn get_cursor_world_position(
     }
 }
 
+/// The window and camera, to look up where the cursor is in the world
+#[derive(SystemParam)]
+pub struct CursorWorldPosition<'w, 's> {
+    windows: Query<'w, 's, &'static Window>,
+    camera_q: Query<'w, 's, (&'static Camera, &'static GlobalTransform)>,
+}
+
+impl CursorWorldPosition<'_, '_> {
+    pub fn get(&self) -> Option<Vec2> {
+        get_cursor_world_position(&self.windows, &self.camera_q)
+    }
+}
+
 pub fn fade_sprite_alpha(sprite: &mut Sprite, fade_amount: f32) {
     let Srgba {
         red,
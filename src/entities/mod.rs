@@ -2,4 +2,6 @@
 
 pub mod obstacles;
 pub mod ragdoll;
+pub mod spawnable;
+pub mod terrain;
 pub mod weapons;
@@ -1,51 +1,73 @@
 use bevy::prelude::*;
 use bevy_rapier2d::prelude::*;
 
-use crate::core::constants::IRON_BLOCK_SPAWN_KEY;
-use crate::core::utils::get_cursor_world_position;
+use crate::entities::obstacles::shape::{insert_shape_visual, BodyShape};
+use crate::entities::spawnable::{SpawnCategory, SpawnShape, Spawnable};
 use crate::systems::damage::connection::Connectable;
+use crate::systems::input::action_map::InputBinding;
 use crate::systems::input::drag::Draggable;
 
 #[derive(Component)]
 pub struct IronBlock;
 
-pub fn spawn_iron_block_on_keypress(
-    mut commands: Commands,
-    keyboard: Res<ButtonInput<KeyCode>>,
-    windows: Query<&Window>,
-    camera_q: Query<(&Camera, &GlobalTransform)>,
-) {
-    if keyboard.just_pressed(IRON_BLOCK_SPAWN_KEY) {
-        if let Some(world_pos) = get_cursor_world_position(&windows, &camera_q) {
-            spawn_iron_block(&mut commands, world_pos);
-        }
+pub fn iron_block_spawnable() -> Spawnable {
+    Spawnable {
+        id: "iron_block",
+        name: "Iron",
+        color: IRON_COLOR,
+        category: SpawnCategory::Obstacles,
+        shape: SpawnShape::Shaped {
+            spawn: |commands, position, size, shape| {
+                vec![spawn_iron_body(commands, position, size, shape)]
+            },
+            default_size: Vec2::new(60.0, 60.0),
+        },
+        hotkey: Some(InputBinding::key(KeyCode::KeyI)),
     }
 }
 
-pub fn spawn_iron_block_from_ui(commands: &mut Commands, position: Vec2) {
-    spawn_iron_block(commands, position);
+pub fn spawn_iron_block_from_ui(commands: &mut Commands, position: Vec2) -> Entity {
+    spawn_iron_block(commands, position)
 }
 
-fn spawn_iron_block(commands: &mut Commands, position: Vec2) {
-    let width = 60.0;
-    let height = 60.0;
+fn spawn_iron_block(commands: &mut Commands, position: Vec2) -> Entity {
+    spawn_iron_block_with_size(commands, position, Vec2::new(60.0, 60.0))
+}
 
-    commands.spawn((
-        Sprite {
-            color: Color::srgb(0.6, 0.6, 0.65),
-            custom_size: Some(Vec2::new(width, height)),
-            ..default()
-        },
-        Transform::from_xyz(position.x, position.y, 0.0),
+pub const IRON_COLOR: Color = Color::srgb(0.6, 0.6, 0.65);
+
+/// Physics components of an iron body, whatever its shape. Iron takes no damage.
+pub fn iron_body() -> impl Bundle {
+    (
         RigidBody::Dynamic,
-        Collider::cuboid(width / 2.0, height / 2.0),
         ColliderMassProperties::Density(7.8),
+        ReadMassProperties::default(),
         Restitution::coefficient(0.2),
         Friction::coefficient(0.5),
+        IronBlock,
+    )
+}
+
+pub fn spawn_iron_block_with_size(commands: &mut Commands, position: Vec2, size: Vec2) -> Entity {
+    spawn_iron_body(commands, position, size, BodyShape::Rectangle)
+}
+
+/// Spawn an iron body in `shape`, filling the box of `size` around `position`
+pub fn spawn_iron_body(
+    commands: &mut Commands,
+    position: Vec2,
+    size: Vec2,
+    shape: BodyShape,
+) -> Entity {
+    let mut entity_commands = commands.spawn((
+        Transform::from_xyz(position.x, position.y, 0.0),
+        shape.collider(size),
+        iron_body(),
         ExternalImpulse::default(),
         Velocity::zero(),  // Initialize with zero velocity for stable physics
-        IronBlock,
         Draggable,
         Connectable,
     ));
+    insert_shape_visual(&mut entity_commands, shape, size, IRON_COLOR);
+    entity_commands.id()
 }
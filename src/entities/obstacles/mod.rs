@@ -1,7 +1,9 @@
 //! Obstacle entities (boxes, blocks, etc.)
 
 pub mod iron_block;
+pub mod polygon;
+pub mod shape;
 pub mod wooden_box;
 
-pub use iron_block::spawn_iron_block_on_keypress;
-pub use wooden_box::spawn_wooden_box_on_keypress;
+pub use iron_block::iron_block_spawnable;
+pub use wooden_box::wooden_box_spawnable;
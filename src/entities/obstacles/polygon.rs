@@ -0,0 +1,179 @@
+//! Custom rigid bodies drawn with the polygon tool
+//!
+//! The outline is kept on the body, so it can be saved, drawn as a mesh and
+//! broken into fragments. The collider is a compound of convex pieces, since
+//! a concave outline cannot be one convex shape.
+
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::core::polygon::{convex_decomposition, extent, signed_area};
+use crate::entities::obstacles::iron_block::{iron_body, IRON_COLOR};
+use crate::entities::obstacles::wooden_box::{wooden_body, WOOD_COLOR};
+use crate::systems::damage::connection::Connectable;
+use crate::systems::input::drag::Draggable;
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum PolygonMaterial {
+    /// Burns and breaks like a wooden box
+    #[default]
+    Wood,
+    /// Heavy and unbreakable like an iron block
+    Iron,
+    /// Fixed in place, for ramps, pivots and custom terrain
+    Static,
+}
+
+impl PolygonMaterial {
+    pub const ALL: [PolygonMaterial; 3] = [
+        PolygonMaterial::Wood,
+        PolygonMaterial::Iron,
+        PolygonMaterial::Static,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            PolygonMaterial::Wood => "Wood",
+            PolygonMaterial::Iron => "Iron",
+            PolygonMaterial::Static => "Static",
+        }
+    }
+
+    pub fn color(self) -> Color {
+        match self {
+            PolygonMaterial::Wood => WOOD_COLOR,
+            PolygonMaterial::Iron => IRON_COLOR,
+            PolygonMaterial::Static => Color::srgb(0.3, 0.3, 0.3),
+        }
+    }
+}
+
+/// A body with a drawn outline
+#[derive(Component, Clone, Debug)]
+pub struct PolygonBody {
+    pub material: PolygonMaterial,
+    /// Counter-clockwise outline relative to the body's centre of mass
+    pub points: Vec<Vec2>,
+}
+
+impl PolygonBody {
+    /// Size of the box around the outline, used where other bodies use their sprite size
+    pub fn extent(&self) -> Vec2 {
+        extent(&self.points)
+    }
+}
+
+/// One convex collider per piece of the outline, or a box around it if no
+/// piece is usable
+fn polygon_collider(points: &[Vec2]) -> Collider {
+    let mut pieces: Vec<Collider> = convex_decomposition(points)
+        .iter()
+        .filter_map(|piece| Collider::convex_hull(piece))
+        .collect();
+
+    match pieces.len() {
+        0 => {
+            let half_extent = extent(points) / 2.0;
+            Collider::cuboid(half_extent.x.max(1.0), half_extent.y.max(1.0))
+        }
+        1 => pieces.remove(0),
+        _ => Collider::compound(
+            pieces
+                .into_iter()
+                .map(|piece| (Vect::ZERO, 0.0, piece))
+                .collect(),
+        ),
+    }
+}
+
+/// Spawn a body at `position` with the counter-clockwise `points` around it.
+/// Its mesh is attached by `attach_polygon_meshes` when rendering.
+pub fn spawn_polygon_body(
+    commands: &mut Commands,
+    position: Vec2,
+    points: &[Vec2],
+    material: PolygonMaterial,
+) -> Entity {
+    let mut entity_commands = commands.spawn((
+        Transform::from_xyz(position.x, position.y, 0.0),
+        Visibility::default(),
+        polygon_collider(points),
+        ExternalImpulse::default(),
+        Velocity::zero(),  // Initialize with zero velocity for stable physics
+        PolygonBody {
+            material,
+            points: points.to_vec(),
+        },
+        Draggable,
+        Connectable,
+    ));
+
+    match material {
+        PolygonMaterial::Wood => {
+            entity_commands.insert(wooden_body(signed_area(points).abs()));
+        }
+        PolygonMaterial::Iron => {
+            entity_commands.insert(iron_body());
+        }
+        PolygonMaterial::Static => {
+            entity_commands.insert((
+                RigidBody::Fixed,
+                Friction::coefficient(0.7),
+                ReadMassProperties::default(),
+            ));
+        }
+    }
+
+    entity_commands.id()
+}
+
+/// Filled mesh of a simple counter-clockwise outline, with texture coordinates
+/// spanning the box around it
+#[cfg(feature = "render")]
+pub(crate) fn outline_mesh(points: &[Vec2]) -> Mesh {
+    use bevy::render::mesh::{Indices, PrimitiveTopology};
+    use bevy::render::render_asset::RenderAssetUsages;
+
+    use crate::core::polygon::triangulate;
+
+    let min = points.iter().copied().reduce(Vec2::min).unwrap_or_default();
+    let size = extent(points).max(Vec2::ONE);
+
+    let positions: Vec<[f32; 3]> = points.iter().map(|p| [p.x, p.y, 0.0]).collect();
+    let normals = vec![[0.0, 0.0, 1.0]; positions.len()];
+    let uvs: Vec<[f32; 2]> = points
+        .iter()
+        .map(|p| {
+            let uv = (*p - min) / size;
+            [uv.x, 1.0 - uv.y]
+        })
+        .collect();
+    let indices: Vec<u32> = triangulate(points)
+        .into_iter()
+        .flatten()
+        .map(|index| index as u32)
+        .collect();
+
+    Mesh::new(PrimitiveTopology::TriangleList, RenderAssetUsages::default())
+        .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, positions)
+        .with_inserted_attribute(Mesh::ATTRIBUTE_NORMAL, normals)
+        .with_inserted_attribute(Mesh::ATTRIBUTE_UV_0, uvs)
+        .with_inserted_indices(Indices::U32(indices))
+}
+
+/// Give newly spawned polygon bodies a filled mesh of their outline
+#[cfg(feature = "render")]
+pub fn attach_polygon_meshes(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    query: Query<(Entity, &PolygonBody), Added<PolygonBody>>,
+) {
+    for (entity, polygon) in query.iter() {
+        commands.entity(entity).insert((
+            Mesh2d(meshes.add(outline_mesh(&polygon.points))),
+            MeshMaterial2d(materials.add(ColorMaterial::from_color(polygon.material.color()))),
+        ));
+    }
+}
@@ -0,0 +1,218 @@
+//! Primitive shapes that wooden boxes and iron blocks can be dragged out in
+//!
+//! A shaped body is sized by the box around it and centred on that box, like a
+//! plain box. Rectangles are drawn with a sprite; the other shapes carry a
+//! `ShapedBody` and are drawn with a mesh of their outline.
+
+use std::f32::consts::{FRAC_PI_2, PI, TAU};
+
+use bevy::ecs::system::EntityCommands;
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Segments in the outline of a full circle; capsule ends get half as many
+const CIRCLE_SEGMENTS: usize = 32;
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum BodyShape {
+    #[default]
+    Rectangle,
+    /// Sized by its diameter
+    Circle,
+    /// Right triangle with the right angle in its bottom left corner
+    Triangle,
+    /// Rounded at both ends of its longer side
+    Capsule,
+}
+
+impl BodyShape {
+    pub const ALL: [BodyShape; 4] = [
+        BodyShape::Rectangle,
+        BodyShape::Circle,
+        BodyShape::Triangle,
+        BodyShape::Capsule,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            BodyShape::Rectangle => "Box",
+            BodyShape::Circle => "Circle",
+            BodyShape::Triangle => "Triangle",
+            BodyShape::Capsule => "Capsule",
+        }
+    }
+
+    /// Centre and size of the shape dragged from `start` to `end`, with each
+    /// side kept between `min` and `max`. A circle is centred on `start` with
+    /// the drag length as its radius; the other shapes fill the dragged box.
+    pub fn drag_bounds(self, start: Vec2, end: Vec2, min: f32, max: f32) -> (Vec2, Vec2) {
+        match self {
+            BodyShape::Circle => {
+                let diameter = (start.distance(end) * 2.0).clamp(min, max);
+                (start, Vec2::splat(diameter))
+            }
+            _ => {
+                let size = (end - start).abs().clamp(Vec2::splat(min), Vec2::splat(max));
+                ((start + end) / 2.0, size)
+            }
+        }
+    }
+
+    pub fn collider(self, size: Vec2) -> Collider {
+        let half = size / 2.0;
+        match self {
+            BodyShape::Rectangle => Collider::cuboid(half.x, half.y),
+            BodyShape::Circle => Collider::ball(half.min_element()),
+            BodyShape::Triangle => Collider::triangle(
+                Vec2::new(-half.x, -half.y),
+                Vec2::new(half.x, -half.y),
+                Vec2::new(-half.x, half.y),
+            ),
+            BodyShape::Capsule => {
+                let (radius, half_length) = capsule_dimensions(size);
+                if size.x >= size.y {
+                    Collider::capsule_x(half_length, radius)
+                } else {
+                    Collider::capsule_y(half_length, radius)
+                }
+            }
+        }
+    }
+
+    /// Area of the shape, which health and fragments scale with
+    pub fn area(self, size: Vec2) -> f32 {
+        match self {
+            BodyShape::Rectangle => size.x * size.y,
+            BodyShape::Circle => PI * (size.min_element() / 2.0).powi(2),
+            BodyShape::Triangle => size.x * size.y / 2.0,
+            BodyShape::Capsule => {
+                let (radius, half_length) = capsule_dimensions(size);
+                PI * radius * radius + 4.0 * half_length * radius
+            }
+        }
+    }
+
+    /// Counter-clockwise outline around the centre of the box
+    pub fn outline(self, size: Vec2) -> Vec<Vec2> {
+        let half = size / 2.0;
+        match self {
+            BodyShape::Rectangle => vec![
+                Vec2::new(-half.x, -half.y),
+                Vec2::new(half.x, -half.y),
+                Vec2::new(half.x, half.y),
+                Vec2::new(-half.x, half.y),
+            ],
+            BodyShape::Circle => (0..CIRCLE_SEGMENTS)
+                .map(|i| Vec2::from_angle(TAU * i as f32 / CIRCLE_SEGMENTS as f32))
+                .map(|direction| direction * half.min_element())
+                .collect(),
+            BodyShape::Triangle => vec![
+                Vec2::new(-half.x, -half.y),
+                Vec2::new(half.x, -half.y),
+                Vec2::new(-half.x, half.y),
+            ],
+            BodyShape::Capsule => {
+                let (radius, half_length) = capsule_dimensions(size);
+                let axis = capsule_axis(size);
+                let segments = CIRCLE_SEGMENTS / 2;
+                // Each end is a half circle, starting a quarter turn before the axis
+                let start_angle = axis.to_angle() - FRAC_PI_2;
+                let mut points = Vec::with_capacity(2 * (segments + 1));
+                for (end, turn) in [(axis * half_length, 0.0), (-axis * half_length, PI)] {
+                    for i in 0..=segments {
+                        let angle = start_angle + turn + PI * i as f32 / segments as f32;
+                        points.push(end + Vec2::from_angle(angle) * radius);
+                    }
+                }
+                // The ends of a capsule as wide as it is long meet in the middle
+                points.dedup_by(|a, b| a.distance(*b) < 0.01);
+                if points.len() > 1 && points[0].distance(points[points.len() - 1]) < 0.01 {
+                    points.pop();
+                }
+                points
+            }
+        }
+    }
+
+    /// Whether `point`, relative to the centre of the box, is inside the shape
+    pub fn contains(self, size: Vec2, point: Vec2) -> bool {
+        let half = size / 2.0;
+        match self {
+            BodyShape::Rectangle => point.abs().cmple(half).all(),
+            BodyShape::Circle => point.length() <= half.min_element(),
+            BodyShape::Triangle => {
+                let corner = (point + half) / size.max(Vec2::splat(f32::EPSILON));
+                corner.x >= 0.0 && corner.y >= 0.0 && corner.x + corner.y <= 1.0
+            }
+            BodyShape::Capsule => {
+                let (radius, half_length) = capsule_dimensions(size);
+                let axis = capsule_axis(size);
+                let along = point.dot(axis).clamp(-half_length, half_length);
+                point.distance(axis * along) <= radius
+            }
+        }
+    }
+}
+
+/// Radius of the rounded ends, and half the length of the straight part between them
+fn capsule_dimensions(size: Vec2) -> (f32, f32) {
+    let radius = size.min_element() / 2.0;
+    (radius, size.max_element() / 2.0 - radius)
+}
+
+/// Direction of the longer side
+fn capsule_axis(size: Vec2) -> Vec2 {
+    if size.x >= size.y {
+        Vec2::X
+    } else {
+        Vec2::Y
+    }
+}
+
+/// A body in a shape other than a rectangle, which has no sprite to hold its
+/// size and colour
+#[derive(Component, Clone, Copy, Debug)]
+pub struct ShapedBody {
+    pub shape: BodyShape,
+    pub size: Vec2,
+    pub color: Color,
+}
+
+/// Draw a body in `shape`: with a sprite if it is a rectangle, and otherwise
+/// with the mesh `attach_shape_meshes` gives it
+pub fn insert_shape_visual(
+    entity_commands: &mut EntityCommands,
+    shape: BodyShape,
+    size: Vec2,
+    color: Color,
+) {
+    if shape == BodyShape::Rectangle {
+        entity_commands.insert(Sprite {
+            color,
+            custom_size: Some(size),
+            ..default()
+        });
+    } else {
+        entity_commands.insert((ShapedBody { shape, size, color }, Visibility::default()));
+    }
+}
+
+/// Give newly spawned shaped bodies a filled mesh of their outline
+#[cfg(feature = "render")]
+pub fn attach_shape_meshes(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    query: Query<(Entity, &ShapedBody), Added<ShapedBody>>,
+) {
+    use crate::entities::obstacles::polygon::outline_mesh;
+
+    for (entity, shaped) in query.iter() {
+        let mesh = outline_mesh(&shaped.shape.outline(shaped.size));
+        commands.entity(entity).insert((
+            Mesh2d(meshes.add(mesh)),
+            MeshMaterial2d(materials.add(ColorMaterial::from_color(shaped.color))),
+        ));
+    }
+}
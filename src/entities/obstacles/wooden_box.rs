@@ -2,58 +2,83 @@ use bevy::prelude::*;
 use bevy_rapier2d::prelude::*;
 
 use crate::core::components::{Flammable, Health};
-use crate::core::constants::WOODEN_BOX_SPAWN_KEY;
-use crate::core::utils::get_cursor_world_position;
+use crate::entities::obstacles::shape::{insert_shape_visual, BodyShape};
+use crate::entities::spawnable::{SpawnCategory, SpawnShape, Spawnable};
 use crate::systems::damage::connection::Connectable;
+use crate::systems::input::action_map::InputBinding;
 use crate::systems::input::drag::Draggable;
 
 #[derive(Component)]
 pub struct WoodenBox;
 
-pub fn spawn_wooden_box_on_keypress(
-    mut commands: Commands,
-    keyboard: Res<ButtonInput<KeyCode>>,
-    windows: Query<&Window>,
-    camera_q: Query<(&Camera, &GlobalTransform)>,
-) {
-    if keyboard.just_pressed(WOODEN_BOX_SPAWN_KEY) {
-        if let Some(world_pos) = get_cursor_world_position(&windows, &camera_q) {
-            spawn_wooden_box(&mut commands, world_pos);
-        }
+pub fn wooden_box_spawnable() -> Spawnable {
+    Spawnable {
+        id: "wooden_box",
+        name: "Box",
+        color: WOOD_COLOR,
+        category: SpawnCategory::Obstacles,
+        shape: SpawnShape::Shaped {
+            spawn: |commands, position, size, shape| {
+                vec![spawn_wooden_body(commands, position, size, shape)]
+            },
+            default_size: Vec2::new(60.0, 60.0),
+        },
+        hotkey: Some(InputBinding::key(KeyCode::KeyW)),
     }
 }
 
-pub fn spawn_wooden_box_from_ui(commands: &mut Commands, position: Vec2) {
-    spawn_wooden_box(commands, position);
+pub fn spawn_wooden_box_from_ui(commands: &mut Commands, position: Vec2) -> Entity {
+    spawn_wooden_box(commands, position)
 }
 
-fn spawn_wooden_box(commands: &mut Commands, position: Vec2) {
-    let width = 60.0;
-    let height = 60.0;
+fn spawn_wooden_box(commands: &mut Commands, position: Vec2) -> Entity {
+    spawn_wooden_box_with_size(commands, position, Vec2::new(60.0, 60.0))
+}
 
-    commands.spawn((
-        Sprite {
-            color: Color::srgb(0.6, 0.4, 0.2),
-            custom_size: Some(Vec2::new(width, height)),
-            ..default()
-        },
-        Transform::from_xyz(position.x, position.y, 0.0),
+pub const WOOD_COLOR: Color = Color::srgb(0.6, 0.4, 0.2);
+
+/// Physics and damage components of a wooden body of the given area, whatever its shape
+pub fn wooden_body(area: f32) -> impl Bundle {
+    // Health scales with area so that a default 60x60 box has 100 HP
+    let health = 100.0 * area / (60.0 * 60.0);
+
+    (
         RigidBody::Dynamic,
-        Collider::cuboid(width / 2.0, height / 2.0),
         ColliderMassProperties::Density(0.8),
+        ReadMassProperties::default(),
         Restitution::coefficient(0.3),
         Friction::coefficient(0.7),
-        ExternalImpulse::default(),
-        Velocity::zero(),  // Initialize with zero velocity for stable physics
         WoodenBox,
-        Draggable,
-        Connectable,
         Health {
-            current: 100.0,
-            max: 100.0,
+            current: health,
+            max: health,
         },
         Flammable {
             ignition_threshold: 0.5,
         },
+    )
+}
+
+pub fn spawn_wooden_box_with_size(commands: &mut Commands, position: Vec2, size: Vec2) -> Entity {
+    spawn_wooden_body(commands, position, size, BodyShape::Rectangle)
+}
+
+/// Spawn a wooden body in `shape`, filling the box of `size` around `position`
+pub fn spawn_wooden_body(
+    commands: &mut Commands,
+    position: Vec2,
+    size: Vec2,
+    shape: BodyShape,
+) -> Entity {
+    let mut entity_commands = commands.spawn((
+        Transform::from_xyz(position.x, position.y, 0.0),
+        shape.collider(size),
+        wooden_body(shape.area(size)),
+        ExternalImpulse::default(),
+        Velocity::zero(),  // Initialize with zero velocity for stable physics
+        Draggable,
+        Connectable,
     ));
+    insert_shape_visual(&mut entity_commands, shape, size, WOOD_COLOR);
+    entity_commands.id()
 }
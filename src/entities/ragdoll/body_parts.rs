@@ -42,6 +42,7 @@ pub fn spawn_body_part(commands: &mut Commands, config: BodyPartConfig) -> Entit
             RigidBody::Dynamic,
             Collider::cuboid(config.size.x / 2.0, config.size.y / 2.0),
             ColliderMassProperties::Density(config.density),
+            ReadMassProperties::default(),
             Damping {
                 linear_damping: config.linear_damping,
                 angular_damping: config.angular_damping,
@@ -3,4 +3,4 @@
 pub mod body_parts;
 pub mod ragdoll;
 
-pub use ragdoll::{spawn_ragdoll_on_keypress, spawn_ragdoll_from_ui};
+pub use ragdoll::{ragdoll_spawnable, spawn_ragdoll_from_ui};
@@ -1,28 +1,27 @@
 use bevy::prelude::*;
 use bevy_rapier2d::prelude::*;
 
-use crate::core::constants::RAGDOLL_SPAWN_KEY;
-use crate::core::utils::get_cursor_world_position;
 use crate::entities::ragdoll::body_parts::{create_joint, spawn_body_part, BodyPartConfig, JointConfig};
+use crate::entities::spawnable::{SpawnCategory, SpawnShape, Spawnable};
+use crate::systems::input::action_map::InputBinding;
 
-pub fn spawn_ragdoll_on_keypress(
-    mut commands: Commands,
-    keyboard: Res<ButtonInput<KeyCode>>,
-    windows: Query<&Window>,
-    camera_q: Query<(&Camera, &GlobalTransform)>,
-) {
-    if keyboard.just_pressed(RAGDOLL_SPAWN_KEY) {
-        if let Some(world_pos) = get_cursor_world_position(&windows, &camera_q) {
-            spawn_ragdoll(&mut commands, world_pos);
-        }
+pub fn ragdoll_spawnable() -> Spawnable {
+    Spawnable {
+        id: "ragdoll",
+        name: "Ragdoll",
+        color: Color::srgb(0.9, 0.7, 0.6),
+        category: SpawnCategory::Characters,
+        shape: SpawnShape::Point(spawn_ragdoll),
+        hotkey: Some(InputBinding::key(KeyCode::KeyR)),
     }
 }
 
-pub fn spawn_ragdoll_from_ui(commands: &mut Commands, position: Vec2) {
-    spawn_ragdoll(commands, position);
+pub fn spawn_ragdoll_from_ui(commands: &mut Commands, position: Vec2) -> Vec<Entity> {
+    spawn_ragdoll(commands, position)
 }
 
-fn spawn_ragdoll(commands: &mut Commands, position: Vec2) {
+/// Spawn a full ragdoll, returning all of its body parts
+fn spawn_ragdoll(commands: &mut Commands, position: Vec2) -> Vec<Entity> {
     let skin_color = Color::srgb(0.9, 0.7, 0.6);
     let shirt_color = Color::srgb(0.4, 0.6, 0.8);
     let pants_color = Color::srgb(0.3, 0.5, 0.7);
@@ -273,4 +272,17 @@ fn spawn_ragdoll(commands: &mut Commands, position: Vec2) {
             max_angle: 0.0,
         },
     );
+
+    vec![
+        head,
+        torso,
+        left_upper_arm,
+        left_lower_arm,
+        right_upper_arm,
+        right_lower_arm,
+        left_upper_leg,
+        left_lower_leg,
+        right_upper_leg,
+        right_lower_leg,
+    ]
 }
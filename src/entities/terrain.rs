@@ -0,0 +1,78 @@
+//! Static terrain: floors, walls, platforms, ramps and ceilings
+//!
+//! Every piece of terrain is a fixed box with a size and a rotation, so a ramp
+//! is a platform turned to a slope and a pit is a gap between two floors.
+
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+use serde::{Deserialize, Serialize};
+
+const TERRAIN_COLOR: Color = Color::srgb(0.3, 0.3, 0.3);
+
+/// Marks a piece of static terrain, and holds its size
+#[derive(Component, Clone, Copy, Debug)]
+pub struct Terrain {
+    pub size: Vec2,
+}
+
+/// One piece of terrain, as stored in level files
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+pub struct TerrainPiece {
+    pub position: Vec2,
+    pub size: Vec2,
+    /// Counter-clockwise, in radians
+    pub rotation: f32,
+}
+
+impl TerrainPiece {
+    pub fn new(position: Vec2, size: Vec2) -> Self {
+        Self {
+            position,
+            size,
+            rotation: 0.0,
+        }
+    }
+
+    pub fn from_transform(transform: &Transform, terrain: &Terrain) -> Self {
+        Self {
+            position: transform.translation.truncate(),
+            size: terrain.size,
+            rotation: transform.rotation.to_euler(EulerRot::XYZ).2,
+        }
+    }
+
+    pub fn transform(&self) -> Transform {
+        Transform::from_xyz(self.position.x, self.position.y, 0.0)
+            .with_rotation(Quat::from_rotation_z(self.rotation))
+    }
+
+    /// `point` relative to the centre of the piece, in its unrotated frame
+    pub fn to_local(&self, point: Vec2) -> Vec2 {
+        Vec2::from_angle(-self.rotation).rotate(point - self.position)
+    }
+
+    pub fn to_world(&self, local: Vec2) -> Vec2 {
+        self.position + Vec2::from_angle(self.rotation).rotate(local)
+    }
+
+    pub fn contains(&self, point: Vec2) -> bool {
+        let local = self.to_local(point);
+        local.abs().cmple(self.size / 2.0).all()
+    }
+}
+
+pub fn spawn_terrain(commands: &mut Commands, piece: &TerrainPiece) -> Entity {
+    commands
+        .spawn((
+            Sprite {
+                color: TERRAIN_COLOR,
+                custom_size: Some(piece.size),
+                ..default()
+            },
+            piece.transform(),
+            RigidBody::Fixed,
+            Collider::cuboid(piece.size.x / 2.0, piece.size.y / 2.0),
+            Terrain { size: piece.size },
+        ))
+        .id()
+}
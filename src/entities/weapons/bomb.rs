@@ -2,57 +2,68 @@ use bevy::prelude::*;
 use bevy_rapier2d::prelude::*;
 
 use crate::core::components::Bomb;
-use crate::core::constants::{BOMB_SPAWN_KEY, EXPLOSION_RADIUS};
-use crate::core::utils::get_cursor_world_position;
+use crate::core::events::Exploded;
+use crate::core::rng::{RngStream, SimRng};
+use crate::core::tuning::ExplosionTuning;
 use crate::entities::weapons::explosion::{spawn_explosion_debris, spawn_smoke_particles};
 use crate::entities::weapons::shockwave::{spawn_shockwave, spawn_shockwave_visuals};
+use crate::entities::spawnable::{SpawnCategory, SpawnShape, Spawnable};
 use crate::systems::damage::connection::Connectable;
+use crate::systems::input::action_map::InputBinding;
 use crate::systems::input::drag::Draggable;
 
-pub fn spawn_bomb_on_keypress(
-    mut commands: Commands,
-    keyboard: Res<ButtonInput<KeyCode>>,
-    windows: Query<&Window>,
-    camera_q: Query<(&Camera, &GlobalTransform)>,
-) {
-    if keyboard.just_pressed(BOMB_SPAWN_KEY) {
-        if let Some(world_pos) = get_cursor_world_position(&windows, &camera_q) {
-            spawn_bomb(&mut commands, world_pos);
-        }
+pub fn bomb_spawnable() -> Spawnable {
+    Spawnable {
+        id: "bomb",
+        name: "Bomb",
+        color: Color::srgb(0.8, 0.2, 0.1),
+        category: SpawnCategory::Explosives,
+        shape: SpawnShape::Point(|commands, position| vec![spawn_bomb(commands, position)]),
+        hotkey: Some(InputBinding::key(KeyCode::KeyB)),
     }
 }
 
-pub fn spawn_bomb_from_ui(commands: &mut Commands, position: Vec2) {
-    spawn_bomb(commands, position);
+pub fn spawn_bomb_from_ui(commands: &mut Commands, position: Vec2) -> Entity {
+    spawn_bomb(commands, position)
+}
+
+fn spawn_bomb(commands: &mut Commands, position: Vec2) -> Entity {
+    spawn_bomb_with_fuse(commands, position, 2.0)
 }
 
-fn spawn_bomb(commands: &mut Commands, position: Vec2) {
+pub fn spawn_bomb_with_fuse(commands: &mut Commands, position: Vec2, fuse_secs: f32) -> Entity {
     let radius = 15.0;
 
-    commands.spawn((
-        Sprite {
-            color: Color::srgb(0.1, 0.1, 0.1),
-            custom_size: Some(Vec2::new(radius * 2.0, radius * 2.0)),
-            ..default()
-        },
-        Transform::from_xyz(position.x, position.y, 0.0),
-        RigidBody::Dynamic,
-        Collider::ball(radius),
-        ColliderMassProperties::Density(2.0),
-        Restitution::coefficient(0.5),
-        Velocity::zero(),  // Initialize with zero velocity for stable physics
-        ExternalImpulse::default(),
-        Bomb {
-            timer: Timer::from_seconds(2.0, TimerMode::Once),
-        },
-        Draggable,
-        Connectable,
-    ));
+    commands
+        .spawn((
+            Sprite {
+                color: Color::srgb(0.1, 0.1, 0.1),
+                custom_size: Some(Vec2::new(radius * 2.0, radius * 2.0)),
+                ..default()
+            },
+            Transform::from_xyz(position.x, position.y, 0.0),
+            RigidBody::Dynamic,
+            Collider::ball(radius),
+            ColliderMassProperties::Density(2.0),
+            ReadMassProperties::default(),
+            Restitution::coefficient(0.5),
+            Velocity::zero(),  // Initialize with zero velocity for stable physics
+            ExternalImpulse::default(),
+            Bomb {
+                timer: Timer::from_seconds(fuse_secs, TimerMode::Once),
+            },
+            Draggable,
+            Connectable,
+        ))
+        .id()
 }
 
 pub fn bomb_timer_system(
     mut commands: Commands,
+    mut exploded: EventWriter<Exploded>,
     time: Res<Time>,
+    mut sim_rng: ResMut<SimRng>,
+    tuning: Res<ExplosionTuning>,
     mut query: Query<(Entity, &mut Bomb, &Transform)>,
 ) {
     for (entity, mut bomb, transform) in query.iter_mut() {
@@ -63,11 +74,15 @@ pub fn bomb_timer_system(
 
             commands.entity(entity).despawn();
 
-            let peak_pressure = 80000.0;
-            spawn_shockwave(&mut commands, position, EXPLOSION_RADIUS, peak_pressure);
+            spawn_shockwave(&mut commands, position, tuning.radius, tuning.peak_pressure);
+            exploded.send(Exploded {
+                position,
+                radius: tuning.radius,
+                pressure: tuning.peak_pressure,
+            });
             
-            spawn_shockwave_visuals(&mut commands, position, EXPLOSION_RADIUS);
-            spawn_explosion_debris(&mut commands, position);
+            spawn_shockwave_visuals(&mut commands, position, tuning.radius);
+            spawn_explosion_debris(&mut commands, sim_rng.stream(RngStream::Bomb), position);
             spawn_smoke_particles(&mut commands, position);
         }
     }
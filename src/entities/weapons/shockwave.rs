@@ -1,17 +1,25 @@
 use bevy::prelude::*;
 use bevy_rapier2d::prelude::*;
+use rand::Rng;
 
 use crate::core::components::{Health, RagdollPart, ShockwaveRing};
+use crate::core::events::{BodyDestroyed, DamageCause, JointBroken};
+use crate::core::rng::{RngStream, SimRng};
 use crate::entities::obstacles::iron_block::IronBlock;
+use crate::entities::obstacles::polygon::PolygonBody;
+use crate::entities::obstacles::shape::ShapedBody;
 use crate::entities::obstacles::wooden_box::WoodenBox;
 use crate::entities::weapons::explosion::spawn_object_fragments;
-use crate::systems::damage::damage::{Fractured, JointHealth};
+use crate::systems::damage::damage::{break_joint, Fractured, JointHealth};
 use crate::systems::effects::blood::spawn_blood_particles;
+use crate::systems::physics::physics_utils::fragment_source;
+use crate::systems::physics::spatial_index::SpatialIndex;
 
 pub fn spawn_shockwave(commands: &mut Commands, position: Vec2, max_radius: f32, peak_pressure: f32) {
     commands.spawn(ShockwaveRing {
         origin: position,
         current_radius: 0.0,
+        previous_radius: 0.0,
         max_radius,
         peak_pressure,
         wave_speed: 1200.0,
@@ -22,7 +30,10 @@ pub fn spawn_shockwave(commands: &mut Commands, position: Vec2, max_radius: f32,
 
 pub fn update_shockwave(
     mut commands: Commands,
+    mut destroyed: EventWriter<BodyDestroyed>,
     time: Res<Time>,
+    mut sim_rng: ResMut<SimRng>,
+    index: Res<SpatialIndex>,
     rapier_context: Query<&RapierContext>,
     mut shockwave_query: Query<(Entity, &mut ShockwaveRing)>,
     mut physics_query: Query<(
@@ -35,6 +46,8 @@ pub fn update_shockwave(
         Option<&IronBlock>,
         Option<&ReadMassProperties>,
         Option<&Sprite>,
+        Option<&PolygonBody>,
+        Option<&ShapedBody>,
         Option<&Velocity>,
     ), With<RigidBody>>,
     blocking_query: Query<(), Or<(With<IronBlock>, With<WoodenBox>)>>,
@@ -42,12 +55,14 @@ pub fn update_shockwave(
     let Ok(context) = rapier_context.get_single() else {
         return;
     };
+    let rng = sim_rng.stream(RngStream::Shockwave);
     
     for (shockwave_entity, mut shockwave) in shockwave_query.iter_mut() {
         shockwave.lifetime.tick(time.delta());
         
         let old_radius = shockwave.current_radius;
         shockwave.current_radius += shockwave.wave_speed * time.delta_secs();
+        shockwave.previous_radius = old_radius;
         
         let wave_decay = 1.0 - (shockwave.current_radius / shockwave.max_radius).powf(1.5);
         
@@ -56,7 +71,11 @@ pub fn update_shockwave(
             continue;
         }
         
-        for (entity, transform, mut impulse, health_opt, ragdoll_opt, wooden_box_opt, iron_block_opt, mass_props_opt, sprite_opt, velocity_opt) in physics_query.iter_mut() {
+        let outer_radius = shockwave.current_radius + shockwave.wave_thickness;
+        for (candidate, _) in index.candidates_in_ring(shockwave.origin, old_radius, outer_radius) {
+            let Ok((entity, transform, mut impulse, health_opt, ragdoll_opt, wooden_box_opt, iron_block_opt, mass_props_opt, sprite_opt, polygon_opt, shaped_opt, velocity_opt)) = physics_query.get_mut(candidate) else {
+                continue;
+            };
             let pos = transform.translation.truncate();
             let distance = pos.distance(shockwave.origin);
             
@@ -117,6 +136,10 @@ pub fn update_shockwave(
                     } else {
                         transform.scale.x.max(transform.scale.y).max(20.0)
                     }
+                } else if let Some(polygon) = polygon_opt {
+                    polygon.extent().max_element()
+                } else if let Some(shaped) = shaped_opt {
+                    shaped.size.max_element()
                 } else {
                     transform.scale.x.max(transform.scale.y).max(20.0)
                 };
@@ -128,7 +151,7 @@ pub fn update_shockwave(
                 impulse.impulse += impulse_vec;
                 
                 let torque_factor = (distance / shockwave.max_radius).clamp(0.0, 1.0);
-                let random_torque = (rand::random::<f32>() - 0.5) * 2.0;
+                let random_torque = (rng.random::<f32>() - 0.5) * 2.0;
                 let torque = random_torque * impulse_magnitude * 0.1 * (1.0 - torque_factor);
                 impulse.torque_impulse += torque;
                 
@@ -141,22 +164,22 @@ pub fn update_shockwave(
                         let velocity_factor = (impulse_magnitude / mass).min(1000.0) / 1000.0;
                         let damage = base_damage * (1.0 + velocity_factor * 2.0);
                         
+                        let was_alive = health.current > 0.0;
                         health.current -= damage;
                         
-                        if health.current <= 0.0 {
+                        // Only the blow that takes health to zero destroys the body
+                        if was_alive && health.current <= 0.0 {
                             let current_velocity = velocity_opt
                                 .map(|v| v.linvel)
                                 .unwrap_or(direction * (pressure * 0.3).min(500.0));
                             
-                            if let Some(sprite) = sprite_opt {
-                                let size = sprite.custom_size.unwrap_or(Vec2::new(20.0, 20.0));
-                                let color = sprite.color;
-                                
+                            if let Some(source) =
+                                fragment_source(transform, sprite_opt, polygon_opt, shaped_opt)
+                            {
                                 spawn_object_fragments(
                                     &mut commands,
-                                    pos,
-                                    size,
-                                    color,
+                                    rng,
+                                    &source,
                                     current_velocity,
                                     wooden_box_opt.is_some() || iron_block_opt.is_some(),
                                 );
@@ -168,6 +191,11 @@ pub fn update_shockwave(
                             }
                             
                             commands.entity(entity).despawn();
+                            destroyed.send(BodyDestroyed {
+                                entity,
+                                position: pos,
+                                cause: DamageCause::Shockwave,
+                            });
                         }
                     }
                 }
@@ -304,11 +332,18 @@ pub fn animate_explosion_core(
 
 pub fn shockwave_joint_damage(
     mut commands: Commands,
+    mut joint_broken: EventWriter<JointBroken>,
+    index: Res<SpatialIndex>,
     shockwave_query: Query<&ShockwaveRing>,
     mut joint_query: Query<(Entity, &mut JointHealth, &Transform), With<RagdollPart>>,
 ) {
     for shockwave in shockwave_query.iter() {
-        for (entity, mut joint_health, transform) in joint_query.iter_mut() {
+        let inner_radius = shockwave.current_radius - shockwave.wave_thickness;
+        let outer_radius = shockwave.current_radius + shockwave.wave_thickness;
+        for (candidate, _) in index.candidates_in_ring(shockwave.origin, inner_radius, outer_radius) {
+            let Ok((entity, mut joint_health, transform)) = joint_query.get_mut(candidate) else {
+                continue;
+            };
             let pos = transform.translation.truncate();
             let distance = pos.distance(shockwave.origin);
             
@@ -324,12 +359,21 @@ pub fn shockwave_joint_damage(
                 let pressure = shockwave.peak_pressure * distance_factor;
                 let joint_damage = pressure * 0.004;
                 
+                let was_intact = joint_health.current > 0.0;
                 joint_health.current -= joint_damage;
                 
                 if joint_health.current <= 0.0 {
-                    spawn_blood_particles(&mut commands, pos, Vec2::ZERO);
-                    commands.entity(entity).remove::<ImpulseJoint>();
-                    commands.entity(entity).remove::<JointHealth>();
+                    if was_intact {
+                        spawn_blood_particles(&mut commands, pos, Vec2::ZERO);
+                        break_joint(
+                            &mut commands,
+                            &mut joint_broken,
+                            entity,
+                            &joint_health,
+                            pos,
+                            DamageCause::Shockwave,
+                        );
+                    }
                 } else if joint_health.current < joint_health.max * 0.5 {
                     if commands.get_entity(entity).is_some() {
                         commands.entity(entity).insert(Fractured {
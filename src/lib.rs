@@ -0,0 +1,13 @@
+//! Bevy 2D Ragdoll Sandbox - A physics simulation game with ragdolls and explosions
+//!
+//! The gameplay lives in [`SandboxPlugins`], which can be added to a windowed app
+//! or driven headlessly under `MinimalPlugins` from tests and tools.
+
+pub mod core;
+pub mod entities;
+pub mod plugins;
+pub mod prelude;
+pub mod systems;
+pub mod ui;
+
+pub use plugins::SandboxPlugins;
@@ -3,17 +3,25 @@
 use bevy::prelude::*;
 use bevy_rapier2d::prelude::*;
 
-mod core;
-mod entities;
-mod plugins;
-mod prelude;
-mod systems;
-mod ui;
+use bevy_sandbox_ragdoll::systems::persistence::CurrentLevel;
+use bevy_sandbox_ragdoll::SandboxPlugins;
 
-use plugins::{DamagePlugin, EffectsPlugin, EntitiesPlugin, InputPlugin, UiPlugin};
+/// The level named by a `--level <name>` argument, if any
+fn startup_level() -> Option<String> {
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == "--level" {
+            return args.next();
+        }
+    }
+    None
+}
 
 fn main() {
     App::new()
+        .insert_resource(CurrentLevel {
+            name: startup_level(),
+        })
         .add_plugins(
             DefaultPlugins.set(WindowPlugin {
                 primary_window: Some(Window {
@@ -25,13 +33,8 @@ fn main() {
             }),
         )
         .add_plugins(RapierPhysicsPlugin::<NoUserData>::pixels_per_meter(100.0))
-        .add_plugins(RapierDebugRenderPlugin::default())
-        .add_plugins((
-            UiPlugin,
-            EntitiesPlugin,
-            EffectsPlugin,
-            InputPlugin,
-            DamagePlugin,
-        ))
+        // Switched on from the debug menu
+        .add_plugins(RapierDebugRenderPlugin::default().disabled())
+        .add_plugins(SandboxPlugins::windowed())
         .run();
 }
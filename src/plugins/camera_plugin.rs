@@ -0,0 +1,32 @@
+//! Plugin for moving the view around the sandbox
+
+use bevy::prelude::*;
+use bevy::transform::TransformSystem;
+use bevy_rapier2d::prelude::*;
+use crate::systems::camera::{
+    follow_camera_target, pan_camera, reset_camera, toggle_camera_follow, zoom_camera,
+    CameraControl,
+};
+use crate::systems::input::is_rebinding;
+
+/// Zoom, pan and follow controls for the `SandboxCamera` spawned by the UI setup.
+/// The camera can still be moved while a replay is playing.
+pub struct CameraControlPlugin;
+
+impl Plugin for CameraControlPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<CameraControl>()
+            .add_systems(
+                Update,
+                (zoom_camera, pan_camera, toggle_camera_follow, reset_camera)
+                    .run_if(not(is_rebinding)),
+            )
+            // Track the body where physics left it this frame, before transforms propagate
+            .add_systems(
+                PostUpdate,
+                follow_camera_target
+                    .after(PhysicsSet::Writeback)
+                    .before(TransformSystem::TransformPropagate),
+            );
+    }
+}
@@ -1,20 +1,25 @@
 //! Plugin for damage and connection systems
 
 use bevy::prelude::*;
+use crate::core::events::{ConnectionBroken, JointBroken};
 use crate::systems::damage::{
-    apply_explosive_joint_damage, break_joints_on_force_limit, check_joint_damage,
-    collision_joint_damage, detect_impact_damage, end_drag_connection,
-    handle_despawned_connected_entities, start_drag_connection, track_velocity,
-    update_connection_visuals, update_drag_connection, update_hover_indicator,
-    update_hover_indicator_position, visualize_fractures, DragConnectionState, SelectionState,
+    apply_connection_tuning, apply_explosive_joint_damage, break_joints_on_force_limit, check_joint_damage,
+    collision_joint_damage, detect_impact_damage, handle_despawned_connected_entities,
+    measure_connection_forces, track_velocity, update_hover_indicator_position,
+    visualize_fractures,
 };
+#[cfg(feature = "render")]
+use crate::systems::damage::update_connection_visuals;
+use crate::systems::effects::EmitParticles;
 
 pub struct DamagePlugin;
 
 impl Plugin for DamagePlugin {
     fn build(&self, app: &mut App) {
-        app.init_resource::<SelectionState>()
-            .init_resource::<DragConnectionState>()
+        app.add_event::<JointBroken>()
+            .add_event::<ConnectionBroken>()
+            // Blood is emitted as particle bursts, shown if `EffectsPlugin` is present
+            .add_event::<EmitParticles>()
             .add_systems(
                 Update,
                 (
@@ -29,16 +34,23 @@ impl Plugin for DamagePlugin {
             .add_systems(
                 Update,
                 (
-                    update_hover_indicator,
                     update_hover_indicator_position,
-                    start_drag_connection,
-                    update_drag_connection,
-                    end_drag_connection,
+                    apply_connection_tuning,
+                    measure_connection_forces,
                     break_joints_on_force_limit,
-                    update_connection_visuals,
                     handle_despawned_connected_entities,
                 )
                     .chain(),
             );
+
+        // Headless apps have no gizmos to draw the lines with, even with `render` on
+        #[cfg(feature = "render")]
+        app.add_systems(
+            Update,
+            update_connection_visuals
+                .after(break_joints_on_force_limit)
+                .before(handle_despawned_connected_entities)
+                .run_if(resource_exists::<GizmoConfigStore>),
+        );
     }
 }
@@ -0,0 +1,28 @@
+//! Plugin for the physics debug overlays
+
+use bevy::prelude::*;
+use crate::systems::debug::{
+    debug_overlay_hotkeys, draw_joint_health_bars, draw_shockwave_rings, draw_sleeping_bodies,
+    draw_velocity_vectors, is_layer_enabled, sync_rapier_debug_render, DebugLayer, DebugOverlay,
+};
+use crate::systems::input::is_rebinding;
+
+/// Overlays toggled from the debug menu. Collider, joint and contact layers
+/// steer `RapierDebugRenderPlugin`, which should be added disabled alongside.
+pub struct DebugOverlayPlugin;
+
+impl Plugin for DebugOverlayPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<DebugOverlay>().add_systems(
+            Update,
+            (
+                debug_overlay_hotkeys.run_if(not(is_rebinding)),
+                sync_rapier_debug_render,
+                draw_velocity_vectors.run_if(is_layer_enabled(DebugLayer::Velocities)),
+                draw_sleeping_bodies.run_if(is_layer_enabled(DebugLayer::Sleeping)),
+                draw_joint_health_bars.run_if(is_layer_enabled(DebugLayer::JointHealth)),
+                draw_shockwave_rings.run_if(is_layer_enabled(DebugLayer::Shockwaves)),
+            ),
+        );
+    }
+}
@@ -1,33 +1,38 @@
 //! Plugin for visual effects systems
 
 use bevy::prelude::*;
+use crate::core::events::{BodyDestroyed, Ignited};
+use crate::core::rng::SimRng;
+use crate::core::tuning::ParticleTuning;
+use crate::plugins::spatial_index_plugin::add_spatial_index;
 use crate::systems::effects::{
-    animate_blood_particles, animate_explosion_flash, animate_explosion_shockwave,
-    animate_fire_particles, animate_smoke_particles, apply_fire_damage,
-    ignite_ragdoll_on_keypress, spread_fire,
+    animate_explosion_flash, animate_explosion_shockwave, animate_particles, apply_fire_damage,
+    emit_particles, spread_fire, EmitParticles, ParticlePool,
 };
 
 pub struct EffectsPlugin;
 
 impl Plugin for EffectsPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(
-            Update,
-            (
-                animate_explosion_flash,
-                animate_explosion_shockwave,
-                animate_smoke_particles,
-                animate_blood_particles,
-            ),
-        )
-        .add_systems(
-            Update,
-            (
-                ignite_ragdoll_on_keypress,
-                apply_fire_damage,
-                spread_fire,
-                animate_fire_particles,
-            ),
-        );
+        // Fire spreads to the bodies the spatial index finds nearby
+        add_spatial_index(app);
+        app.init_resource::<SimRng>()
+            .add_event::<BodyDestroyed>()
+            .add_event::<Ignited>()
+            .add_event::<EmitParticles>()
+            .init_resource::<ParticlePool>()
+            // The budget lives with the tuning, but effects work without the tuning plugin
+            .init_resource::<ParticleTuning>()
+            .add_systems(
+                Update,
+                (
+                    animate_explosion_flash,
+                    animate_explosion_shockwave,
+                    animate_particles,
+                ),
+            )
+            .add_systems(Update, (apply_fire_damage, spread_fire))
+            // Bursts requested during Update are placed once their events are out
+            .add_systems(PostUpdate, emit_particles);
     }
 }
@@ -1,33 +1,70 @@
 //! Plugin for entity spawning and management systems
+//!
+//! Registers the built-in spawnables; other plugins add theirs the same way,
+//! with `App::register_spawnable`.
 
 use bevy::prelude::*;
-use crate::entities::obstacles::{spawn_iron_block_on_keypress, spawn_wooden_box_on_keypress};
-use crate::entities::ragdoll::spawn_ragdoll_on_keypress;
+use crate::core::events::{BodyDestroyed, Exploded, JointBroken};
+use crate::core::rng::{log_simulation_seed, SimRng};
+use crate::core::setup::WorldBounds;
+use crate::core::tuning::DebrisTuning;
+#[cfg(feature = "render")]
+use crate::entities::obstacles::polygon::attach_polygon_meshes;
+#[cfg(feature = "render")]
+use crate::entities::obstacles::shape::attach_shape_meshes;
+use crate::entities::obstacles::{iron_block_spawnable, wooden_box_spawnable};
+use crate::entities::ragdoll::ragdoll_spawnable;
+use crate::entities::spawnable::SpawnableAppExt;
 use crate::entities::weapons::{
-    animate_explosion_core, animate_shockwave_visual, bomb_timer_system,
-    shockwave_joint_damage, spawn_bomb_on_keypress, update_shockwave,
+    animate_explosion_core, animate_shockwave_visual, bomb_spawnable, bomb_timer_system,
+    shockwave_joint_damage, update_shockwave,
 };
-use crate::systems::physics::{apply_explosion, cleanup_debris};
+use crate::plugins::spatial_index_plugin::add_spatial_index;
+use crate::systems::effects::EmitParticles;
+use crate::systems::physics::{apply_explosion, cleanup_debris, DebrisOrder};
 
 pub struct EntitiesPlugin;
 
 impl Plugin for EntitiesPlugin {
     fn build(&self, app: &mut App) {
+        // Shockwaves look up the bodies in their ring through the spatial index
+        add_spatial_index(app);
+        app.init_resource::<SimRng>()
+            .add_event::<BodyDestroyed>()
+            .add_event::<JointBroken>()
+            .add_event::<Exploded>()
+            // Smoke and blood are emitted as particle bursts, shown if `EffectsPlugin` is present
+            .add_event::<EmitParticles>()
+            .init_resource::<WorldBounds>()
+            .init_resource::<DebrisOrder>()
+            // The debris policy lives with the tuning, but works without the tuning plugin
+            .init_resource::<DebrisTuning>()
+            .register_spawnable(ragdoll_spawnable())
+            .register_spawnable(bomb_spawnable())
+            .register_spawnable(wooden_box_spawnable())
+            .register_spawnable(iron_block_spawnable())
+            .add_systems(Startup, log_simulation_seed)
+            .add_systems(
+                Update,
+                (
+                    bomb_timer_system,
+                    apply_explosion,
+                    update_shockwave,
+                    shockwave_joint_damage,
+                    cleanup_debris,
+                    animate_shockwave_visual,
+                    animate_explosion_core,
+                ),
+            );
+
+        // Polygons and shapes other than rectangles have no sprite, so they are
+        // drawn with a mesh of their outline, in apps that render meshes
+        #[cfg(feature = "render")]
         app.add_systems(
             Update,
-            (
-                spawn_ragdoll_on_keypress,
-                spawn_bomb_on_keypress,
-                spawn_wooden_box_on_keypress,
-                spawn_iron_block_on_keypress,
-                bomb_timer_system,
-                apply_explosion,
-                update_shockwave,
-                shockwave_joint_damage,
-                cleanup_debris,
-                animate_shockwave_visual,
-                animate_explosion_core,
-            ),
+            (attach_polygon_meshes, attach_shape_meshes)
+                .run_if(resource_exists::<Assets<Mesh>>)
+                .run_if(resource_exists::<Assets<ColorMaterial>>),
         );
     }
 }
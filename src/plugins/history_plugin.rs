@@ -0,0 +1,21 @@
+//! Plugin for undoing and redoing editing actions
+
+use bevy::prelude::*;
+use crate::systems::history::{
+    apply_undo_redo, delete_bodies, DeleteBodiesEvent, EditHistory, RedoEvent, UndoEvent,
+};
+
+pub struct HistoryPlugin;
+
+impl Plugin for HistoryPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<EditHistory>()
+            .add_event::<UndoEvent>()
+            .add_event::<RedoEvent>()
+            .add_event::<DeleteBodiesEvent>()
+            .add_systems(
+                Update,
+                (apply_undo_redo, delete_bodies).chain(),
+            );
+    }
+}
@@ -1,17 +1,73 @@
 //! Plugin for input handling systems
+//!
+//! Everything that reads the mouse or keyboard is registered here, so the rest
+//! of the sandbox can run without a window. Requires `ReplayPlugin` and
+//! `HistoryPlugin`: the input systems report what the user did to both, and
+//! stand down during playback. Controls are looked up in the `ActionMap`, and
+//! gameplay input is suspended while the bindings screen is open. While the
+//! level editor is on, the pointer edits terrain and the body tools rest.
 
 use bevy::prelude::*;
+use crate::core::setup::WorldBounds;
+use crate::entities::spawnable::{spawn_on_hotkey, SpawnableRegistry};
+use crate::plugins::spatial_index_plugin::add_spatial_index;
+use crate::systems::damage::{
+    end_drag_connection, start_drag_connection, update_hover_indicator, DragConnectionState,
+    SelectionState,
+};
+#[cfg(feature = "render")]
+use crate::systems::damage::update_drag_connection;
+use crate::systems::effects::ignite_ragdoll_on_keypress;
+use crate::systems::history::{delete_bodies, undo_redo_hotkeys};
 use crate::systems::input::{
-    end_create_drag_system, end_drag_system, start_create_drag_system, start_drag_system,
-    update_create_drag_system, update_drag_system, CreateDragState, DragState,
+    capture_rebinding, clear_level_editor_state, clear_polygon_draft, clipboard_hotkeys,
+    draw_polygon_outline, end_create_drag_system, end_drag_system, end_selection_drag,
+    end_terrain_drag, erase_body_on_click, is_editing_level, is_rebinding, load_action_map,
+    prune_selection, selection_hotkeys, start_create_drag_system, start_drag_system,
+    start_selection_drag, start_terrain_drag, terrain_hotkeys, toggle_bindings_screen,
+    toggle_level_editor, update_create_drag_system, update_drag_system, update_selection_drag,
+    update_selection_highlights, update_terrain_drag, ActionMap, Clipboard, CreateDragState,
+    DragState, LevelEditor, LevelEditorState, PolygonTool, RebindState, Selection,
+    SelectionDragState,
 };
+#[cfg(feature = "render")]
+use crate::systems::input::{draw_create_preview, draw_level_editor, draw_polygon_draft};
+use crate::systems::replay::{is_recording, is_replaying, replay_hotkeys};
+use crate::systems::time::time_control_hotkeys;
+use crate::ui::SelectedObject;
 
 pub struct InputPlugin;
 
 impl Plugin for InputPlugin {
     fn build(&self, app: &mut App) {
-        app.init_resource::<DragState>()
+        // Igniting at the cursor picks the nearest flammable body from the spatial index
+        add_spatial_index(app);
+        app.init_resource::<ActionMap>()
+            .init_resource::<RebindState>()
+            .init_resource::<DragState>()
             .init_resource::<CreateDragState>()
+            .init_resource::<SelectedObject>()
+            .init_resource::<SelectionState>()
+            .init_resource::<DragConnectionState>()
+            .init_resource::<Selection>()
+            .init_resource::<SelectionDragState>()
+            .init_resource::<Clipboard>()
+            .init_resource::<SpawnableRegistry>()
+            .init_resource::<LevelEditor>()
+            .init_resource::<LevelEditorState>()
+            .init_resource::<PolygonTool>()
+            .init_resource::<WorldBounds>()
+            .add_systems(Startup, load_action_map)
+            .add_systems(
+                Update,
+                (toggle_bindings_screen, capture_rebinding).chain(),
+            )
+            .add_systems(
+                Update,
+                (spawn_on_hotkey, ignite_ragdoll_on_keypress)
+                    .run_if(not(is_replaying))
+                    .run_if(not(is_rebinding)),
+            )
             .add_systems(
                 Update,
                 (
@@ -21,8 +77,102 @@ impl Plugin for InputPlugin {
                     update_create_drag_system,
                     end_drag_system,
                     end_create_drag_system,
+                )
+                    .chain()
+                    .run_if(not(is_replaying))
+                    .run_if(not(is_rebinding))
+                    .run_if(not(is_editing_level)),
+            )
+            .add_systems(
+                Update,
+                (update_hover_indicator, start_drag_connection, end_drag_connection)
+                    .chain()
+                    .run_if(not(is_replaying))
+                    .run_if(not(is_rebinding))
+                    .run_if(not(is_editing_level)),
+            )
+            .add_systems(
+                Update,
+                (
+                    clear_polygon_draft,
+                    draw_polygon_outline
+                        .run_if(not(is_replaying))
+                        .run_if(not(is_rebinding))
+                        .run_if(not(is_editing_level)),
+                )
+                    .chain(),
+            )
+            .add_systems(
+                Update,
+                erase_body_on_click
+                    .before(delete_bodies)
+                    .run_if(not(is_replaying))
+                    .run_if(not(is_rebinding))
+                    .run_if(not(is_editing_level)),
+            )
+            // Group edits and pastes are not recorded, so they rest while recording
+            .add_systems(
+                Update,
+                (
+                    prune_selection,
+                    (
+                        start_selection_drag,
+                        update_selection_drag,
+                        end_selection_drag,
+                        selection_hotkeys.before(delete_bodies),
+                        clipboard_hotkeys,
+                    )
+                        .run_if(not(is_replaying))
+                        .run_if(not(is_recording))
+                        .run_if(not(is_rebinding))
+                        .run_if(not(is_editing_level)),
+                    update_selection_highlights,
                 )
                     .chain(),
+            )
+            // Terrain edits are not recorded either, so the editor rests while recording
+            .add_systems(
+                Update,
+                (
+                    toggle_level_editor.run_if(not(is_rebinding)),
+                    clear_level_editor_state,
+                    (
+                        start_terrain_drag,
+                        update_terrain_drag,
+                        end_terrain_drag,
+                        terrain_hotkeys,
+                    )
+                        .run_if(is_editing_level)
+                        .run_if(not(is_replaying))
+                        .run_if(not(is_recording))
+                        .run_if(not(is_rebinding)),
+                )
+                    .chain(),
+            )
+            .add_systems(
+                Update,
+                (replay_hotkeys, undo_redo_hotkeys, time_control_hotkeys)
+                    .run_if(not(is_rebinding)),
             );
+
+        #[cfg(feature = "render")]
+        app.add_systems(
+            Update,
+            (
+                update_drag_connection
+                    .after(start_drag_connection)
+                    .before(end_drag_connection)
+                    .run_if(not(is_replaying))
+                    .run_if(not(is_rebinding))
+                    .run_if(not(is_editing_level)),
+                draw_level_editor
+                    .after(end_terrain_drag)
+                    .run_if(is_editing_level),
+                draw_polygon_draft.after(draw_polygon_outline),
+                draw_create_preview
+                    .after(update_create_drag_system)
+                    .run_if(not(is_editing_level)),
+            ),
+        );
     }
 }
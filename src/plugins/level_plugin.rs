@@ -0,0 +1,26 @@
+//! Plugin for the static terrain: building the level at startup, and loading
+//! and saving level files
+//!
+//! The level editor itself reads the mouse, so it is registered by `InputPlugin`.
+//! Requires `ReplayPlugin`: levels cannot be loaded while a session is running.
+
+use bevy::prelude::*;
+use crate::core::setup::WorldBounds;
+use crate::systems::persistence::{
+    load_level_library, load_level_system, save_level_system, spawn_current_level, CurrentLevel,
+    LevelLibrary, LoadLevelEvent, SaveLevelEvent,
+};
+
+pub struct LevelPlugin;
+
+impl Plugin for LevelPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<LoadLevelEvent>()
+            .add_event::<SaveLevelEvent>()
+            .init_resource::<WorldBounds>()
+            .init_resource::<CurrentLevel>()
+            .init_resource::<LevelLibrary>()
+            .add_systems(Startup, (load_level_library, spawn_current_level))
+            .add_systems(Update, (save_level_system, load_level_system).chain());
+    }
+}
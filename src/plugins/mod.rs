@@ -1,13 +1,35 @@
 //! Plugin modules for organizing system registration
 
+pub mod camera_plugin;
+pub mod debug_overlay_plugin;
 pub mod entities_plugin;
 pub mod effects_plugin;
+pub mod history_plugin;
 pub mod input_plugin;
+pub mod level_plugin;
 pub mod damage_plugin;
 pub mod ui_plugin;
+pub mod persistence_plugin;
+pub mod replay_plugin;
+pub mod sandbox_plugins;
+pub mod spatial_index_plugin;
+pub mod stats_plugin;
+pub mod time_control_plugin;
+pub mod tuning_plugin;
 
+pub use camera_plugin::CameraControlPlugin;
+pub use debug_overlay_plugin::DebugOverlayPlugin;
 pub use entities_plugin::EntitiesPlugin;
 pub use effects_plugin::EffectsPlugin;
+pub use history_plugin::HistoryPlugin;
 pub use input_plugin::InputPlugin;
+pub use level_plugin::LevelPlugin;
 pub use damage_plugin::DamagePlugin;
 pub use ui_plugin::UiPlugin;
+pub use persistence_plugin::PersistencePlugin;
+pub use replay_plugin::ReplayPlugin;
+pub use sandbox_plugins::SandboxPlugins;
+pub use spatial_index_plugin::SpatialIndexPlugin;
+pub use stats_plugin::StatsPlugin;
+pub use time_control_plugin::TimeControlPlugin;
+pub use tuning_plugin::TuningPlugin;
@@ -0,0 +1,35 @@
+//! Plugin for saving and loading sandbox scenes and prefabs
+//!
+//! Requires `HistoryPlugin` and `ReplayPlugin`: spawning a prefab can be
+//! undone, and is reported to recordings like any other spawn. Loading a scene
+//! clears the history, and cannot be done while a session is running.
+
+use bevy::prelude::*;
+use crate::systems::persistence::{
+    load_prefab_library, load_scene_system, save_prefab_system, save_scene_system,
+    spawn_prefab_system, LoadSceneEvent, PrefabLibrary, SavePrefabEvent, SaveSceneEvent,
+    SpawnPrefabEvent,
+};
+
+pub struct PersistencePlugin;
+
+impl Plugin for PersistencePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<SaveSceneEvent>()
+            .add_event::<LoadSceneEvent>()
+            .add_event::<SavePrefabEvent>()
+            .add_event::<SpawnPrefabEvent>()
+            .init_resource::<PrefabLibrary>()
+            .add_systems(Startup, load_prefab_library)
+            .add_systems(
+                Update,
+                (
+                    save_scene_system,
+                    load_scene_system,
+                    save_prefab_system,
+                    spawn_prefab_system,
+                )
+                    .chain(),
+            );
+    }
+}
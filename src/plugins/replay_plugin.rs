@@ -0,0 +1,53 @@
+//! Plugin for recording user actions and replaying them deterministically
+
+use bevy::prelude::*;
+use crate::core::setup::WorldBounds;
+use crate::entities::spawnable::SpawnableRegistry;
+use crate::plugins::spatial_index_plugin::add_spatial_index;
+use crate::systems::history::{delete_bodies, DeleteBodiesEvent, EditHistory};
+use crate::systems::input::DragState;
+use crate::systems::persistence::CurrentLevel;
+use crate::systems::replay::{
+    advance_replay_frame, handle_replay_controls, is_replaying, record_user_actions,
+    replay_connect_actions, replay_drag_actions, replay_erase_actions, replay_spawn_actions,
+    ReplayControl, ReplayState, UserAction,
+};
+use crate::systems::time::TimeControls;
+
+pub struct ReplayPlugin;
+
+impl Plugin for ReplayPlugin {
+    fn build(&self, app: &mut App) {
+        // Replayed ignitions pick their body from the spatial index
+        add_spatial_index(app);
+        app.add_event::<UserAction>()
+            .add_event::<ReplayControl>()
+            .init_resource::<ReplayState>()
+            // Playback drives dragging itself, so it works without the input plugin
+            .init_resource::<DragState>()
+            // Sessions reset the time controls, which may not be set up otherwise
+            .init_resource::<TimeControls>()
+            // Sessions start in the recorded level, which `LevelPlugin` normally tracks
+            .init_resource::<WorldBounds>()
+            .init_resource::<CurrentLevel>()
+            // Spawns are replayed through the registry, whoever filled it
+            .init_resource::<SpawnableRegistry>()
+            // Erasing goes through the history's deletion, applied in the same frame
+            .add_event::<DeleteBodiesEvent>()
+            // Sessions clear the history, which `HistoryPlugin` normally owns
+            .init_resource::<EditHistory>()
+            .add_systems(PreUpdate, handle_replay_controls)
+            .add_systems(
+                Update,
+                (
+                    replay_spawn_actions,
+                    replay_drag_actions,
+                    replay_connect_actions,
+                    replay_erase_actions.before(delete_bodies),
+                )
+                    .run_if(is_replaying),
+            )
+            .add_systems(PostUpdate, record_user_actions)
+            .add_systems(Last, advance_replay_frame);
+    }
+}
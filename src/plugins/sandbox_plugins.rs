@@ -0,0 +1,73 @@
+//! Plugin group bundling every sandbox plugin
+
+use bevy::app::PluginGroupBuilder;
+use bevy::prelude::*;
+
+use crate::plugins::{
+    CameraControlPlugin, DamagePlugin, DebugOverlayPlugin, EffectsPlugin, EntitiesPlugin,
+    HistoryPlugin, InputPlugin, LevelPlugin, PersistencePlugin, ReplayPlugin, StatsPlugin,
+    TimeControlPlugin, TuningPlugin, UiPlugin,
+};
+
+/// All gameplay plugins of the sandbox.
+///
+/// The physics backend is not part of the group: add
+/// `RapierPhysicsPlugin::<NoUserData>::pixels_per_meter(100.0)` alongside it.
+///
+/// The members that read the mouse and keyboard or need a window (input, top
+/// bar, camera controls and debug overlays) start disabled, so the group runs
+/// headless under `MinimalPlugins` as it is:
+///
+/// ```no_run
+/// use bevy::prelude::*;
+/// use bevy_rapier2d::prelude::*;
+/// use bevy_sandbox_ragdoll::SandboxPlugins;
+///
+/// App::new()
+///     .add_plugins((MinimalPlugins, TransformPlugin, HierarchyPlugin))
+///     .add_plugins(RapierPhysicsPlugin::<NoUserData>::pixels_per_meter(100.0))
+///     .add_plugins(SandboxPlugins)
+///     .run();
+/// ```
+///
+/// `tests/headless.rs` runs the group this way for a fixed number of frames.
+/// A windowed app adds [`SandboxPlugins::windowed`] instead, and tools can opt
+/// into single members with `PluginGroupBuilder::enable`, for example
+/// `SandboxPlugins.build().enable::<InputPlugin>()` to drive `ActionInput`.
+/// Any member can be opted out with `PluginGroupBuilder::disable`.
+pub struct SandboxPlugins;
+
+impl SandboxPlugins {
+    /// The group with its input, top bar, camera control and debug overlay members enabled
+    pub fn windowed() -> PluginGroupBuilder {
+        Self.build()
+            .enable::<InputPlugin>()
+            .enable::<UiPlugin>()
+            .enable::<CameraControlPlugin>()
+            .enable::<DebugOverlayPlugin>()
+    }
+}
+
+impl PluginGroup for SandboxPlugins {
+    fn build(self) -> PluginGroupBuilder {
+        PluginGroupBuilder::start::<Self>()
+            .add(TuningPlugin)
+            .add(EntitiesPlugin)
+            .add(LevelPlugin)
+            .add(DamagePlugin)
+            .add(EffectsPlugin)
+            .add(PersistencePlugin)
+            .add(ReplayPlugin)
+            .add(HistoryPlugin)
+            .add(TimeControlPlugin)
+            .add(StatsPlugin)
+            .add(InputPlugin)
+            .add(UiPlugin)
+            .add(CameraControlPlugin)
+            .add(DebugOverlayPlugin)
+            .disable::<InputPlugin>()
+            .disable::<UiPlugin>()
+            .disable::<CameraControlPlugin>()
+            .disable::<DebugOverlayPlugin>()
+    }
+}
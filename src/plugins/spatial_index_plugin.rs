@@ -0,0 +1,24 @@
+//! Plugin keeping the spatial index of rigid bodies up to date
+//!
+//! Not part of `SandboxPlugins` on its own: the plugins whose systems query the
+//! index add it when it is missing, so it runs once however they are combined.
+
+use bevy::prelude::*;
+use crate::systems::physics::{rebuild_spatial_index, SpatialIndex};
+
+pub struct SpatialIndexPlugin;
+
+impl Plugin for SpatialIndexPlugin {
+    fn build(&self, app: &mut App) {
+        // Bodies are indexed where the last physics step left them, before anything reads them
+        app.init_resource::<SpatialIndex>()
+            .add_systems(PreUpdate, rebuild_spatial_index);
+    }
+}
+
+/// Add `SpatialIndexPlugin` unless another plugin already did
+pub(crate) fn add_spatial_index(app: &mut App) {
+    if !app.is_plugin_added::<SpatialIndexPlugin>() {
+        app.add_plugins(SpatialIndexPlugin);
+    }
+}
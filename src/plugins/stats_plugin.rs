@@ -0,0 +1,25 @@
+//! Plugin for counting entities and gameplay events, and exporting a session summary
+
+use bevy::prelude::*;
+use crate::core::events::{BodyDestroyed, ConnectionBroken, Exploded, Ignited, JointBroken};
+use crate::systems::stats::{
+    count_live_entities, count_session_events, export_summary_on_exit, LiveCounts,
+    SessionStats,
+};
+
+pub struct StatsPlugin;
+
+impl Plugin for StatsPlugin {
+    fn build(&self, app: &mut App) {
+        // The events are counted whichever plugins send them
+        app.add_event::<BodyDestroyed>()
+            .add_event::<JointBroken>()
+            .add_event::<ConnectionBroken>()
+            .add_event::<Exploded>()
+            .add_event::<Ignited>()
+            .init_resource::<LiveCounts>()
+            .init_resource::<SessionStats>()
+            .add_systems(PostUpdate, (count_session_events, count_live_entities))
+            .add_systems(Last, export_summary_on_exit);
+    }
+}
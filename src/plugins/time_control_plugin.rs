@@ -0,0 +1,21 @@
+//! Plugin for pausing, single-stepping and slowing down the simulation
+
+use bevy::prelude::*;
+use crate::systems::time::{
+    apply_time_controls, handle_time_control_events, TimeControlEvent, TimeControls,
+};
+
+/// Send `TimeControlEvent`s to pause, step or change the speed of the simulation.
+/// The new settings take effect from the next frame.
+pub struct TimeControlPlugin;
+
+impl Plugin for TimeControlPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<TimeControlEvent>()
+            .init_resource::<TimeControls>()
+            .add_systems(
+                Last,
+                (handle_time_control_events, apply_time_controls).chain(),
+            );
+    }
+}
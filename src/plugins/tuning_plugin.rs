@@ -0,0 +1,37 @@
+//! Plugin for loading and hot-reloading simulation tuning
+
+use bevy::prelude::*;
+use crate::core::tuning::{
+    apply_tuning_changes, load_tuning, ConnectionTuning, DebrisTuning, ExplosionTuning,
+    FireTuning, JointDamageTuning, ParticleTuning, Tuning, TuningHandle, TuningLoader,
+};
+
+/// Provides the tuning resources read by the damage, fire, explosion, particle and debris systems.
+///
+/// The resources start out at their built-in defaults. When the app has an
+/// `AssetPlugin`, they are then filled from `assets/sandbox.tuning.ron` and
+/// refreshed whenever that file is saved.
+pub struct TuningPlugin;
+
+impl Plugin for TuningPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ExplosionTuning>()
+            .init_resource::<FireTuning>()
+            .init_resource::<JointDamageTuning>()
+            .init_resource::<ConnectionTuning>()
+            .init_resource::<ParticleTuning>()
+            .init_resource::<DebrisTuning>();
+
+        if !app.is_plugin_added::<AssetPlugin>() {
+            return;
+        }
+
+        app.init_asset::<Tuning>()
+            .init_asset_loader::<TuningLoader>()
+            .add_systems(Startup, load_tuning)
+            .add_systems(
+                PreUpdate,
+                apply_tuning_changes.run_if(resource_exists::<TuningHandle>),
+            );
+    }
+}
@@ -1,24 +1,99 @@
 //! Plugin for user interface systems
+//!
+//! Requires `LevelPlugin`: the level panel lists, loads and saves its levels.
 
 use bevy::prelude::*;
 use crate::core::setup::setup;
+use crate::entities::spawnable::SpawnableRegistry;
+use crate::plugins::spatial_index_plugin::add_spatial_index;
+use crate::systems::debug::DebugOverlay;
+use crate::systems::input::{
+    capture_rebinding, is_editing_level, is_rebinding, load_action_map, ActionMap,
+    CreateDragState, LevelEditor, PolygonTool, RebindState,
+};
+use crate::systems::replay::is_replaying;
+use crate::systems::stats::{count_live_entities, count_session_events, LiveCounts, SessionStats};
 use crate::ui::{
-    handle_button_clicks, setup_ui_topbar, spawn_selected_object_on_click,
-    sync_selection_with_connection_system, SelectedObject,
+    handle_action_buttons, handle_binding_buttons, handle_bindings_menu_buttons,
+    handle_debug_layer_buttons,
+    handle_button_clicks, handle_inspector_edit_buttons, handle_level_buttons,
+    handle_polygon_material_buttons, handle_prefab_save_button, handle_shape_buttons, handle_time_control_buttons, select_inspected_body,
+    setup_inspector, setup_time_controls, setup_ui_topbar, spawn_selected_object_on_click,
+    sync_selection_with_connection_system, type_prompt_name, update_bindings_screen,
+    update_debug_menu, update_inspector, update_inspector_values, update_level_panel, update_name_prompt,
+    update_polygon_panel, update_prefab_palette, update_shape_panel, update_stats_hud, update_time_scale_label, InspectorState,
+    NamePrompt, SelectedObject,
 };
 
 pub struct UiPlugin;
 
 impl Plugin for UiPlugin {
     fn build(&self, app: &mut App) {
+        // The fire tool picks the nearest flammable body from the spatial index
+        add_spatial_index(app);
         app.init_resource::<SelectedObject>()
-            .add_systems(Startup, (setup, setup_ui_topbar))
+            .init_resource::<ActionMap>()
+            .init_resource::<RebindState>()
+            .init_resource::<InspectorState>()
+            .init_resource::<NamePrompt>()
+            .init_resource::<LevelEditor>()
+            .init_resource::<PolygonTool>()
+            .init_resource::<CreateDragState>()
+            .init_resource::<DebugOverlay>()
+            .init_resource::<SpawnableRegistry>()
+            // The HUD stays at zero without the stats plugin
+            .init_resource::<LiveCounts>()
+            .init_resource::<SessionStats>()
+            .add_systems(
+                Startup,
+                (
+                    setup,
+                    // Button labels show the hotkeys as loaded from the bindings file
+                    setup_ui_topbar.after(load_action_map),
+                    setup_time_controls,
+                    setup_inspector,
+                ),
+            )
             .add_systems(
                 Update,
                 (
                     handle_button_clicks,
-                    spawn_selected_object_on_click,
+                    handle_action_buttons,
+                    spawn_selected_object_on_click
+                        .run_if(not(is_replaying))
+                        .run_if(not(is_rebinding))
+                        .run_if(not(is_editing_level)),
                     sync_selection_with_connection_system,
+                    handle_time_control_buttons,
+                    update_time_scale_label,
+                    (
+                        select_inspected_body.run_if(not(is_rebinding)),
+                        handle_inspector_edit_buttons,
+                        update_inspector,
+                        update_inspector_values,
+                    )
+                        .chain(),
+                    (handle_prefab_save_button, update_prefab_palette, update_name_prompt),
+                    (handle_level_buttons, update_level_panel).chain(),
+                    (handle_polygon_material_buttons, update_polygon_panel).chain(),
+                    (handle_shape_buttons, update_shape_panel).chain(),
+                    (handle_debug_layer_buttons, update_debug_menu).chain(),
+                ),
+            )
+            // Typing a name must swallow input before any other system sees it
+            .add_systems(PreUpdate, type_prompt_name.after(bevy::input::InputSystem))
+            .add_systems(
+                PostUpdate,
+                update_stats_hud
+                    .after(count_live_entities)
+                    .after(count_session_events),
+            )
+            .add_systems(
+                Update,
+                (
+                    (handle_binding_buttons, handle_bindings_menu_buttons)
+                        .before(capture_rebinding),
+                    update_bindings_screen.after(capture_rebinding),
                 ),
             );
     }
@@ -3,5 +3,12 @@
 pub use crate::core::components::*;
 pub use crate::core::constants::*;
 pub use crate::core::utils::*;
+pub use crate::entities::spawnable::{
+    SpawnCategory, SpawnShape, Spawnable, SpawnableAppExt, SpawnableRegistry,
+};
+pub use crate::systems::input::InputBinding;
+pub use crate::systems::persistence::CurrentLevel;
+pub use crate::systems::stats::{LiveCounts, SessionStats};
+pub use crate::plugins::SandboxPlugins;
 pub use bevy::prelude::*;
 pub use bevy_rapier2d::prelude::*;
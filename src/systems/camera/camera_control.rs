@@ -0,0 +1,181 @@
+//! Zooming, panning and following bodies with the sandbox camera
+//!
+//! Only the camera's `Transform` and `OrthographicProjection` are changed, so
+//! `get_cursor_world_position` and every tool built on it keep mapping the
+//! cursor onto the world correctly at any zoom level or offset.
+
+use bevy::input::mouse::{AccumulatedMouseScroll, MouseScrollUnit};
+use bevy::prelude::*;
+use bevy::time::Real;
+use bevy_rapier2d::prelude::*;
+
+use crate::core::entity_finder::find_body_at_point;
+use crate::core::utils::get_cursor_world_position;
+use crate::systems::input::action_map::{ActionInput, InputAction};
+use crate::systems::input::drag::Draggable;
+
+pub const MIN_ZOOM: f32 = 0.25;
+pub const MAX_ZOOM: f32 = 4.0;
+/// Projection scale change per line scrolled
+const ZOOM_STEP: f32 = 1.1;
+/// Pixel-based scrolling (touchpads) reports roughly this many pixels per line
+const PIXELS_PER_SCROLL_LINE: f32 = 40.0;
+/// How quickly a followed body is caught up with, per second
+const FOLLOW_SHARPNESS: f32 = 8.0;
+
+/// The camera the sandbox is viewed through
+#[derive(Component)]
+pub struct SandboxCamera;
+
+#[derive(Resource, Default)]
+pub struct CameraControl {
+    /// Body the camera is tracking
+    pub follow: Option<Entity>,
+    /// Cursor position, in window pixels, on the previous frame of a pan
+    pan_cursor: Option<Vec2>,
+}
+
+/// Offset of the cursor from the window centre, in world units at a projection scale of 1
+fn cursor_offset(window: &Window) -> Option<Vec2> {
+    let cursor = window.cursor_position()?;
+    let offset = cursor - window.size() / 2.0;
+    // Window y grows downwards, world y upwards
+    Some(Vec2::new(offset.x, -offset.y))
+}
+
+/// Zoom with the mouse wheel, keeping the point under the cursor fixed
+pub fn zoom_camera(
+    scroll: Res<AccumulatedMouseScroll>,
+    windows: Query<&Window>,
+    mut camera_query: Query<(&mut Transform, &mut OrthographicProjection), With<SandboxCamera>>,
+) {
+    let lines = match scroll.unit {
+        MouseScrollUnit::Line => scroll.delta.y,
+        MouseScrollUnit::Pixel => scroll.delta.y / PIXELS_PER_SCROLL_LINE,
+    };
+    if lines == 0.0 {
+        return;
+    }
+
+    let Ok(window) = windows.get_single() else {
+        return;
+    };
+    let Ok((mut transform, mut projection)) = camera_query.get_single_mut() else {
+        return;
+    };
+
+    let old_scale = projection.scale;
+    let new_scale = (old_scale * ZOOM_STEP.powf(-lines)).clamp(MIN_ZOOM, MAX_ZOOM);
+    projection.scale = new_scale;
+
+    if let Some(offset) = cursor_offset(window) {
+        let camera_pos = transform.translation.truncate();
+        let cursor_world = camera_pos + offset * old_scale;
+        let new_pos = cursor_world - offset * new_scale;
+        transform.translation.x = new_pos.x;
+        transform.translation.y = new_pos.y;
+    }
+}
+
+/// Drag the view around while the pan button is held. Panning stops following.
+pub fn pan_camera(
+    input: ActionInput,
+    mut control: ResMut<CameraControl>,
+    windows: Query<&Window>,
+    mut camera_query: Query<(&mut Transform, &OrthographicProjection), With<SandboxCamera>>,
+) {
+    if !input.pressed(InputAction::PanCamera) {
+        control.pan_cursor = None;
+        return;
+    }
+
+    let Ok(window) = windows.get_single() else {
+        return;
+    };
+    let Some(cursor) = window.cursor_position() else {
+        control.pan_cursor = None;
+        return;
+    };
+    let Ok((mut transform, projection)) = camera_query.get_single_mut() else {
+        return;
+    };
+
+    if let Some(previous) = control.pan_cursor {
+        let delta = cursor - previous;
+        if delta != Vec2::ZERO {
+            transform.translation.x -= delta.x * projection.scale;
+            transform.translation.y += delta.y * projection.scale;
+            control.follow = None;
+        }
+    }
+    control.pan_cursor = Some(cursor);
+}
+
+/// Start following the body under the cursor, or stop following
+pub fn toggle_camera_follow(
+    input: ActionInput,
+    mut control: ResMut<CameraControl>,
+    windows: Query<&Window>,
+    camera_q: Query<(&Camera, &GlobalTransform)>,
+    draggable_query: Query<(), With<Draggable>>,
+    rapier_context: Query<&RapierContext>,
+) {
+    if !input.just_pressed(InputAction::FollowBody) {
+        return;
+    }
+
+    let target = get_cursor_world_position(&windows, &camera_q)
+        .zip(rapier_context.get_single().ok())
+        .and_then(|(world_pos, context)| find_body_at_point(context, world_pos))
+        .filter(|&entity| draggable_query.contains(entity));
+
+    control.follow = match target {
+        Some(entity) if control.follow != Some(entity) => Some(entity),
+        _ => None,
+    };
+}
+
+/// Return to the initial view
+pub fn reset_camera(
+    input: ActionInput,
+    mut control: ResMut<CameraControl>,
+    mut camera_query: Query<(&mut Transform, &mut OrthographicProjection), With<SandboxCamera>>,
+) {
+    if !input.just_pressed(InputAction::ResetCamera) {
+        return;
+    }
+
+    control.follow = None;
+    for (mut transform, mut projection) in camera_query.iter_mut() {
+        transform.translation.x = 0.0;
+        transform.translation.y = 0.0;
+        projection.scale = 1.0;
+    }
+}
+
+/// Ease the camera towards the followed body. Runs on real time, so the view
+/// can still settle while the simulation is paused or slowed down.
+pub fn follow_camera_target(
+    time: Res<Time<Real>>,
+    mut control: ResMut<CameraControl>,
+    target_query: Query<&Transform, Without<SandboxCamera>>,
+    mut camera_query: Query<&mut Transform, With<SandboxCamera>>,
+) {
+    let Some(target) = control.follow else {
+        return;
+    };
+    let Ok(target_transform) = target_query.get(target) else {
+        // The body was destroyed
+        control.follow = None;
+        return;
+    };
+
+    let target_pos = target_transform.translation.truncate();
+    let blend = 1.0 - (-FOLLOW_SHARPNESS * time.delta_secs()).exp();
+    for mut transform in camera_query.iter_mut() {
+        let camera_pos = transform.translation.truncate();
+        let new_pos = camera_pos.lerp(target_pos, blend);
+        transform.translation.x = new_pos.x;
+        transform.translation.y = new_pos.y;
+    }
+}
@@ -0,0 +1,8 @@
+//! Camera zoom, panning and follow mode
+
+pub mod camera_control;
+
+pub use camera_control::{
+    follow_camera_target, pan_camera, reset_camera, toggle_camera_follow, zoom_camera,
+    CameraControl, SandboxCamera,
+};
@@ -1,11 +1,19 @@
+use bevy::ecs::system::SystemParam;
 use bevy::prelude::*;
 use bevy_rapier2d::prelude::*;
+use serde::{Deserialize, Serialize};
 
 use crate::core::components::{Connection, ConnectionKind};
+use crate::core::events::{ConnectionBreakCause, ConnectionBroken};
+use crate::core::entity_finder::find_body_at_point;
+use crate::core::tuning::ConnectionTuning;
 use crate::core::utils::get_cursor_world_position;
+use crate::systems::history::{EditCommand, EditHistory};
+use crate::systems::input::action_map::{ActionInput, InputAction};
+use crate::systems::replay::UserAction;
 
 /// Material type for connections, affecting joint strength and behavior
-#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
 pub enum ConnectionMaterial {
     Wood,      // Weak, moderate flexibility
     Metal,     // Strong, rigid
@@ -25,17 +33,6 @@ impl ConnectionMaterial {
         }
     }
     
-    /// Get the damping coefficient for this material
-    /// Damping > 2.5 greatly improves stability, rubber/rope materials need damping > 3
-    pub fn damping(&self) -> f32 {
-        match self {
-            ConnectionMaterial::Wood => 3.5,   // Soft material, needs high damping
-            ConnectionMaterial::Metal => 2.5,  // Rigid material, moderate damping
-            ConnectionMaterial::Rope => 5.0,   // Very soft, needs very high damping
-            ConnectionMaterial::Plastic => 3.0, // Soft material, needs high damping
-        }
-    }
-    
     /// Get the color for visual representation
     pub fn color(&self) -> Color {
         match self {
@@ -55,16 +52,6 @@ impl ConnectionMaterial {
             ConnectionMaterial::Plastic => 3.5,
         }
     }
-    
-    /// Get the break force threshold for this material
-    pub fn break_force(&self) -> f32 {
-        match self {
-            ConnectionMaterial::Wood => 5000.0,
-            ConnectionMaterial::Metal => 15000.0,
-            ConnectionMaterial::Rope => 2000.0,
-            ConnectionMaterial::Plastic => 7000.0,
-        }
-    }
 }
 
 impl Default for ConnectionMaterial {
@@ -167,22 +154,9 @@ pub fn update_hover_indicator(
     };
 
     if let Some(world_pos) = get_cursor_world_position(&windows, &camera_q) {
-        // Use raycast to detect the object under cursor
-        let filter = QueryFilter::default();
-        let mut hover_entity = None;
-        
-        if let Some((entity, _toi)) = context.cast_ray(
-            world_pos,
-            Vec2::new(0.0, -1.0),
-            0.1,
-            true,
-            filter,
-        ) {
-            // Check if the hit entity is connectable
-            if connectable_query.get(entity).is_ok() {
-                hover_entity = Some(entity);
-            }
-        }
+        // Rapier's query pipeline finds the collider under the cursor without scanning every body
+        let hover_entity = find_body_at_point(context, world_pos)
+            .filter(|&entity| connectable_query.get(entity).is_ok());
 
         // Remove old hover indicator
         for entity in hover_query.iter() {
@@ -235,7 +209,7 @@ pub fn start_drag_connection(
     mut commands: Commands,
     mut drag_conn_state: ResMut<DragConnectionState>,
     selection_state: Res<SelectionState>,
-    mouse_button: Res<ButtonInput<MouseButton>>,
+    input: ActionInput,
     windows: Query<&Window>,
     camera_q: Query<(&Camera, &GlobalTransform)>,
     connectable_query: Query<(Entity, &Transform), With<Connectable>>,
@@ -256,18 +230,9 @@ pub fn start_drag_connection(
         return;
     };
 
-    if mouse_button.just_pressed(MouseButton::Left) {
+    if input.just_pressed(InputAction::Connect) {
         if let Some(world_pos) = get_cursor_world_position(&windows, &camera_q) {
-            // Use raycast to detect the object under cursor
-            let filter = QueryFilter::default();
-            
-            if let Some((entity, _toi)) = context.cast_ray(
-                world_pos,
-                Vec2::new(0.0, -1.0),
-                0.1,
-                true,
-                filter,
-            ) {
+            if let Some(entity) = find_body_at_point(context, world_pos) {
                 // Check if the hit entity is connectable
                 if let Ok((entity, _transform)) = connectable_query.get(entity) {
                     // Start dragging connection from this entity
@@ -312,15 +277,15 @@ pub fn update_drag_connection(
 
 /// End drag connection and create constraint if over another object
 pub fn end_drag_connection(
-    mut commands: Commands,
+    mut connector: BodyConnector,
     mut drag_conn_state: ResMut<DragConnectionState>,
+    mut actions: EventWriter<UserAction>,
+    mut history: ResMut<EditHistory>,
     selection_state: Res<SelectionState>,
-    mouse_button: Res<ButtonInput<MouseButton>>,
+    input: ActionInput,
     windows: Query<&Window>,
     camera_q: Query<(&Camera, &GlobalTransform)>,
     connectable_query: Query<(Entity, &Transform), With<Connectable>>,
-    global_transform_query: Query<&GlobalTransform>,
-    mut velocity_query: Query<&mut Velocity>,
     line_query: Query<Entity, With<ConnectionDragLine>>,
     rapier_context: Query<&RapierContext>,
 ) {
@@ -332,137 +297,34 @@ pub fn end_drag_connection(
         return;
     };
 
-    if mouse_button.just_released(MouseButton::Left) {
+    if input.just_released(InputAction::Connect) {
         if let Some(start_entity) = drag_conn_state.start_entity {
             if let Some(cursor_pos) = get_cursor_world_position(&windows, &camera_q) {
-                // Use raycast to detect the object under cursor
-                let filter = QueryFilter::default();
-                let mut target_entity = None;
-                
-                if let Some((entity, _toi)) = context.cast_ray(
-                    cursor_pos,
-                    Vec2::new(0.0, -1.0),
-                    0.1,
-                    true,
-                    filter,
-                ) {
-                    // Check if the hit entity is connectable and not the start entity
-                    if entity != start_entity && connectable_query.get(entity).is_ok() {
-                        target_entity = Some(entity);
-                    }
-                }
+                // Check if the hit entity is connectable and not the start entity
+                let target_entity = find_body_at_point(context, cursor_pos).filter(|&entity| {
+                    entity != start_entity && connectable_query.get(entity).is_ok()
+                });
 
                 // If we found a target, create the connection
                 if let Some(end_entity) = target_entity {
-                    if let (Ok(start_global_transform), Ok(end_global_transform)) = (
-                        global_transform_query.get(start_entity),
-                        global_transform_query.get(end_entity),
+                    let kind = match selection_state.constraint_type {
+                        ConstraintType::Fixed => ConnectionKind::Fixed,
+                        ConstraintType::Hinge => ConnectionKind::Hinge,
+                    };
+                    let material = selection_state.material;
+
+                    if let Some(connection) = connector.connect(
+                        (start_entity, drag_conn_state.start_position),
+                        (end_entity, cursor_pos),
+                        kind,
+                        material,
                     ) {
-                        let start_body_pos = start_global_transform.translation().truncate();
-                        let end_body_pos = end_global_transform.translation().truncate();
-
-                        let start_click_pos = drag_conn_state.start_position;
-                        let end_click_pos = cursor_pos;
-
-                        let material = selection_state.material;
-
-                        // Calculate anchors on the connected objects in local space
-                        // We need to transform world-space click positions into local space
-                        // by applying the inverse rotation of each body
-                        let start_rotation = start_global_transform.to_scale_rotation_translation().1;
-                        let end_rotation = end_global_transform.to_scale_rotation_translation().1;
-                        
-                        // Convert world offset to local offset by applying inverse rotation
-                        // For 2D physics, we extract the Z-axis rotation angle and use 2D rotation
-                        let start_world_offset = start_click_pos - start_body_pos;
-                        let end_world_offset = end_click_pos - end_body_pos;
-                        
-                        // Extract 2D rotation angles (rotation around Z-axis in 2D)
-                        let start_angle = start_rotation.to_euler(bevy::math::EulerRot::XYZ).2;
-                        let end_angle = end_rotation.to_euler(bevy::math::EulerRot::XYZ).2;
-                        
-                        // Apply inverse 2D rotation to transform world offsets to local space
-                        let cos_start = (-start_angle).cos();
-                        let sin_start = (-start_angle).sin();
-                        let anchor_on_start = Vec2::new(
-                            start_world_offset.x * cos_start - start_world_offset.y * sin_start,
-                            start_world_offset.x * sin_start + start_world_offset.y * cos_start,
-                        );
-                        
-                        let cos_end = (-end_angle).cos();
-                        let sin_end = (-end_angle).sin();
-                        let anchor_on_end = Vec2::new(
-                            end_world_offset.x * cos_end - end_world_offset.y * sin_end,
-                            end_world_offset.x * sin_end + end_world_offset.y * cos_end,
-                        );
-
-                        // CRITICAL: Zero out velocities at connection moment to prevent explosion
-                        // This is essential for PPG (People Playground) style physics stability
-                        if let Ok(mut vel) = velocity_query.get_mut(start_entity) {
-                            vel.linvel = Vec2::ZERO;
-                            vel.angvel = 0.0;
-                        }
-                        if let Ok(mut vel) = velocity_query.get_mut(end_entity) {
-                            vel.linvel = Vec2::ZERO;
-                            vel.angvel = 0.0;
-                        }
-
-                        let break_force = material.break_force();
-                        let connection_kind = match selection_state.constraint_type {
-                            ConstraintType::Fixed => ConnectionKind::Fixed,
-                            ConstraintType::Hinge => ConnectionKind::Hinge,
-                        };
-
-                        // Create appropriate joint type based on constraint type
-                        // Anti-vibration/anti-explosion strategy:
-                        // 1. Apply proper damping (> 2.5) via motor force
-                        // 2. Local anchors are precisely calculated above
-                        // 3. Velocities are zeroed to prevent explosive forces
-                        let joint = match selection_state.constraint_type {
-                            ConstraintType::Fixed => {
-                                // Fixed joint: non-rotatable, rigid connection like a nail or weld
-                                // Uses material compliance internally for stability
-                                let fixed_joint = FixedJointBuilder::new()
-                                    .local_anchor1(anchor_on_start)
-                                    .local_anchor2(anchor_on_end);
-                                
-                                ImpulseJoint::new(end_entity, fixed_joint)
-                            }
-                            ConstraintType::Hinge => {
-                                // Revolute joint: rotatable connection like a bearing or hinge
-                                // Apply material damping for rotational friction
-                                let revolute_joint = RevoluteJointBuilder::new()
-                                    .local_anchor1(anchor_on_start)
-                                    .local_anchor2(anchor_on_end)
-                                    .motor_model(MotorModel::ForceBased)
-                                    .motor_max_force(material.damping() * 100.0);
-                                
-                                ImpulseJoint::new(end_entity, revolute_joint)
-                            }
-                        };
-
-                        commands.entity(start_entity).with_children(|parent| {
-                            parent.spawn((
-                                joint,
-                                UserCreatedJoint,
-                                JointMaterial(material),
-                                Connection {
-                                    a: start_entity,
-                                    b: end_entity,
-                                    anchor_a: anchor_on_start,
-                                    anchor_b: anchor_on_end,
-                                    kind: connection_kind,
-                                    break_force,
-                                    current_force: 0.0,
-                                },
-                                ConnectionVisual {
-                                    entity1: start_entity,
-                                    entity2: end_entity,
-                                    anchor1: anchor_on_start,
-                                    anchor2: anchor_on_end,
-                                    material,
-                                },
-                            ));
+                        history.record(EditCommand::Disconnect(connection));
+                        actions.send(UserAction::Connect {
+                            start: drag_conn_state.start_position,
+                            end: cursor_pos,
+                            kind,
+                            material,
                         });
                     }
                 }
@@ -471,7 +333,7 @@ pub fn end_drag_connection(
 
         // Clean up drag line
         for entity in line_query.iter() {
-            commands.entity(entity).despawn();
+            connector.commands.entity(entity).despawn();
         }
 
         // Reset drag connection state
@@ -480,6 +342,169 @@ pub fn end_drag_connection(
     }
 }
 
+/// Connects bodies where the user or a replay dragged a connection between them
+#[derive(SystemParam)]
+pub struct BodyConnector<'w, 's> {
+    commands: Commands<'w, 's>,
+    tuning: Res<'w, ConnectionTuning>,
+    global_transforms: Query<'w, 's, &'static GlobalTransform>,
+    velocities: Query<'w, 's, &'static mut Velocity>,
+}
+
+impl BodyConnector<'_, '_> {
+    /// Join two bodies at the world-space points where the connection drag started
+    /// and ended. Returns the created connection, or `None` if either body has no transform yet.
+    pub fn connect(
+        &mut self,
+        (start_entity, start_click_pos): (Entity, Vec2),
+        (end_entity, end_click_pos): (Entity, Vec2),
+        kind: ConnectionKind,
+        material: ConnectionMaterial,
+    ) -> Option<Connection> {
+        let (Ok(start_global_transform), Ok(end_global_transform)) = (
+            self.global_transforms.get(start_entity),
+            self.global_transforms.get(end_entity),
+        ) else {
+            return None;
+        };
+
+        let start_body_pos = start_global_transform.translation().truncate();
+        let end_body_pos = end_global_transform.translation().truncate();
+
+        // Calculate anchors on the connected objects in local space
+        // We need to transform world-space click positions into local space
+        // by applying the inverse rotation of each body
+        let start_rotation = start_global_transform.to_scale_rotation_translation().1;
+        let end_rotation = end_global_transform.to_scale_rotation_translation().1;
+
+        // Convert world offset to local offset by applying inverse rotation
+        // For 2D physics, we extract the Z-axis rotation angle and use 2D rotation
+        let start_world_offset = start_click_pos - start_body_pos;
+        let end_world_offset = end_click_pos - end_body_pos;
+
+        // Extract 2D rotation angles (rotation around Z-axis in 2D)
+        let start_angle = start_rotation.to_euler(bevy::math::EulerRot::XYZ).2;
+        let end_angle = end_rotation.to_euler(bevy::math::EulerRot::XYZ).2;
+
+        // Apply inverse 2D rotation to transform world offsets to local space
+        let cos_start = (-start_angle).cos();
+        let sin_start = (-start_angle).sin();
+        let anchor_on_start = Vec2::new(
+            start_world_offset.x * cos_start - start_world_offset.y * sin_start,
+            start_world_offset.x * sin_start + start_world_offset.y * cos_start,
+        );
+
+        let cos_end = (-end_angle).cos();
+        let sin_end = (-end_angle).sin();
+        let anchor_on_end = Vec2::new(
+            end_world_offset.x * cos_end - end_world_offset.y * sin_end,
+            end_world_offset.x * sin_end + end_world_offset.y * cos_end,
+        );
+
+        // CRITICAL: Zero out velocities at connection moment to prevent explosion
+        // This is essential for PPG (People Playground) style physics stability
+        if let Ok(mut vel) = self.velocities.get_mut(start_entity) {
+            vel.linvel = Vec2::ZERO;
+            vel.angvel = 0.0;
+        }
+        if let Ok(mut vel) = self.velocities.get_mut(end_entity) {
+            vel.linvel = Vec2::ZERO;
+            vel.angvel = 0.0;
+        }
+
+        let connection = Connection {
+            a: start_entity,
+            b: end_entity,
+            anchor_a: anchor_on_start,
+            anchor_b: anchor_on_end,
+            kind,
+            break_force: self.tuning.material(material).break_force,
+            current_force: 0.0,
+        };
+        spawn_connection(
+            &mut self.commands,
+            connection.clone(),
+            material,
+            &self.tuning,
+        );
+
+        Some(connection)
+    }
+}
+
+/// Spawn a user-created joint between `connection.a` and `connection.b`.
+///
+/// The joint entity is parented to `connection.a` so it is cleaned up with it,
+/// and carries the `Connection`, `JointMaterial` and `ConnectionVisual` records.
+pub fn spawn_connection(
+    commands: &mut Commands,
+    connection: Connection,
+    material: ConnectionMaterial,
+    tuning: &ConnectionTuning,
+) -> Entity {
+    // Create appropriate joint type based on constraint type
+    // Anti-vibration/anti-explosion strategy:
+    // 1. Apply proper damping (> 2.5) via motor force
+    // 2. Local anchors are precisely calculated by the caller
+    // 3. Velocities are zeroed by the caller to prevent explosive forces
+    let joint = match connection.kind {
+        ConnectionKind::Fixed => {
+            // Fixed joint: non-rotatable, rigid connection like a nail or weld
+            // Uses material compliance internally for stability
+            let fixed_joint = FixedJointBuilder::new()
+                .local_anchor1(connection.anchor_a)
+                .local_anchor2(connection.anchor_b);
+
+            ImpulseJoint::new(connection.b, fixed_joint)
+        }
+        ConnectionKind::Hinge => {
+            // Revolute joint: rotatable connection like a bearing or hinge
+            // Apply material damping for rotational friction
+            let revolute_joint = RevoluteJointBuilder::new()
+                .local_anchor1(connection.anchor_a)
+                .local_anchor2(connection.anchor_b)
+                .motor_model(MotorModel::ForceBased)
+                .motor_max_force(tuning.material(material).damping * 100.0);
+
+            ImpulseJoint::new(connection.b, revolute_joint)
+        }
+    };
+
+    let visual = ConnectionVisual {
+        entity1: connection.a,
+        entity2: connection.b,
+        anchor1: connection.anchor_a,
+        anchor2: connection.anchor_b,
+        material,
+    };
+    let parent = connection.a;
+
+    let joint_entity = commands
+        .spawn((joint, UserCreatedJoint, JointMaterial(material), connection, visual))
+        .id();
+    commands.entity(parent).add_child(joint_entity);
+
+    joint_entity
+}
+
+/// Push reloaded material tuning onto connections that already exist
+pub fn apply_connection_tuning(
+    tuning: Res<ConnectionTuning>,
+    mut joint_query: Query<(&mut Connection, &mut ImpulseJoint, &JointMaterial)>,
+) {
+    if !tuning.is_changed() || tuning.is_added() {
+        return;
+    }
+
+    for (mut connection, mut joint, material) in joint_query.iter_mut() {
+        let material = tuning.material(material.0);
+        connection.break_force = material.break_force;
+
+        if let TypedJoint::RevoluteJoint(revolute) = &mut joint.data {
+            revolute.set_motor_max_force(material.damping * 100.0);
+        }
+    }
+}
 
 fn spawn_connection_drag_line(commands: &mut Commands) {
     commands.spawn(ConnectionDragLine);
@@ -488,6 +513,7 @@ fn spawn_connection_drag_line(commands: &mut Commands) {
 /// System to despawn joint entities if one of the connected bodies is despawned
 pub fn handle_despawned_connected_entities(
     mut commands: Commands,
+    mut broken: EventWriter<ConnectionBroken>,
     joint_query: Query<(Entity, &Connection)>,
     transform_query: Query<&Transform>, // Used to check for existence
 ) {
@@ -496,6 +522,12 @@ pub fn handle_despawned_connected_entities(
             // One of the connected entities is despawned, so despawn the joint entity
             if let Some(mut entity_commands) = commands.get_entity(joint_entity) {
                 entity_commands.despawn();
+                broken.send(ConnectionBroken {
+                    entity: joint_entity,
+                    a: connection.a,
+                    b: connection.b,
+                    cause: ConnectionBreakCause::BodyRemoved,
+                });
             }
         }
     }
@@ -543,15 +575,45 @@ pub fn update_connection_visuals(
     }
 }
 
+/// Record the force each connection's joint applied over the last physics step,
+/// from the linear impulse Rapier solved for it
+pub fn measure_connection_forces(
+    rapier_context: Query<&RapierContext>,
+    mut connection_query: Query<(&RapierImpulseJointHandle, &mut Connection)>,
+) {
+    let Ok(context) = rapier_context.get_single() else {
+        return;
+    };
+    let dt = context.integration_parameters.dt;
+    if dt <= 0.0 {
+        return;
+    }
+
+    for (handle, mut connection) in connection_query.iter_mut() {
+        connection.current_force = context
+            .impulse_joints
+            .get(handle.0)
+            .map_or(0.0, |joint| {
+                Vec2::new(joint.impulses.x, joint.impulses.y).length() / dt
+            });
+    }
+}
+
 /// System to check for and break joints that exceed their force limit
 pub fn break_joints_on_force_limit(
     mut commands: Commands,
-    joint_query: Query<(Entity, &ImpulseJoint, &Connection)>,
-    _rapier_context: Query<&RapierContext>,
+    mut broken: EventWriter<ConnectionBroken>,
+    joint_query: Query<(Entity, &Connection), With<ImpulseJoint>>,
 ) {
-    for (entity, _joint, connection) in joint_query.iter() {
+    for (entity, connection) in joint_query.iter() {
         if connection.current_force > connection.break_force {
             commands.entity(entity).despawn_recursive();
+            broken.send(ConnectionBroken {
+                entity,
+                a: connection.a,
+                b: connection.b,
+                cause: ConnectionBreakCause::Overload,
+            });
         }
     }
 }
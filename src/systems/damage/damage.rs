@@ -2,9 +2,11 @@ use bevy::prelude::*;
 use bevy_rapier2d::prelude::*;
 
 use crate::core::components::RagdollPart;
+use crate::core::events::{DamageCause, JointBroken};
+use crate::core::tuning::JointDamageTuning;
 use crate::systems::effects::blood::spawn_blood_particles;
 
-#[derive(Component)]
+#[derive(Component, Clone)]
 pub struct JointHealth {
     pub current: f32,
     pub max: f32,
@@ -22,8 +24,29 @@ pub struct PreviousVelocity {
     pub angvel: f32,
 }
 
+/// Detach a ragdoll part from its parent and report the break
+pub fn break_joint(
+    commands: &mut Commands,
+    joint_broken: &mut EventWriter<JointBroken>,
+    entity: Entity,
+    joint_health: &JointHealth,
+    position: Vec2,
+    cause: DamageCause,
+) {
+    commands.entity(entity).remove::<ImpulseJoint>();
+    commands.entity(entity).remove::<JointHealth>();
+    joint_broken.send(JointBroken {
+        entity,
+        parent: joint_health.parent_entity,
+        position,
+        cause,
+    });
+}
+
 pub fn check_joint_damage(
     mut commands: Commands,
+    mut joint_broken: EventWriter<JointBroken>,
+    tuning: Res<JointDamageTuning>,
     mut joint_query: Query<(Entity, &mut JointHealth, &Transform)>,
     velocity_query: Query<&Velocity, With<RagdollPart>>,
 ) {
@@ -36,28 +59,40 @@ pub fn check_joint_damage(
             let angular_diff = (child_vel.angvel - parent_vel.angvel).abs();
             
             // More sensitive stress calculation
-            let stress = velocity_diff * 0.015 + angular_diff * 0.15;
+            let stress = velocity_diff * tuning.linear_stress_factor
+                + angular_diff * tuning.angular_stress_factor;
             
             // Lower threshold for joint stress damage
-            if stress > 3.0 {
-                let mut damage = (stress - 3.0) * 0.7;
+            if stress > tuning.stress_threshold {
+                let mut damage = (stress - tuning.stress_threshold) * tuning.damage_per_stress;
                 
                 // Extra damage for extreme stress (violent movements)
-                if stress > 15.0 {
-                    let extreme_stress_multiplier = (stress / 15.0).min(2.0);
+                if stress > tuning.extreme_stress {
+                    let extreme_stress_multiplier =
+                        (stress / tuning.extreme_stress).min(tuning.max_extreme_multiplier);
                     damage *= extreme_stress_multiplier;
                 }
                 
+                let was_intact = joint_health.current > 0.0;
                 joint_health.current -= damage;
                 
                 if joint_health.current <= 0.0 {
-                    let position = transform.translation.truncate();
-                    // Use velocity difference for blood spray direction
-                    let blood_vel = (child_vel.linvel - parent_vel.linvel) * 0.3;
-                    spawn_blood_particles(&mut commands, position, blood_vel);
-                    
-                    commands.entity(entity).remove::<ImpulseJoint>();
-                    commands.entity(entity).remove::<JointHealth>();
+                    // Another system may have broken it earlier this frame
+                    if was_intact {
+                        let position = transform.translation.truncate();
+                        // Use velocity difference for blood spray direction
+                        let blood_vel = (child_vel.linvel - parent_vel.linvel) * 0.3;
+                        spawn_blood_particles(&mut commands, position, blood_vel);
+                        
+                        break_joint(
+                            &mut commands,
+                            &mut joint_broken,
+                            entity,
+                            &joint_health,
+                            position,
+                            DamageCause::Stress,
+                        );
+                    }
                 } else if joint_health.current < joint_health.max * 0.5 && joint_health.current > 0.0 {
                     if commands.get_entity(entity).is_some() {
                         commands.entity(entity).insert(Fractured {
@@ -72,6 +107,7 @@ pub fn check_joint_damage(
 
 pub fn apply_explosive_joint_damage(
     mut commands: Commands,
+    mut joint_broken: EventWriter<JointBroken>,
     mut joint_query: Query<(Entity, &mut JointHealth, &Transform)>,
     explosion_force_query: Query<(Entity, &Transform, &ExternalImpulse), (With<RagdollPart>, Changed<ExternalImpulse>)>,
 ) {
@@ -84,14 +120,23 @@ pub fn apply_explosive_joint_damage(
                 
                 if distance < 50.0 && joint_entity == force_entity {
                     let damage = impulse_magnitude * 0.02;
+                    let was_intact = joint_health.current > 0.0;
                     joint_health.current -= damage;
                     
                     if joint_health.current <= 0.0 {
-                        let position = joint_transform.translation.truncate();
-                        spawn_blood_particles(&mut commands, position, Vec2::ZERO);
-                        
-                        commands.entity(joint_entity).remove::<ImpulseJoint>();
-                        commands.entity(joint_entity).remove::<JointHealth>();
+                        if was_intact {
+                            let position = joint_transform.translation.truncate();
+                            spawn_blood_particles(&mut commands, position, Vec2::ZERO);
+                            
+                            break_joint(
+                                &mut commands,
+                                &mut joint_broken,
+                                joint_entity,
+                                &joint_health,
+                                position,
+                                DamageCause::Explosion,
+                            );
+                        }
                     } else if joint_health.current < joint_health.max * 0.5 {
                         if commands.get_entity(joint_entity).is_some() {
                             commands.entity(joint_entity).insert(Fractured {
@@ -136,6 +181,7 @@ pub fn track_velocity(
 
 pub fn detect_impact_damage(
     mut commands: Commands,
+    mut joint_broken: EventWriter<JointBroken>,
     mut joint_query: Query<(Entity, &mut JointHealth, &Transform), Without<Velocity>>,
     mut ragdoll_query: Query<(Entity, &Velocity, Option<&PreviousVelocity>), With<RagdollPart>>,
 ) {
@@ -167,20 +213,29 @@ pub fn detect_impact_damage(
                             impact_damage += fall_damage_bonus;
                         }
                         
+                        let was_intact = joint_health.current > 0.0;
                         joint_health.current -= impact_damage;
                         
                         if joint_health.current <= 0.0 {
-                            let position = joint_transform.translation.truncate();
-                            // More dramatic blood spray for high impacts
-                            let blood_velocity = if velocity_change > 500.0 {
-                                velocity.linvel * 0.5
-                            } else {
-                                velocity.linvel * 0.3
-                            };
-                            spawn_blood_particles(&mut commands, position, blood_velocity);
-                            
-                            commands.entity(joint_entity).remove::<ImpulseJoint>();
-                            commands.entity(joint_entity).remove::<JointHealth>();
+                            if was_intact {
+                                let position = joint_transform.translation.truncate();
+                                // More dramatic blood spray for high impacts
+                                let blood_velocity = if velocity_change > 500.0 {
+                                    velocity.linvel * 0.5
+                                } else {
+                                    velocity.linvel * 0.3
+                                };
+                                spawn_blood_particles(&mut commands, position, blood_velocity);
+                                
+                                break_joint(
+                                    &mut commands,
+                                    &mut joint_broken,
+                                    joint_entity,
+                                    &joint_health,
+                                    position,
+                                    DamageCause::Impact,
+                                );
+                            }
                         } else if joint_health.current < joint_health.max * 0.5 {
                             if commands.get_entity(joint_entity).is_some() {
                                 commands.entity(joint_entity).insert(Fractured {
@@ -197,6 +252,7 @@ pub fn detect_impact_damage(
 
 pub fn collision_joint_damage(
     mut commands: Commands,
+    mut joint_broken: EventWriter<JointBroken>,
     mut collision_events: EventReader<CollisionEvent>,
     mut joint_query: Query<(Entity, &mut JointHealth, &Transform, Option<&Velocity>), With<RagdollPart>>,
     mass_query: Query<Option<&ReadMassProperties>>,
@@ -231,21 +287,30 @@ pub fn collision_joint_damage(
                             collision_damage += high_velocity_bonus;
                         }
                         
+                        let was_intact = joint_health.current > 0.0;
                         joint_health.current -= collision_damage;
                         
                         if joint_health.current <= 0.0 {
-                            let position = transform.translation.truncate();
-                            let vel_dir = velocity_opt.map(|v| v.linvel).unwrap_or(Vec2::ZERO);
-                            // More blood for higher velocity collisions
-                            let blood_velocity = if velocity > 400.0 {
-                                vel_dir * 0.4
-                            } else {
-                                vel_dir * 0.2
-                            };
-                            spawn_blood_particles(&mut commands, position, blood_velocity);
-                            
-                            commands.entity(joint_entity).remove::<ImpulseJoint>();
-                            commands.entity(joint_entity).remove::<JointHealth>();
+                            if was_intact {
+                                let position = transform.translation.truncate();
+                                let vel_dir = velocity_opt.map(|v| v.linvel).unwrap_or(Vec2::ZERO);
+                                // More blood for higher velocity collisions
+                                let blood_velocity = if velocity > 400.0 {
+                                    vel_dir * 0.4
+                                } else {
+                                    vel_dir * 0.2
+                                };
+                                spawn_blood_particles(&mut commands, position, blood_velocity);
+                                
+                                break_joint(
+                                    &mut commands,
+                                    &mut joint_broken,
+                                    joint_entity,
+                                    &joint_health,
+                                    position,
+                                    DamageCause::Collision,
+                                );
+                            }
                         } else if joint_health.current < joint_health.max * 0.5 {
                             if commands.get_entity(joint_entity).is_some() {
                                 commands.entity(joint_entity).insert(Fractured {
@@ -0,0 +1,8 @@
+//! Physics debug overlays
+
+pub mod overlay;
+
+pub use overlay::{
+    debug_overlay_hotkeys, draw_joint_health_bars, draw_shockwave_rings, draw_sleeping_bodies,
+    draw_velocity_vectors, is_layer_enabled, sync_rapier_debug_render, DebugLayer, DebugOverlay,
+};
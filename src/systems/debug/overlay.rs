@@ -0,0 +1,209 @@
+//! Physics debug overlays, each switched on separately from the debug menu
+//!
+//! Collider wireframes, joint anchors and contacts are drawn by Rapier's own
+//! debug renderer, whose mode follows the enabled layers. The other layers
+//! are drawn with gizmos here. Every layer starts off.
+
+use std::collections::BTreeSet;
+
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+use crate::core::components::ShockwaveRing;
+use crate::systems::damage::damage::JointHealth;
+use crate::systems::input::action_map::{ActionInput, InputAction};
+
+/// Velocity vectors are drawn as far as a body travels in this many seconds
+const VELOCITY_SECONDS: f32 = 0.1;
+/// Bodies slower than this get no velocity vector
+const MIN_DRAWN_SPEED: f32 = 5.0;
+const JOINT_BAR_SIZE: Vec2 = Vec2::new(24.0, 4.0);
+/// Joint health bars sit this far above their joint's anchor
+const JOINT_BAR_OFFSET: f32 = 8.0;
+const SLEEPING_MARKER_RADIUS: f32 = 6.0;
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub enum DebugLayer {
+    Colliders,
+    JointAnchors,
+    /// Contact points and their normals
+    Contacts,
+    Velocities,
+    Sleeping,
+    /// Health bars on ragdoll joints
+    JointHealth,
+    /// Radius and thickness of travelling shockwaves
+    Shockwaves,
+}
+
+impl DebugLayer {
+    pub const ALL: [DebugLayer; 7] = [
+        DebugLayer::Colliders,
+        DebugLayer::JointAnchors,
+        DebugLayer::Contacts,
+        DebugLayer::Velocities,
+        DebugLayer::Sleeping,
+        DebugLayer::JointHealth,
+        DebugLayer::Shockwaves,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            DebugLayer::Colliders => "Colliders",
+            DebugLayer::JointAnchors => "Joint anchors",
+            DebugLayer::Contacts => "Contacts",
+            DebugLayer::Velocities => "Velocities",
+            DebugLayer::Sleeping => "Sleeping",
+            DebugLayer::JointHealth => "Joint health",
+            DebugLayer::Shockwaves => "Shockwaves",
+        }
+    }
+
+    /// What Rapier's debug renderer draws for this layer, if it draws it
+    fn rapier_mode(self) -> Option<DebugRenderMode> {
+        match self {
+            DebugLayer::Colliders => Some(DebugRenderMode::COLLIDER_SHAPES),
+            DebugLayer::JointAnchors => Some(DebugRenderMode::JOINTS),
+            DebugLayer::Contacts => Some(DebugRenderMode::CONTACTS),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Resource, Clone, PartialEq, Debug, Default)]
+pub struct DebugOverlay {
+    pub is_menu_open: bool,
+    pub layers: BTreeSet<DebugLayer>,
+}
+
+impl DebugOverlay {
+    pub fn is_enabled(&self, layer: DebugLayer) -> bool {
+        self.layers.contains(&layer)
+    }
+
+    pub fn toggle(&mut self, layer: DebugLayer) {
+        if !self.layers.remove(&layer) {
+            self.layers.insert(layer);
+        }
+    }
+}
+
+/// Run condition for systems drawing `layer`
+pub fn is_layer_enabled(layer: DebugLayer) -> impl Fn(Res<DebugOverlay>) -> bool {
+    move |overlay: Res<DebugOverlay>| overlay.is_enabled(layer)
+}
+
+pub fn debug_overlay_hotkeys(input: ActionInput, mut overlay: ResMut<DebugOverlay>) {
+    if input.just_pressed(InputAction::ToggleDebugMenu) {
+        overlay.is_menu_open = !overlay.is_menu_open;
+    }
+}
+
+/// Point Rapier's debug renderer at the enabled layers, and switch it off when
+/// none of them are its own. Does nothing without `RapierDebugRenderPlugin`.
+pub fn sync_rapier_debug_render(
+    overlay: Res<DebugOverlay>,
+    context: Option<ResMut<DebugRenderContext>>,
+) {
+    let Some(mut context) = context else {
+        return;
+    };
+    if !overlay.is_changed() && !context.is_added() {
+        return;
+    }
+
+    let mut mode = DebugRenderMode::empty();
+    for layer in &overlay.layers {
+        if let Some(layer_mode) = layer.rapier_mode() {
+            mode |= layer_mode;
+        }
+    }
+    context.enabled = mode != DebugRenderMode::empty();
+    context.pipeline.mode = mode;
+}
+
+pub fn draw_velocity_vectors(
+    mut gizmos: Gizmos,
+    body_query: Query<(&GlobalTransform, &Velocity), With<RigidBody>>,
+) {
+    for (transform, velocity) in body_query.iter() {
+        if velocity.linvel.length() < MIN_DRAWN_SPEED {
+            continue;
+        }
+        let start = transform.translation().truncate();
+        let end = start + velocity.linvel * VELOCITY_SECONDS;
+        gizmos.arrow_2d(start, end, Color::srgb(0.3, 0.9, 1.0));
+    }
+}
+
+/// Ring the centre of every body Rapier has put to sleep
+pub fn draw_sleeping_bodies(
+    mut gizmos: Gizmos,
+    rapier_context: Query<&RapierContext>,
+    transform_query: Query<&GlobalTransform>,
+) {
+    let Ok(context) = rapier_context.get_single() else {
+        return;
+    };
+
+    for (entity, handle) in context.entity2body() {
+        let is_sleeping = context.bodies.get(*handle).is_some_and(|body| body.is_sleeping());
+        if !is_sleeping {
+            continue;
+        }
+        if let Ok(transform) = transform_query.get(*entity) {
+            gizmos.circle_2d(
+                transform.translation().truncate(),
+                SLEEPING_MARKER_RADIUS,
+                Color::srgb(0.5, 0.5, 1.0),
+            );
+        }
+    }
+}
+
+/// A bar above each ragdoll joint, emptying and turning red as the joint is damaged
+pub fn draw_joint_health_bars(
+    mut gizmos: Gizmos,
+    joint_query: Query<(&JointHealth, &ImpulseJoint, &GlobalTransform)>,
+) {
+    for (joint_health, joint, transform) in joint_query.iter() {
+        let anchor = transform
+            .transform_point(joint.data.as_ref().local_anchor2().extend(0.0))
+            .truncate();
+        let center = anchor + Vec2::Y * JOINT_BAR_OFFSET;
+        let fraction = (joint_health.current / joint_health.max.max(f32::EPSILON)).clamp(0.0, 1.0);
+
+        let left = center - Vec2::X * JOINT_BAR_SIZE.x / 2.0;
+        let filled = left + Vec2::X * JOINT_BAR_SIZE.x * fraction;
+        let fill_color = Color::srgb(1.0 - fraction, fraction, 0.2);
+        // Stacked lines fill the bar, since gizmos only draw outlines
+        for row in [-1.0, 0.0, 1.0] {
+            let offset = Vec2::Y * row * JOINT_BAR_SIZE.y / 4.0;
+            gizmos.line_2d(left + offset, filled + offset, fill_color);
+        }
+        gizmos.rect_2d(
+            Isometry2d::from_translation(center),
+            JOINT_BAR_SIZE,
+            Color::srgba(0.9, 0.9, 0.9, 0.6),
+        );
+    }
+}
+
+/// The wave front of each shockwave, and the band it pushed and damaged bodies
+/// in on its last step: from where the front was to a wave's thickness past it
+pub fn draw_shockwave_rings(mut gizmos: Gizmos, shockwave_query: Query<&ShockwaveRing>) {
+    for shockwave in shockwave_query.iter() {
+        let color = Color::srgb(1.0, 0.6, 0.2);
+        gizmos.circle_2d(shockwave.origin, shockwave.current_radius, color);
+
+        let band_color = color.with_alpha(0.4);
+        if shockwave.previous_radius > 0.0 {
+            gizmos.circle_2d(shockwave.origin, shockwave.previous_radius, band_color);
+        }
+        gizmos.circle_2d(
+            shockwave.origin,
+            shockwave.current_radius + shockwave.wave_thickness,
+            band_color,
+        );
+    }
+}
@@ -1,6 +1,6 @@
 use bevy::prelude::*;
 
-use crate::core::components::{ExplosionFlash, ExplosionShockwave, SmokeParticle};
+use crate::core::components::{ExplosionFlash, ExplosionShockwave};
 use crate::core::utils::set_sprite_alpha;
 
 pub fn animate_explosion_flash(
@@ -47,29 +47,3 @@ pub fn animate_explosion_shockwave(
         }
     }
 }
-
-pub fn animate_smoke_particles(
-    mut commands: Commands,
-    time: Res<Time>,
-    mut query: Query<(Entity, &mut SmokeParticle, &mut Transform, &mut Sprite)>,
-) {
-    for (entity, mut smoke, mut transform, mut sprite) in query.iter_mut() {
-        smoke.lifetime.tick(time.delta());
-
-        transform.translation += smoke.velocity.extend(0.0) * time.delta_secs();
-
-        smoke.velocity.y += 30.0 * time.delta_secs();
-        smoke.velocity *= 0.98;
-
-        let progress = smoke.lifetime.fraction();
-        let scale = 1.0 + progress * 2.0;
-        transform.scale = Vec3::splat(scale);
-
-        let alpha = (1.0 - progress) * 0.6;
-        set_sprite_alpha(&mut sprite, alpha);
-
-        if smoke.lifetime.just_finished() {
-            commands.entity(entity).despawn();
-        }
-    }
-}
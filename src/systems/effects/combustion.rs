@@ -1,22 +1,33 @@
 use bevy::prelude::*;
 use rand::Rng;
 
-use crate::core::components::{FireParticle, Flammable, Health, OnFire, RagdollPart};
-use crate::core::constants::{FIRE_DAMAGE_PER_SEC, FIRE_DURATION, FIRE_SPAWN_KEY, FIRE_SPREAD_RADIUS};
-use crate::core::entity_finder::find_closest_entity;
-use crate::core::utils::{get_cursor_world_position, set_sprite_alpha};
+use crate::core::components::{Flammable, Health, OnFire, RagdollPart};
+use crate::core::events::{BodyDestroyed, DamageCause, Ignited, IgnitionCause};
+use crate::core::rng::{RngStream, SimRng};
+use crate::core::tuning::FireTuning;
+use crate::core::utils::get_cursor_world_position;
 use crate::systems::effects::blood::spawn_blood_particles;
+use crate::systems::effects::particles::{
+    spawn_particles, FadeMode, ParticleSpawnConfig, ScaleMode,
+};
+use crate::systems::input::action_map::{ActionInput, InputAction};
+use crate::systems::physics::spatial_index::SpatialIndex;
+use crate::systems::replay::UserAction;
 
 pub fn ignite_ragdoll_on_keypress(
     mut commands: Commands,
-    keyboard: Res<ButtonInput<KeyCode>>,
+    mut actions: EventWriter<UserAction>,
+    input: ActionInput,
+    tuning: Res<FireTuning>,
+    index: Res<SpatialIndex>,
     windows: Query<&Window>,
     camera_q: Query<(&Camera, &GlobalTransform)>,
     flammable_query: Query<(Entity, &Transform, &Flammable), Without<OnFire>>,
 ) {
-    if keyboard.just_pressed(FIRE_SPAWN_KEY) {
+    if input.just_pressed(InputAction::Ignite) {
         if let Some(world_pos) = get_cursor_world_position(&windows, &camera_q) {
-            ignite_at_position(&mut commands, world_pos, &flammable_query);
+            ignite_at_position(&mut commands, world_pos, &tuning, &index, &flammable_query);
+            actions.send(UserAction::Ignite { position: world_pos });
         }
     }
 }
@@ -24,27 +35,44 @@ pub fn ignite_ragdoll_on_keypress(
 pub fn spawn_fire_from_ui(
     commands: &mut Commands,
     position: Vec2,
+    tuning: &FireTuning,
+    index: &SpatialIndex,
     flammable_query: &Query<(Entity, &Transform, &Flammable), Without<OnFire>>,
 ) {
-    ignite_at_position(commands, position, flammable_query);
+    ignite_at_position(commands, position, tuning, index, flammable_query);
 }
 
 fn ignite_at_position(
     commands: &mut Commands,
     world_pos: Vec2,
+    tuning: &FireTuning,
+    index: &SpatialIndex,
     flammable_query: &Query<(Entity, &Transform, &Flammable), Without<OnFire>>,
 ) {
-    if let Some(entity) = find_closest_entity(flammable_query.iter(), world_pos, 100.0) {
-        commands.entity(entity).insert(OnFire {
-            intensity: 1.0,
-            duration: Timer::from_seconds(FIRE_DURATION, TimerMode::Once),
-        });
+    let is_flammable = |entity| flammable_query.contains(entity);
+    if let Some(entity) = index.closest(world_pos, 100.0, is_flammable) {
+        ignite_entity(commands, entity, tuning);
     }
 }
 
+/// Set a flammable body on fire at full intensity, as the user asked for it
+pub fn ignite_entity(commands: &mut Commands, entity: Entity, tuning: &FireTuning) {
+    let intensity = 1.0;
+    commands
+        .entity(entity)
+        .insert(OnFire::new(intensity, tuning.duration));
+    commands.send_event(Ignited {
+        entity,
+        intensity,
+        cause: IgnitionCause::User,
+    });
+}
+
 pub fn apply_fire_damage(
     mut commands: Commands,
+    mut destroyed: EventWriter<BodyDestroyed>,
     time: Res<Time>,
+    tuning: Res<FireTuning>,
     mut fire_query: Query<(
         Entity,
         &Transform,
@@ -55,15 +83,19 @@ pub fn apply_fire_damage(
 ) {
     for (entity, transform, mut on_fire, mut health, ragdoll_opt) in fire_query.iter_mut() {
         on_fire.duration.tick(time.delta());
+        on_fire.emit_timer.tick(time.delta());
 
-        let damage = FIRE_DAMAGE_PER_SEC * time.delta_secs() * on_fire.intensity;
+        let damage = tuning.damage_per_sec * time.delta_secs() * on_fire.intensity;
+        let was_alive = health.current > 0.0;
         health.current -= damage;
 
-        spawn_fire_particles(
-            &mut commands,
-            transform.translation.truncate(),
-            on_fire.intensity,
-        );
+        for _ in 0..on_fire.emit_timer.times_finished_this_tick() {
+            spawn_fire_particles(
+                &mut commands,
+                transform.translation.truncate(),
+                on_fire.intensity,
+            );
+        }
 
         if health.current <= 0.0 {
             if ragdoll_opt.is_some() {
@@ -74,6 +106,14 @@ pub fn apply_fire_damage(
                 );
             }
             commands.entity(entity).despawn();
+            // An explosion may have destroyed it earlier this frame
+            if was_alive {
+                destroyed.send(BodyDestroyed {
+                    entity,
+                    position: transform.translation.truncate(),
+                    cause: DamageCause::Fire,
+                });
+            }
         } else if on_fire.duration.finished() {
             commands.entity(entity).remove::<OnFire>();
         }
@@ -82,87 +122,76 @@ pub fn apply_fire_damage(
 
 pub fn spread_fire(
     mut commands: Commands,
+    mut ignited: EventWriter<Ignited>,
+    mut sim_rng: ResMut<SimRng>,
+    tuning: Res<FireTuning>,
+    index: Res<SpatialIndex>,
     fire_query: Query<(&Transform, &OnFire)>,
     flammable_query: Query<(Entity, &Transform, &Flammable), Without<OnFire>>,
 ) {
+    let rng = sim_rng.stream(RngStream::Fire);
+    // Several fires can reach the same body in one frame, and the last one sets its intensity
+    let mut caught_fire: Vec<(Entity, f32)> = Vec::new();
+
     for (fire_transform, on_fire) in fire_query.iter() {
         let fire_pos = fire_transform.translation.truncate();
 
-        for (entity, transform, _flammable) in flammable_query.iter() {
+        for (candidate, _) in index.candidates_in_radius(fire_pos, tuning.spread_radius) {
+            let Ok((entity, transform, _flammable)) = flammable_query.get(candidate) else {
+                continue;
+            };
             let pos = transform.translation.truncate();
             let distance = fire_pos.distance(pos);
 
-            if distance < FIRE_SPREAD_RADIUS {
+            if distance < tuning.spread_radius {
                 let spread_chance =
-                    (1.0 - distance / FIRE_SPREAD_RADIUS) * on_fire.intensity * 0.01;
-                if rand::thread_rng().gen::<f32>() < spread_chance {
-                    commands.entity(entity).insert(OnFire {
-                        intensity: on_fire.intensity * 0.8,
-                        duration: Timer::from_seconds(FIRE_DURATION, TimerMode::Once),
-                    });
+                    (1.0 - distance / tuning.spread_radius) * on_fire.intensity * 0.01;
+                if rng.random::<f32>() < spread_chance {
+                    let intensity = on_fire.intensity * 0.8;
+                    commands
+                        .entity(entity)
+                        .insert(OnFire::new(intensity, tuning.duration));
+                    caught_fire.retain(|&(other, _)| other != entity);
+                    caught_fire.push((entity, intensity));
                 }
             }
         }
     }
-}
 
-fn spawn_fire_particles(commands: &mut Commands, position: Vec2, intensity: f32) {
-    let mut rng = rand::thread_rng();
-
-    for _ in 0..(3.0 * intensity) as i32 {
-        let angle = rng.gen_range(0.0..std::f32::consts::TAU);
-        let speed = rng.gen_range(20.0..60.0);
-        let velocity = Vec2::new(angle.cos() * speed, rng.gen_range(40.0..100.0));
-
-        let size = rng.gen_range(4.0..10.0) * intensity;
-        let color_choice = rng.gen_range(0..3);
-        let color = match color_choice {
-            0 => Color::srgba(1.0, 0.9, 0.0, rng.gen_range(0.6..1.0)),
-            1 => Color::srgba(1.0, 0.5, 0.0, rng.gen_range(0.6..1.0)),
-            _ => Color::srgba(1.0, 0.2, 0.0, rng.gen_range(0.6..1.0)),
-        };
-
-        let offset = Vec2::new(rng.gen_range(-8.0..8.0), rng.gen_range(-8.0..8.0));
-
-        commands.spawn((
-            Sprite {
-                color,
-                custom_size: Some(Vec2::new(size, size)),
-                ..default()
-            },
-            Transform::from_xyz(position.x + offset.x, position.y + offset.y, 0.5),
-            FireParticle {
-                lifetime: Timer::from_seconds(rng.gen_range(0.3..0.8), TimerMode::Once),
-                velocity,
-            },
-        ));
+    for (entity, intensity) in caught_fire {
+        ignited.send(Ignited {
+            entity,
+            intensity,
+            cause: IgnitionCause::Spread,
+        });
     }
 }
 
-pub fn animate_fire_particles(
-    mut commands: Commands,
-    time: Res<Time>,
-    mut fire_query: Query<(Entity, &mut Transform, &mut Sprite, &mut FireParticle)>,
-) {
-    for (entity, mut transform, mut sprite, mut particle) in fire_query.iter_mut() {
-        particle.lifetime.tick(time.delta());
-
-        transform.translation.x += particle.velocity.x * time.delta_secs();
-        transform.translation.y += particle.velocity.y * time.delta_secs();
-
-        particle.velocity.y += 50.0 * time.delta_secs();
-        particle.velocity *= 0.95;
-
-        let progress = particle.lifetime.fraction();
-        let scale = 1.0 + progress * 0.5;
-        transform.scale = Vec3::splat(scale);
-
-        let Srgba { alpha, .. } = sprite.color.to_srgba();
-        let new_alpha = (1.0 - progress) * alpha;
-        set_sprite_alpha(&mut sprite, new_alpha);
-
-        if particle.lifetime.finished() {
-            commands.entity(entity).despawn();
-        }
-    }
+fn spawn_fire_particles(commands: &mut Commands, position: Vec2, intensity: f32) {
+    spawn_particles(
+        commands,
+        ParticleSpawnConfig {
+            count: (3.0 * intensity) as usize,
+            position,
+            offset_range: Vec2::splat(8.0),
+            z_index: 0.5,
+            size_range: (4.0 * intensity, 10.0 * intensity),
+            speed_range: (20.0, 60.0),
+            lifetime_range: (0.3, 0.8),
+            color_fn: Box::new(|rng| match rng.random_range(0..3) {
+                0 => Color::srgb(1.0, 0.9, 0.0),
+                1 => Color::srgb(1.0, 0.5, 0.0),
+                _ => Color::srgb(1.0, 0.2, 0.0),
+            }),
+            // Flickering sideways while rising
+            velocity_fn: Box::new(|rng, speed| {
+                let angle = rng.random_range(0.0..std::f32::consts::TAU);
+                Vec2::new(angle.cos() * speed, rng.random_range(40.0..100.0))
+            }),
+            gravity: 50.0,
+            drag: 0.95,
+            fade_mode: FadeMode::Constant(0.8),
+            scale_mode: ScaleMode::GrowLinear(1.5),
+        },
+    );
 }
@@ -5,8 +5,12 @@ pub mod blood;
 pub mod combustion;
 pub mod particles;
 
-pub use animation::{animate_explosion_flash, animate_explosion_shockwave, animate_smoke_particles};
-pub use blood::animate_blood_particles;
+pub use animation::{animate_explosion_flash, animate_explosion_shockwave};
+pub use blood::spawn_blood_particles;
 pub use combustion::{
-    animate_fire_particles, apply_fire_damage, ignite_ragdoll_on_keypress, spread_fire, spawn_fire_from_ui,
+    apply_fire_damage, ignite_entity, ignite_ragdoll_on_keypress, spread_fire, spawn_fire_from_ui,
+};
+pub use particles::{
+    animate_particles, emit_particles, spawn_particles, EmitParticles, Particle, ParticlePool,
+    ParticleSpawnConfig,
 };
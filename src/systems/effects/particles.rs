@@ -1,6 +1,17 @@
+//! Pooled sprite particles shared by every visual effect
+//!
+//! Effects describe a burst with a `ParticleSpawnConfig` and hand it to
+//! `spawn_particles`, which only sends an `EmitParticles` event, so any system
+//! with `Commands` can emit. `emit_particles` then places the burst on pooled
+//! entities: expired particles are hidden and reused instead of despawned.
+//! The number of live particles is capped by `ParticleTuning::max_particles`;
+//! past half the budget bursts are thinned out evenly, and at the budget new
+//! bursts are dropped, so heavy scenes lose detail instead of frames.
+
 use bevy::prelude::*;
 use rand::Rng;
 
+use crate::core::tuning::ParticleTuning;
 use crate::core::utils::set_sprite_alpha;
 
 #[derive(Component)]
@@ -24,12 +35,72 @@ pub enum ScaleMode {
     ShrinkLinear(f32),
 }
 
+/// One particle of a burst, with its random values already drawn
+#[derive(Clone)]
+pub struct ParticleSpec {
+    pub position: Vec3,
+    pub size: f32,
+    pub color: Color,
+    pub velocity: Vec2,
+    pub lifetime: f32,
+    pub gravity: f32,
+    pub drag: f32,
+    pub fade_mode: FadeMode,
+    pub scale_mode: ScaleMode,
+}
+
+/// Request to show a burst of particles, sent by `spawn_particles`
+#[derive(Event)]
+pub struct EmitParticles {
+    pub particles: Vec<ParticleSpec>,
+}
+
+/// Hidden particle entities waiting to be reused, and how many are showing
+#[derive(Resource, Default)]
+pub struct ParticlePool {
+    free: Vec<Entity>,
+    live: usize,
+    /// Particles left out of bursts to stay within the budget, since startup
+    pub dropped: u64,
+}
+
+impl ParticlePool {
+    pub fn live(&self) -> usize {
+        self.live
+    }
+
+    /// How many of `requested` particles fit: all of them up to half the
+    /// budget, then a share shrinking to none at the budget
+    fn allowance(&self, requested: usize, max_particles: usize) -> usize {
+        let headroom = max_particles.saturating_sub(self.live);
+        let soft_limit = max_particles / 2;
+        if self.live < soft_limit {
+            return requested.min(headroom);
+        }
+
+        let share = headroom as f32 / (max_particles - soft_limit).max(1) as f32;
+        ((requested as f32 * share).ceil() as usize).min(headroom)
+    }
+}
+
 pub fn animate_particles(
-    mut commands: Commands,
     time: Res<Time>,
-    mut query: Query<(Entity, &mut Particle, &mut Transform, &mut Sprite)>,
+    mut pool: ResMut<ParticlePool>,
+    mut query: Query<(
+        Entity,
+        &mut Particle,
+        &mut Transform,
+        &mut Sprite,
+        &mut Visibility,
+    )>,
 ) {
-    for (entity, mut particle, mut transform, mut sprite) in query.iter_mut() {
+    let mut live = 0;
+
+    for (entity, mut particle, mut transform, mut sprite, mut visibility) in query.iter_mut() {
+        if *visibility == Visibility::Hidden {
+            continue;
+        }
+
         particle.lifetime.tick(time.delta());
 
         transform.translation += particle.velocity.extend(0.0) * time.delta_secs();
@@ -64,14 +135,70 @@ pub fn animate_particles(
         }
 
         if particle.lifetime.finished() {
-            commands.entity(entity).despawn();
+            *visibility = Visibility::Hidden;
+            pool.free.push(entity);
+        } else {
+            live += 1;
+        }
+    }
+
+    pool.live = live;
+}
+
+/// Show requested bursts on pooled entities, spawning more only while the pool is short
+pub fn emit_particles(
+    mut commands: Commands,
+    mut events: EventReader<EmitParticles>,
+    mut pool: ResMut<ParticlePool>,
+    tuning: Res<ParticleTuning>,
+    mut query: Query<(&mut Particle, &mut Transform, &mut Sprite, &mut Visibility)>,
+) {
+    for event in events.read() {
+        let requested = event.particles.len();
+        let allowed = pool.allowance(requested, tuning.max_particles);
+        pool.dropped += (requested - allowed) as u64;
+
+        // Thin the burst evenly, so it keeps its shape with fewer particles
+        for kept in 0..allowed {
+            let spec = &event.particles[kept * requested / allowed];
+            let particle = Particle {
+                lifetime: Timer::from_seconds(spec.lifetime, TimerMode::Once),
+                velocity: spec.velocity,
+                gravity: spec.gravity,
+                drag: spec.drag,
+                fade_mode: spec.fade_mode.clone(),
+                scale_mode: spec.scale_mode.clone(),
+            };
+            let sprite = Sprite {
+                color: spec.color,
+                custom_size: Some(Vec2::splat(spec.size)),
+                ..default()
+            };
+            let transform = Transform::from_translation(spec.position);
+
+            let reused = pool.free.pop().and_then(|entity| query.get_mut(entity).ok());
+            match reused {
+                Some((mut pooled, mut pooled_transform, mut pooled_sprite, mut visibility)) => {
+                    *pooled = particle;
+                    *pooled_transform = transform;
+                    *pooled_sprite = sprite;
+                    *visibility = Visibility::Inherited;
+                }
+                None => {
+                    commands.spawn((sprite, transform, particle));
+                }
+            }
         }
+
+        pool.live += allowed;
     }
 }
 
 pub struct ParticleSpawnConfig {
     pub count: usize,
     pub position: Vec2,
+    /// Particles start up to this far from `position` on each axis
+    pub offset_range: Vec2,
     pub z_index: f32,
     pub size_range: (f32, f32),
     pub speed_range: (f32, f32),
@@ -89,13 +216,14 @@ impl Default for ParticleSpawnConfig {
         Self {
             count: 10,
             position: Vec2::ZERO,
+            offset_range: Vec2::ZERO,
             z_index: 0.0,
             size_range: (3.0, 8.0),
             speed_range: (50.0, 150.0),
             lifetime_range: (1.0, 2.0),
             color_fn: Box::new(|_| Color::WHITE),
             velocity_fn: Box::new(|rng, speed| {
-                let angle = rng.gen_range(0.0..std::f32::consts::TAU);
+                let angle = rng.random_range(0.0..std::f32::consts::TAU);
                 Vec2::new(angle.cos(), angle.sin()) * speed
             }),
             gravity: -400.0,
@@ -106,32 +234,41 @@ impl Default for ParticleSpawnConfig {
     }
 }
 
+/// Emit a burst; it appears once `emit_particles` has run, and may be thinned to fit the budget
 pub fn spawn_particles(commands: &mut Commands, config: ParticleSpawnConfig) {
-    let mut rng = rand::thread_rng();
+    let mut rng = rand::rng();
 
-    for _ in 0..config.count {
-        let size = rng.gen_range(config.size_range.0..config.size_range.1);
-        let speed = rng.gen_range(config.speed_range.0..config.speed_range.1);
-        let lifetime = rng.gen_range(config.lifetime_range.0..config.lifetime_range.1);
+    let particles = (0..config.count)
+        .map(|_| {
+            let size = rng.random_range(config.size_range.0..config.size_range.1);
+            let speed = rng.random_range(config.speed_range.0..config.speed_range.1);
+            let lifetime = rng.random_range(config.lifetime_range.0..config.lifetime_range.1);
 
-        let color = (config.color_fn)(&mut rng);
-        let velocity = (config.velocity_fn)(&mut rng, speed);
+            let color = (config.color_fn)(&mut rng);
+            let velocity = (config.velocity_fn)(&mut rng, speed);
 
-        commands.spawn((
-            Sprite {
+            let offset = if config.offset_range == Vec2::ZERO {
+                Vec2::ZERO
+            } else {
+                Vec2::new(
+                    rng.random_range(-config.offset_range.x..=config.offset_range.x),
+                    rng.random_range(-config.offset_range.y..=config.offset_range.y),
+                )
+            };
+
+            ParticleSpec {
+                position: (config.position + offset).extend(config.z_index),
+                size,
                 color,
-                custom_size: Some(Vec2::new(size, size)),
-                ..default()
-            },
-            Transform::from_xyz(config.position.x, config.position.y, config.z_index),
-            Particle {
-                lifetime: Timer::from_seconds(lifetime, TimerMode::Once),
                 velocity,
+                lifetime,
                 gravity: config.gravity,
                 drag: config.drag,
                 fade_mode: config.fade_mode.clone(),
                 scale_mode: config.scale_mode.clone(),
-            },
-        ));
-    }
+            }
+        })
+        .collect();
+
+    commands.send_event(EmitParticles { particles });
 }
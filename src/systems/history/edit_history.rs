@@ -0,0 +1,146 @@
+//! Undo/redo stacks for editing actions
+//!
+//! Every entry is the inverse of something the user did: spawning a ragdoll
+//! records a `Despawn` of its parts, dragging a body records a `MoveTo` back to
+//! where it was grabbed. Applying an entry produces its own inverse, which goes
+//! onto the opposite stack, so undo and redo share one code path.
+
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+use bevy_rapier2d::prelude::ImpulseJoint;
+
+use crate::core::components::Connection;
+use crate::systems::damage::connection::ConnectionMaterial;
+use crate::systems::damage::damage::JointHealth;
+use crate::systems::persistence::scene::SandboxScene;
+
+/// Oldest entries are dropped once the undo stack grows past this
+const HISTORY_LIMIT: usize = 100;
+
+/// Request to revert the most recent edit
+#[derive(Event)]
+pub struct UndoEvent;
+
+/// Request to reapply the most recently undone edit
+#[derive(Event)]
+pub struct RedoEvent;
+
+/// Request to remove bodies in a way that can be undone
+#[derive(Event)]
+pub struct DeleteBodiesEvent {
+    pub bodies: Vec<Entity>,
+}
+
+#[derive(Clone)]
+pub enum EditCommand {
+    /// Remove bodies, together with their joints and connections
+    Despawn(Vec<Entity>),
+    /// Rebuild removed bodies. `entities` are the ids they had before removal,
+    /// in the same order as `scene.bodies`. `connections` and `joints` linked
+    /// them to bodies that were not removed, and are restored along with them.
+    Respawn {
+        scene: SandboxScene,
+        entities: Vec<Entity>,
+        connections: Vec<(Connection, ConnectionMaterial)>,
+        joints: Vec<RagdollLink>,
+    },
+    /// Put a body back at `position`
+    MoveTo { body: Entity, position: Vec2 },
+    /// Put a group of bodies back at these positions and rotations
+    Arrange(Vec<(Entity, Transform)>),
+    /// Remove a user-created connection
+    Disconnect(Connection),
+    /// Recreate a user-created connection
+    Reconnect(Connection, ConnectionMaterial),
+}
+
+/// Ragdoll joint held by `child`, as it was before one of its ends was removed
+#[derive(Clone)]
+pub struct RagdollLink {
+    pub child: Entity,
+    pub joint: ImpulseJoint,
+    pub health: Option<JointHealth>,
+}
+
+impl RagdollLink {
+    pub(crate) fn remap(&mut self, map: &HashMap<Entity, Entity>) {
+        let remap = |entity: &mut Entity| {
+            if let Some(&new_entity) = map.get(entity) {
+                *entity = new_entity;
+            }
+        };
+
+        remap(&mut self.child);
+        remap(&mut self.joint.parent);
+        if let Some(health) = self.health.as_mut() {
+            remap(&mut health.parent_entity);
+        }
+    }
+}
+
+impl EditCommand {
+    fn remap(&mut self, map: &HashMap<Entity, Entity>) {
+        let remap = |entity: &mut Entity| {
+            if let Some(&new_entity) = map.get(entity) {
+                *entity = new_entity;
+            }
+        };
+
+        match self {
+            EditCommand::Despawn(entities) => entities.iter_mut().for_each(remap),
+            EditCommand::Respawn {
+                entities,
+                connections,
+                joints,
+                ..
+            } => {
+                entities.iter_mut().for_each(remap);
+                for (connection, _) in connections {
+                    remap(&mut connection.a);
+                    remap(&mut connection.b);
+                }
+                for joint in joints {
+                    joint.remap(map);
+                }
+            }
+            EditCommand::MoveTo { body, .. } => remap(body),
+            EditCommand::Arrange(bodies) => {
+                bodies.iter_mut().for_each(|(body, _)| remap(body));
+            }
+            EditCommand::Disconnect(connection) | EditCommand::Reconnect(connection, _) => {
+                remap(&mut connection.a);
+                remap(&mut connection.b);
+            }
+        }
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct EditHistory {
+    pub(crate) undo: Vec<EditCommand>,
+    pub(crate) redo: Vec<EditCommand>,
+}
+
+impl EditHistory {
+    /// Record the inverse of an edit the user just made
+    pub fn record(&mut self, inverse: EditCommand) {
+        self.redo.clear();
+        self.undo.push(inverse);
+
+        if self.undo.len() > HISTORY_LIMIT {
+            self.undo.remove(0);
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+    }
+
+    /// Point every entry at the new ids of bodies that were despawned and rebuilt
+    pub(crate) fn remap(&mut self, map: &HashMap<Entity, Entity>) {
+        for command in self.undo.iter_mut().chain(self.redo.iter_mut()) {
+            command.remap(map);
+        }
+    }
+}
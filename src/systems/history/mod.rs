@@ -0,0 +1,7 @@
+//! Undo/redo history for spawning, moving, connecting and deleting bodies
+
+pub mod edit_history;
+pub mod undo;
+
+pub use edit_history::{DeleteBodiesEvent, EditCommand, EditHistory, RedoEvent, UndoEvent};
+pub use undo::{apply_undo_redo, delete_bodies, undo_redo_hotkeys};
@@ -0,0 +1,316 @@
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+use bevy_rapier2d::prelude::*;
+
+use crate::core::components::{Connection, RagdollPart};
+use crate::core::tuning::ConnectionTuning;
+use crate::systems::damage::connection::{spawn_connection, DragConnectionState, JointMaterial};
+use crate::systems::damage::damage::{Fractured, JointHealth};
+use crate::systems::history::edit_history::{
+    DeleteBodiesEvent, EditCommand, EditHistory, RagdollLink, RedoEvent, UndoEvent,
+};
+use crate::systems::input::action_map::{ActionInput, InputAction};
+use crate::systems::input::drag::{DragState, Draggable};
+use crate::systems::persistence::scene_io::{spawn_scene, SceneCapture};
+use crate::systems::replay::ReplayState;
+
+pub fn undo_redo_hotkeys(
+    input: ActionInput,
+    mut undo_events: EventWriter<UndoEvent>,
+    mut redo_events: EventWriter<RedoEvent>,
+) {
+    if input.just_pressed(InputAction::Undo) {
+        undo_events.send(UndoEvent);
+    } else if input.just_pressed(InputAction::Redo) {
+        redo_events.send(RedoEvent);
+    }
+}
+
+/// Access to the sandbox needed to apply history entries
+#[derive(SystemParam)]
+pub struct HistoryEditor<'w, 's> {
+    commands: Commands<'w, 's>,
+    capture: SceneCapture<'w, 's>,
+    transforms: Query<'w, 's, &'static Transform, With<Draggable>>,
+    connections: Query<'w, 's, (Entity, &'static Connection, Option<&'static JointMaterial>)>,
+    ragdoll_joints: Query<
+        'w,
+        's,
+        (Entity, &'static ImpulseJoint, Option<&'static JointHealth>),
+        With<RagdollPart>,
+    >,
+    connection_tuning: Res<'w, ConnectionTuning>,
+    // Only present when the input plugin is
+    drag_state: Option<ResMut<'w, DragState>>,
+    connection_drag: Option<ResMut<'w, DragConnectionState>>,
+}
+
+impl HistoryEditor<'_, '_> {
+    /// Drop drags that were holding on to removed bodies
+    fn forget_removed(&mut self, entities: &[Entity]) {
+        if let Some(drag_state) = self.drag_state.as_mut() {
+            if drag_state
+                .dragging_entity
+                .is_some_and(|entity| entities.contains(&entity))
+            {
+                drag_state.end();
+            }
+        }
+
+        // The drag line stays until the button is released, but connects nothing
+        if let Some(connection_drag) = self.connection_drag.as_mut() {
+            if connection_drag
+                .start_entity
+                .is_some_and(|entity| entities.contains(&entity))
+            {
+                connection_drag.start_entity = None;
+            }
+        }
+    }
+
+    /// Apply `command` and return its inverse, or `None` if the bodies it refers
+    /// to no longer exist
+    fn apply(&mut self, command: EditCommand, history: &mut EditHistory) -> Option<EditCommand> {
+        match command {
+            EditCommand::Despawn(entities) => {
+                let (scene, entities) = self
+                    .capture
+                    .capture_with_entities(|entity| entities.contains(&entity));
+                if entities.is_empty() {
+                    return None;
+                }
+
+                // Links to bodies that stay are not part of the scene, so they are
+                // kept separately for the undo to restore
+                let connections: Vec<_> = self
+                    .connections
+                    .iter()
+                    .filter(|(_, connection, _)| {
+                        entities.contains(&connection.a) != entities.contains(&connection.b)
+                    })
+                    .map(|(_, connection, material)| {
+                        let material = material.map(|material| material.0).unwrap_or_default();
+                        (connection.clone(), material)
+                    })
+                    .collect();
+                let joints: Vec<_> = self
+                    .ragdoll_joints
+                    .iter()
+                    .filter(|(child, joint, _)| {
+                        entities.contains(child) != entities.contains(&joint.parent)
+                    })
+                    .map(|(child, joint, health)| RagdollLink {
+                        child,
+                        joint: *joint,
+                        health: health.cloned(),
+                    })
+                    .collect();
+
+                // Connections are children of their first body, so this removes them too
+                for &entity in &entities {
+                    self.commands.entity(entity).despawn_recursive();
+                }
+                // Connections hanging off a removed second body go with it, rather
+                // than being left for `handle_despawned_connected_entities`
+                for (joint, connection, _) in self.connections.iter() {
+                    if entities.contains(&connection.b) && !entities.contains(&connection.a) {
+                        self.commands.entity(joint).despawn_recursive();
+                    }
+                }
+                // Likewise for ragdoll joints whose parent was removed
+                for link in &joints {
+                    if !entities.contains(&link.child) {
+                        self.commands
+                            .entity(link.child)
+                            .remove::<(ImpulseJoint, JointHealth, Fractured)>();
+                    }
+                }
+                self.forget_removed(&entities);
+
+                Some(EditCommand::Respawn {
+                    scene,
+                    entities,
+                    connections,
+                    joints,
+                })
+            }
+            EditCommand::Respawn {
+                scene,
+                entities,
+                mut connections,
+                mut joints,
+            } => {
+                let spawned = spawn_scene(
+                    &mut self.commands,
+                    &scene,
+                    Vec2::ZERO,
+                    &self.connection_tuning,
+                );
+                let map: HashMap<Entity, Entity> =
+                    entities.into_iter().zip(spawned.iter().copied()).collect();
+                history.remap(&map);
+
+                for (connection, _) in &mut connections {
+                    connection.a = map.get(&connection.a).copied().unwrap_or(connection.a);
+                    connection.b = map.get(&connection.b).copied().unwrap_or(connection.b);
+                }
+                for joint in &mut joints {
+                    joint.remap(&map);
+                }
+                let survives =
+                    |entity: Entity| spawned.contains(&entity) || self.transforms.contains(entity);
+
+                for (connection, material) in connections {
+                    if survives(connection.a) && survives(connection.b) {
+                        spawn_connection(
+                            &mut self.commands,
+                            connection,
+                            material,
+                            &self.connection_tuning,
+                        );
+                    }
+                }
+                for RagdollLink {
+                    child,
+                    joint,
+                    health,
+                } in joints
+                {
+                    if !survives(child) || !survives(joint.parent) {
+                        continue;
+                    }
+
+                    let mut child_commands = self.commands.entity(child);
+                    child_commands.insert(joint);
+                    if let Some(health) = health {
+                        if health.current < health.max * 0.5 {
+                            child_commands.insert(Fractured {
+                                severity: 1.0 - (health.current / health.max),
+                            });
+                        }
+                        child_commands.insert(health);
+                    }
+                }
+
+                Some(EditCommand::Despawn(spawned))
+            }
+            EditCommand::MoveTo { body, position } => {
+                let transform = self.transforms.get(body).ok()?;
+                let previous_position = transform.translation.truncate();
+
+                let mut moved = *transform;
+                moved.translation.x = position.x;
+                moved.translation.y = position.y;
+                self.commands
+                    .entity(body)
+                    .try_insert((moved, Velocity::zero()));
+
+                Some(EditCommand::MoveTo {
+                    body,
+                    position: previous_position,
+                })
+            }
+            EditCommand::Arrange(bodies) => {
+                let mut previous = Vec::with_capacity(bodies.len());
+                for (body, transform) in bodies {
+                    let Ok(current) = self.transforms.get(body) else {
+                        continue;
+                    };
+                    previous.push((body, *current));
+                    self.commands
+                        .entity(body)
+                        .try_insert((transform, Velocity::zero()));
+                }
+
+                if previous.is_empty() {
+                    return None;
+                }
+                Some(EditCommand::Arrange(previous))
+            }
+            EditCommand::Disconnect(connection) => {
+                let (joint, _, material) = self.connections.iter().find(|(_, existing, _)| {
+                    existing.a == connection.a
+                        && existing.b == connection.b
+                        && existing.anchor_a == connection.anchor_a
+                        && existing.anchor_b == connection.anchor_b
+                })?;
+
+                self.commands.entity(joint).despawn_recursive();
+
+                let material = material.map(|material| material.0).unwrap_or_default();
+                Some(EditCommand::Reconnect(connection, material))
+            }
+            EditCommand::Reconnect(connection, material) => {
+                if !self.transforms.contains(connection.a) || !self.transforms.contains(connection.b) {
+                    return None;
+                }
+
+                spawn_connection(
+                    &mut self.commands,
+                    connection.clone(),
+                    material,
+                    &self.connection_tuning,
+                );
+
+                Some(EditCommand::Disconnect(connection))
+            }
+        }
+    }
+}
+
+/// Apply at most one undo or redo per frame, skipping entries whose bodies are gone
+pub fn apply_undo_redo(
+    mut undo_events: EventReader<UndoEvent>,
+    mut redo_events: EventReader<RedoEvent>,
+    mut history: ResMut<EditHistory>,
+    mut editor: HistoryEditor,
+    replay_state: Res<ReplayState>,
+) {
+    let undo = undo_events.read().count() > 0;
+    let redo = redo_events.read().count() > 0;
+    if !undo && !redo {
+        return;
+    }
+
+    // Edits made through the history are not part of the recorded actions
+    if !matches!(*replay_state, ReplayState::Idle) {
+        warn!("Undo and redo are disabled while recording or playing back a replay");
+        return;
+    }
+
+    loop {
+        let command = if undo {
+            history.undo.pop()
+        } else {
+            history.redo.pop()
+        };
+        let Some(command) = command else {
+            break;
+        };
+        let Some(inverse) = editor.apply(command, &mut history) else {
+            continue;
+        };
+
+        if undo {
+            history.redo.push(inverse);
+        } else {
+            history.undo.push(inverse);
+        }
+        break;
+    }
+}
+
+pub fn delete_bodies(
+    mut delete_events: EventReader<DeleteBodiesEvent>,
+    mut history: ResMut<EditHistory>,
+    mut editor: HistoryEditor,
+) {
+    for event in delete_events.read() {
+        if let Some(inverse) =
+            editor.apply(EditCommand::Despawn(event.bodies.clone()), &mut history)
+        {
+            history.record(inverse);
+        }
+    }
+}
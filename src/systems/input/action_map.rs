@@ -0,0 +1,574 @@
+//! Named input actions and the keys or mouse buttons bound to them
+//!
+//! Gameplay systems ask `ActionInput` whether an action was triggered instead
+//! of reading `KeyCode`s and `MouseButton`s directly, so every control can be
+//! rebound. Bindings are stored in a RON file next to the executable and
+//! edited from the in-game bindings screen. Spawn hotkeys are bound per
+//! registered spawnable, by its id, and default to the key it declares.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::core::constants::BINDINGS_FILE_PATH;
+use crate::entities::spawnable::SpawnableRegistry;
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Serialize, Deserialize)]
+pub enum InputAction {
+    Ignite,
+    /// Grab and move a body
+    Drag,
+    /// Place the object selected in the top bar, or drag out a box or block
+    Place,
+    /// Draw a connection between two bodies while a constraint tool is selected
+    Connect,
+    Undo,
+    Redo,
+    ToggleRecording,
+    TogglePlayback,
+    ToggleBindingsScreen,
+    TogglePause,
+    /// Advance one frame while paused
+    StepFrame,
+    SpeedUp,
+    SlowDown,
+    /// Hold to drag the view around
+    PanCamera,
+    /// Follow the body under the cursor, or stop following
+    FollowBody,
+    ResetCamera,
+    /// Show the body under the cursor in the inspector panel
+    Inspect,
+    RotateSelectionLeft,
+    RotateSelectionRight,
+    /// Pin the selected bodies in place, or release them
+    FreezeSelection,
+    IgniteSelection,
+    DeleteSelection,
+    Copy,
+    Paste,
+    /// Copy the selection and paste it next to the original in one go
+    Duplicate,
+    /// Switch the pointer between bodies and editing the level's terrain
+    ToggleLevelEditor,
+    /// Take back the last point of the polygon being drawn
+    RemovePolygonPoint,
+    /// Open or close the menu of physics debug overlays
+    ToggleDebugMenu,
+}
+
+impl InputAction {
+    pub const ALL: [InputAction; 28] = [
+        InputAction::Ignite,
+        InputAction::Drag,
+        InputAction::Place,
+        InputAction::Connect,
+        InputAction::Undo,
+        InputAction::Redo,
+        InputAction::ToggleRecording,
+        InputAction::TogglePlayback,
+        InputAction::ToggleBindingsScreen,
+        InputAction::TogglePause,
+        InputAction::StepFrame,
+        InputAction::SpeedUp,
+        InputAction::SlowDown,
+        InputAction::PanCamera,
+        InputAction::FollowBody,
+        InputAction::ResetCamera,
+        InputAction::Inspect,
+        InputAction::RotateSelectionLeft,
+        InputAction::RotateSelectionRight,
+        InputAction::FreezeSelection,
+        InputAction::IgniteSelection,
+        InputAction::DeleteSelection,
+        InputAction::Copy,
+        InputAction::Paste,
+        InputAction::Duplicate,
+        InputAction::ToggleLevelEditor,
+        InputAction::RemovePolygonPoint,
+        InputAction::ToggleDebugMenu,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            InputAction::Ignite => "Ignite",
+            InputAction::Drag => "Drag body",
+            InputAction::Place => "Place object",
+            InputAction::Connect => "Connect bodies",
+            InputAction::Undo => "Undo",
+            InputAction::Redo => "Redo",
+            InputAction::ToggleRecording => "Record replay",
+            InputAction::TogglePlayback => "Play replay",
+            InputAction::ToggleBindingsScreen => "Key bindings",
+            InputAction::TogglePause => "Pause",
+            InputAction::StepFrame => "Step frame",
+            InputAction::SpeedUp => "Speed up",
+            InputAction::SlowDown => "Slow down",
+            InputAction::PanCamera => "Pan camera",
+            InputAction::FollowBody => "Follow body",
+            InputAction::ResetCamera => "Reset camera",
+            InputAction::Inspect => "Inspect body",
+            InputAction::RotateSelectionLeft => "Rotate selection left",
+            InputAction::RotateSelectionRight => "Rotate selection right",
+            InputAction::FreezeSelection => "Freeze selection",
+            InputAction::IgniteSelection => "Ignite selection",
+            InputAction::DeleteSelection => "Delete selection",
+            InputAction::Copy => "Copy selection",
+            InputAction::Paste => "Paste",
+            InputAction::Duplicate => "Duplicate selection",
+            InputAction::ToggleLevelEditor => "Level editor",
+            InputAction::RemovePolygonPoint => "Remove polygon point",
+            InputAction::ToggleDebugMenu => "Debug menu",
+        }
+    }
+
+    /// Pointer tools are told apart by the tool selected in the top bar, so
+    /// they may share a button with each other without conflicting
+    pub fn is_pointer_tool(&self) -> bool {
+        matches!(self, InputAction::Drag | InputAction::Place | InputAction::Connect)
+    }
+}
+
+/// Whatever a binding triggers: a built-in action, or spawning the registered
+/// spawnable with this id
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum BindingTarget {
+    Action(InputAction),
+    Spawn(String),
+}
+
+impl BindingTarget {
+    pub fn label(&self, registry: &SpawnableRegistry) -> String {
+        match self {
+            BindingTarget::Action(action) => action.label().to_string(),
+            BindingTarget::Spawn(id) => match registry.find(id) {
+                Some(spawnable) => format!("Spawn {}", spawnable.name.to_lowercase()),
+                None => format!("Spawn {id}"),
+            },
+        }
+    }
+
+    fn is_pointer_tool(&self) -> bool {
+        matches!(self, BindingTarget::Action(action) if action.is_pointer_tool())
+    }
+
+    fn conflicts_with(&self, other: &BindingTarget) -> bool {
+        self != other && !(self.is_pointer_tool() && other.is_pointer_tool())
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum InputButton {
+    Key(KeyCode),
+    Mouse(MouseButton),
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct InputBinding {
+    pub button: InputButton,
+    /// Whether Ctrl has to be held when the button is pressed
+    #[serde(default)]
+    pub ctrl: bool,
+}
+
+impl InputBinding {
+    pub const fn key(key: KeyCode) -> Self {
+        Self {
+            button: InputButton::Key(key),
+            ctrl: false,
+        }
+    }
+
+    pub const fn ctrl_key(key: KeyCode) -> Self {
+        Self {
+            button: InputButton::Key(key),
+            ctrl: true,
+        }
+    }
+
+    pub const fn mouse(button: MouseButton) -> Self {
+        Self {
+            button: InputButton::Mouse(button),
+            ctrl: false,
+        }
+    }
+}
+
+impl fmt::Display for InputBinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.ctrl {
+            write!(f, "Ctrl+")?;
+        }
+
+        match self.button {
+            InputButton::Key(key) => {
+                let name = format!("{key:?}");
+                let name = name
+                    .strip_prefix("Key")
+                    .or_else(|| name.strip_prefix("Digit"))
+                    .unwrap_or(&name);
+                write!(f, "{name}")
+            }
+            InputButton::Mouse(button) => write!(f, "Mouse {button:?}"),
+        }
+    }
+}
+
+#[derive(Resource, Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct ActionMap {
+    bindings: BTreeMap<InputAction, InputBinding>,
+    /// Spawn hotkeys, by spawnable id
+    #[serde(default)]
+    spawn_bindings: BTreeMap<String, InputBinding>,
+}
+
+impl Default for ActionMap {
+    fn default() -> Self {
+        let bindings = InputAction::ALL
+            .into_iter()
+            .map(|action| {
+                let binding = match action {
+                    InputAction::Ignite => InputBinding::key(KeyCode::KeyF),
+                    InputAction::Drag | InputAction::Place | InputAction::Connect => {
+                        InputBinding::mouse(MouseButton::Left)
+                    }
+                    InputAction::Undo => InputBinding::ctrl_key(KeyCode::KeyZ),
+                    InputAction::Redo => InputBinding::ctrl_key(KeyCode::KeyY),
+                    InputAction::ToggleRecording => InputBinding::key(KeyCode::F5),
+                    InputAction::TogglePlayback => InputBinding::key(KeyCode::F6),
+                    InputAction::ToggleBindingsScreen => InputBinding::key(KeyCode::F1),
+                    InputAction::TogglePause => InputBinding::key(KeyCode::Space),
+                    InputAction::StepFrame => InputBinding::key(KeyCode::Period),
+                    InputAction::SpeedUp => InputBinding::key(KeyCode::BracketRight),
+                    InputAction::SlowDown => InputBinding::key(KeyCode::BracketLeft),
+                    InputAction::PanCamera => InputBinding::mouse(MouseButton::Middle),
+                    InputAction::FollowBody => InputBinding::key(KeyCode::KeyT),
+                    InputAction::ResetCamera => InputBinding::key(KeyCode::Home),
+                    InputAction::Inspect => InputBinding::mouse(MouseButton::Right),
+                    InputAction::RotateSelectionLeft => InputBinding::key(KeyCode::KeyQ),
+                    InputAction::RotateSelectionRight => InputBinding::key(KeyCode::KeyE),
+                    InputAction::FreezeSelection => InputBinding::key(KeyCode::KeyG),
+                    InputAction::IgniteSelection => InputBinding::ctrl_key(KeyCode::KeyF),
+                    InputAction::DeleteSelection => InputBinding::key(KeyCode::Delete),
+                    InputAction::Copy => InputBinding::ctrl_key(KeyCode::KeyC),
+                    InputAction::Paste => InputBinding::ctrl_key(KeyCode::KeyV),
+                    InputAction::Duplicate => InputBinding::ctrl_key(KeyCode::KeyD),
+                    InputAction::ToggleLevelEditor => InputBinding::key(KeyCode::F2),
+                    InputAction::RemovePolygonPoint => InputBinding::key(KeyCode::Backspace),
+                    InputAction::ToggleDebugMenu => InputBinding::key(KeyCode::F3),
+                };
+                (action, binding)
+            })
+            .collect();
+
+        Self {
+            bindings,
+            spawn_bindings: BTreeMap::new(),
+        }
+    }
+}
+
+impl ActionMap {
+    pub fn binding(&self, action: InputAction) -> InputBinding {
+        self.bindings[&action]
+    }
+
+    /// The hotkey of the spawnable with this id, if it has one
+    pub fn spawn_binding(&self, id: &str) -> Option<InputBinding> {
+        self.spawn_bindings.get(id).copied()
+    }
+
+    pub fn target_binding(&self, target: &BindingTarget) -> Option<InputBinding> {
+        match target {
+            BindingTarget::Action(action) => Some(self.binding(*action)),
+            BindingTarget::Spawn(id) => self.spawn_binding(id),
+        }
+    }
+
+    /// Give every registered spawnable that is not bound yet its default hotkey
+    pub fn add_spawn_defaults(&mut self, registry: &SpawnableRegistry) {
+        for (_, spawnable) in registry.iter() {
+            if let Some(hotkey) = spawnable.hotkey {
+                self.spawn_bindings
+                    .entry(spawnable.id.to_string())
+                    .or_insert(hotkey);
+            }
+        }
+    }
+
+    /// Every bound target with its binding, actions first
+    fn targets(&self) -> impl Iterator<Item = (BindingTarget, InputBinding)> + '_ {
+        let actions = self
+            .bindings
+            .iter()
+            .map(|(&action, &binding)| (BindingTarget::Action(action), binding));
+        let spawns = self
+            .spawn_bindings
+            .iter()
+            .map(|(id, &binding)| (BindingTarget::Spawn(id.clone()), binding));
+        actions.chain(spawns)
+    }
+
+    /// Another target that would trigger on `binding` if it were given to `target`
+    pub fn conflicting_target(
+        &self,
+        target: &BindingTarget,
+        binding: InputBinding,
+    ) -> Option<BindingTarget> {
+        self.targets()
+            .find(|(other, other_binding)| {
+                *other_binding == binding && target.conflicts_with(other)
+            })
+            .map(|(other, _)| other)
+    }
+
+    /// Every pair of targets that currently share a binding
+    pub fn conflicts(&self) -> Vec<(BindingTarget, BindingTarget)> {
+        let targets: Vec<_> = self.targets().collect();
+        let mut conflicts = Vec::new();
+        for (index, (target, binding)) in targets.iter().enumerate() {
+            for (other, other_binding) in &targets[index + 1..] {
+                if binding == other_binding && target.conflicts_with(other) {
+                    conflicts.push((target.clone(), other.clone()));
+                }
+            }
+        }
+        conflicts
+    }
+
+    /// Bind `target` to `binding`, refusing if another target already uses it
+    pub fn rebind(
+        &mut self,
+        target: &BindingTarget,
+        binding: InputBinding,
+    ) -> Result<(), BindingTarget> {
+        if let Some(other) = self.conflicting_target(target, binding) {
+            return Err(other);
+        }
+
+        match target {
+            BindingTarget::Action(action) => {
+                self.bindings.insert(*action, binding);
+            }
+            BindingTarget::Spawn(id) => {
+                self.spawn_bindings.insert(id.clone(), binding);
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub enum BindingsError {
+    Io(std::io::Error),
+    Serialize(ron::Error),
+    Parse(ron::error::SpannedError),
+}
+
+impl fmt::Display for BindingsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BindingsError::Io(err) => write!(f, "i/o error: {err}"),
+            BindingsError::Serialize(err) => write!(f, "could not serialize bindings: {err}"),
+            BindingsError::Parse(err) => write!(f, "could not parse bindings: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for BindingsError {}
+
+impl From<std::io::Error> for BindingsError {
+    fn from(err: std::io::Error) -> Self {
+        BindingsError::Io(err)
+    }
+}
+
+pub fn write_bindings_file(path: &Path, map: &ActionMap) -> Result<(), BindingsError> {
+    let contents = ron::ser::to_string_pretty(map, ron::ser::PrettyConfig::default())
+        .map_err(BindingsError::Serialize)?;
+    fs::write(path, contents)?;
+    Ok(())
+}
+
+/// Read a bindings file. Actions missing from the file keep their default binding;
+/// spawn hotkeys missing from it are filled in by `ActionMap::add_spawn_defaults`.
+pub fn read_bindings_file(path: &Path) -> Result<ActionMap, BindingsError> {
+    let contents = fs::read_to_string(path)?;
+    let mut map: ActionMap = ron::from_str(&contents).map_err(BindingsError::Parse)?;
+
+    for (action, binding) in ActionMap::default().bindings {
+        map.bindings.entry(action).or_insert(binding);
+    }
+
+    Ok(map)
+}
+
+/// Mouse and keyboard state as seen through the action map
+#[derive(SystemParam)]
+pub struct ActionInput<'w> {
+    map: Res<'w, ActionMap>,
+    keyboard: Res<'w, ButtonInput<KeyCode>>,
+    mouse: Res<'w, ButtonInput<MouseButton>>,
+}
+
+impl ActionInput<'_> {
+    /// Whether the action's button went down this frame, with Ctrl held exactly when the binding asks for it
+    pub fn just_pressed(&self, action: InputAction) -> bool {
+        self.binding_just_pressed(self.map.binding(action))
+    }
+
+    /// Whether the hotkey of the spawnable with this id went down this frame
+    pub fn spawn_just_pressed(&self, id: &str) -> bool {
+        self.map
+            .spawn_binding(id)
+            .is_some_and(|binding| self.binding_just_pressed(binding))
+    }
+
+    fn binding_just_pressed(&self, binding: InputBinding) -> bool {
+        let ctrl_held = self
+            .keyboard
+            .any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]);
+
+        binding.ctrl == ctrl_held
+            && match binding.button {
+                InputButton::Key(key) => self.keyboard.just_pressed(key),
+                InputButton::Mouse(button) => self.mouse.just_pressed(button),
+            }
+    }
+
+    /// Whether Shift is held, which adds to a selection instead of replacing it
+    pub fn shift_pressed(&self) -> bool {
+        self.keyboard
+            .any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight])
+    }
+
+    /// Whether the action's button is held down, regardless of modifiers
+    pub fn pressed(&self, action: InputAction) -> bool {
+        match self.map.binding(action).button {
+            InputButton::Key(key) => self.keyboard.pressed(key),
+            InputButton::Mouse(button) => self.mouse.pressed(button),
+        }
+    }
+
+    /// Whether the action's button was let go this frame. Modifiers are ignored,
+    /// so letting go of Ctrl first does not leave a drag stuck.
+    pub fn just_released(&self, action: InputAction) -> bool {
+        match self.map.binding(action).button {
+            InputButton::Key(key) => self.keyboard.just_released(key),
+            InputButton::Mouse(button) => self.mouse.just_released(button),
+        }
+    }
+}
+
+/// The bindings screen, and the action waiting for a new binding if any
+#[derive(Resource, Default)]
+pub struct RebindState {
+    pub is_open: bool,
+    pub capturing: Option<BindingTarget>,
+    /// Feedback about the last rebinding attempt
+    pub message: Option<String>,
+}
+
+/// Run condition for gameplay input, which is suspended while the bindings screen is open
+pub fn is_rebinding(state: Res<RebindState>) -> bool {
+    state.is_open
+}
+
+/// Replace the default bindings with the ones saved in the bindings file, if there is one
+pub fn load_action_map(mut map: ResMut<ActionMap>, registry: Res<SpawnableRegistry>) {
+    let path = Path::new(BINDINGS_FILE_PATH);
+    if path.exists() {
+        match read_bindings_file(path) {
+            Ok(loaded) => *map = loaded,
+            Err(err) => error!("Failed to load bindings from {}: {err}", path.display()),
+        }
+    }
+    map.add_spawn_defaults(&registry);
+
+    for (target, other) in map.conflicts() {
+        if let Some(binding) = map.target_binding(&target) {
+            warn!(
+                "{} and {} are both bound to {binding}",
+                target.label(&registry),
+                other.label(&registry)
+            );
+        }
+    }
+}
+
+pub fn toggle_bindings_screen(
+    input: ActionInput,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut state: ResMut<RebindState>,
+) {
+    if state.capturing.is_some() {
+        return;
+    }
+
+    let close_requested = state.is_open && keyboard.just_pressed(KeyCode::Escape);
+    if input.just_pressed(InputAction::ToggleBindingsScreen) || close_requested {
+        state.is_open = !state.is_open;
+        state.message = None;
+    }
+}
+
+/// Bind the action waiting on the bindings screen to the next key or mouse button pressed.
+/// Escape cancels.
+pub fn capture_rebinding(
+    mut state: ResMut<RebindState>,
+    mut map: ResMut<ActionMap>,
+    registry: Res<SpawnableRegistry>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mouse: Res<ButtonInput<MouseButton>>,
+) {
+    let Some(target) = state.capturing.clone() else {
+        return;
+    };
+
+    // Capturing started this frame, and the click that started it must not become the binding
+    if state.is_changed() {
+        return;
+    }
+
+    if keyboard.just_pressed(KeyCode::Escape) {
+        state.capturing = None;
+        state.message = None;
+        return;
+    }
+
+    let ctrl = keyboard.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]);
+    let pressed_key = keyboard
+        .get_just_pressed()
+        .find(|key| !matches!(key, KeyCode::ControlLeft | KeyCode::ControlRight))
+        .map(|&key| InputButton::Key(key));
+    let pressed_button = mouse.get_just_pressed().next().map(|&button| InputButton::Mouse(button));
+    let Some(button) = pressed_key.or(pressed_button) else {
+        return;
+    };
+
+    let binding = InputBinding { button, ctrl };
+    state.capturing = None;
+    state.message = Some(match map.rebind(&target, binding) {
+        Ok(()) => {
+            if let Err(err) = write_bindings_file(Path::new(BINDINGS_FILE_PATH), &map) {
+                error!("Failed to save bindings to {BINDINGS_FILE_PATH}: {err}");
+            }
+            format!("{} bound to {binding}", target.label(&registry))
+        }
+        Err(other) => format!("{binding} is already bound to {}", other.label(&registry)),
+    });
+}
+
+/// Put every action and spawn hotkey back on its default binding
+pub fn reset_bindings(map: &mut ActionMap, registry: &SpawnableRegistry) {
+    *map = ActionMap::default();
+    map.add_spawn_defaults(registry);
+    if let Err(err) = write_bindings_file(Path::new(BINDINGS_FILE_PATH), map) {
+        error!("Failed to save bindings to {BINDINGS_FILE_PATH}: {err}");
+    }
+}
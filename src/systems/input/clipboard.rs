@@ -0,0 +1,95 @@
+//! Copying, pasting and duplicating the selection
+//!
+//! Copies are taken with the same `SceneCapture` that saves scenes, so only
+//! joints and connections with both ends inside the selection come along, and
+//! `spawn_scene` points them at the new bodies.
+
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+
+use crate::core::tuning::ConnectionTuning;
+use crate::core::utils::CursorWorldPosition;
+use crate::systems::history::{EditCommand, EditHistory};
+use crate::systems::input::action_map::{ActionInput, InputAction};
+use crate::systems::input::selection::Selection;
+use crate::systems::persistence::scene::SandboxScene;
+use crate::systems::persistence::scene_io::{spawn_scene, SceneCapture};
+
+/// How far a duplicate is placed from the original
+const DUPLICATE_OFFSET: Vec2 = Vec2::new(40.0, -40.0);
+
+#[derive(Resource, Default)]
+pub struct Clipboard {
+    pub scene: Option<SandboxScene>,
+}
+
+/// Capture the selected bodies at rest, so copies do not fly off with the originals' momentum
+fn capture_selection(capture: &SceneCapture, selection: &Selection) -> Option<SandboxScene> {
+    let mut scene = capture.capture(|entity| selection.contains(entity));
+    if scene.bodies.is_empty() {
+        return None;
+    }
+
+    for body in &mut scene.bodies {
+        body.linvel = Vec2::ZERO;
+        body.angvel = 0.0;
+    }
+    Some(scene)
+}
+
+fn scene_center(scene: &SandboxScene) -> Vec2 {
+    let sum: Vec2 = scene.bodies.iter().map(|body| body.position).sum();
+    sum / scene.bodies.len().max(1) as f32
+}
+
+/// Spawns pasted and duplicated bodies, recording them so the paste can be undone
+#[derive(SystemParam)]
+pub struct CopySpawner<'w, 's> {
+    commands: Commands<'w, 's>,
+    history: ResMut<'w, EditHistory>,
+    tuning: Res<'w, ConnectionTuning>,
+}
+
+impl CopySpawner<'_, '_> {
+    fn spawn(&mut self, scene: &SandboxScene, offset: Vec2) -> Vec<Entity> {
+        let spawned = spawn_scene(&mut self.commands, scene, offset, &self.tuning);
+        self.history.record(EditCommand::Despawn(spawned.clone()));
+        spawned
+    }
+}
+
+pub fn clipboard_hotkeys(
+    mut spawner: CopySpawner,
+    mut clipboard: ResMut<Clipboard>,
+    mut selection: ResMut<Selection>,
+    input: ActionInput,
+    capture: SceneCapture,
+    cursor: CursorWorldPosition,
+) {
+    let (scene, offset) = if input.just_pressed(InputAction::Copy) {
+        if let Some(scene) = capture_selection(&capture, &selection) {
+            info!("Copied {} bodies", scene.bodies.len());
+            clipboard.scene = Some(scene);
+        }
+        return;
+    } else if input.just_pressed(InputAction::Paste) {
+        let Some(scene) = clipboard.scene.clone() else {
+            return;
+        };
+        // Centre the copies on the cursor
+        let offset = cursor
+            .get()
+            .map_or(DUPLICATE_OFFSET, |cursor| cursor - scene_center(&scene));
+        (scene, offset)
+    } else if input.just_pressed(InputAction::Duplicate) {
+        let Some(scene) = capture_selection(&capture, &selection) else {
+            return;
+        };
+        (scene, DUPLICATE_OFFSET)
+    } else {
+        return;
+    };
+
+    // Select the copies, so they can be moved into place straight away
+    selection.entities = spawner.spawn(&scene, offset);
+}
@@ -1,7 +1,12 @@
 use bevy::prelude::*;
 use bevy_rapier2d::prelude::*;
 
+use crate::core::entity_finder::find_body_at_point;
 use crate::core::utils::get_cursor_world_position;
+use crate::systems::history::{EditCommand, EditHistory};
+use crate::systems::input::action_map::{ActionInput, InputAction};
+use crate::systems::replay::UserAction;
+use crate::ui::ui_topbar::{ObjectType, SelectedObject};
 
 #[derive(Component)]
 pub struct Draggable;
@@ -11,19 +16,74 @@ pub struct DragState {
     pub dragging_entity: Option<Entity>,
     pub original_body_type: Option<RigidBody>,
     pub drag_offset: Vec2,
+    /// Last cursor position the dragged body was moved to
+    pub cursor_position: Vec2,
+    /// Where the dragged body was when it was grabbed
+    pub start_position: Vec2,
+}
+
+impl DragState {
+    /// Start dragging `entity`, grabbed at `cursor_position`
+    pub fn begin(
+        &mut self,
+        entity: Entity,
+        transform: &Transform,
+        body: RigidBody,
+        cursor_position: Vec2,
+    ) {
+        let object_pos = transform.translation.truncate();
+        self.dragging_entity = Some(entity);
+        self.original_body_type = Some(body);
+        self.drag_offset = object_pos - cursor_position;
+        self.cursor_position = cursor_position;
+        self.start_position = object_pos;
+    }
+
+    /// Stop dragging, returning the dragged entity and the body type to restore
+    pub fn end(&mut self) -> Option<(Entity, Option<RigidBody>)> {
+        let entity = self.dragging_entity.take()?;
+        let original_body = self.original_body_type.take();
+        self.drag_offset = Vec2::ZERO;
+        Some((entity, original_body))
+    }
+}
+
+/// Pin the dragged body under the cursor position stored in `drag_state`
+pub fn hold_dragged_body(
+    drag_state: &DragState,
+    transform: &mut Transform,
+    body: &mut RigidBody,
+    velocity: &mut Velocity,
+) {
+    *body = RigidBody::KinematicPositionBased;
+
+    let target_pos = drag_state.cursor_position + drag_state.drag_offset;
+    transform.translation.x = target_pos.x;
+    transform.translation.y = target_pos.y;
+
+    velocity.linvel = Vec2::ZERO;
+    velocity.angvel = 0.0;
 }
 
 pub fn start_drag_system(
     mut drag_state: ResMut<DragState>,
-    mouse_button: Res<ButtonInput<MouseButton>>,
+    mut actions: EventWriter<UserAction>,
+    input: ActionInput,
     windows: Query<&Window>,
     camera_q: Query<(&Camera, &GlobalTransform)>,
-    draggable_query: Query<(Entity, &Transform, &RigidBody), With<Draggable>>,
+    draggable_query: Query<(&Transform, &RigidBody), With<Draggable>>,
     rapier_context: Query<&RapierContext>,
     selection_state: Res<crate::systems::damage::connection::SelectionState>,
+    selected_object: Res<SelectedObject>,
 ) {
-    // Don't start drag if connection mode is enabled
-    if selection_state.is_enabled {
+    // Don't start drag if connection mode is enabled, or the click is meant for the eraser,
+    // the selection tool or the polygon tool
+    if selection_state.is_enabled
+        || matches!(
+            selected_object.object_type,
+            ObjectType::Eraser | ObjectType::Select | ObjectType::Polygon
+        )
+    {
         return;
     }
 
@@ -31,26 +91,15 @@ pub fn start_drag_system(
         return;
     };
 
-    if mouse_button.just_pressed(MouseButton::Left) && drag_state.dragging_entity.is_none() {
+    if input.just_pressed(InputAction::Drag) && drag_state.dragging_entity.is_none() {
         if let Some(world_pos) = get_cursor_world_position(&windows, &camera_q) {
-            // Use raycast to detect the object under cursor
-            let filter = QueryFilter::default();
-            
-            // Use point intersection to find entities at cursor position
-            if let Some((entity, _toi)) = context.cast_ray(
-                world_pos,
-                Vec2::new(0.0, -1.0), // Direction doesn't matter for point detection
-                0.1, // Very small distance, essentially a point query
-                true,
-                filter,
-            ) {
+            if let Some(entity) = find_body_at_point(context, world_pos) {
                 // Check if the hit entity is draggable
-                if let Ok((entity, transform, body)) = draggable_query.get(entity) {
-                    let object_pos = transform.translation.truncate();
-                    let offset = object_pos - world_pos;
-                    drag_state.dragging_entity = Some(entity);
-                    drag_state.original_body_type = Some(*body);
-                    drag_state.drag_offset = offset;
+                if let Ok((transform, body)) = draggable_query.get(entity) {
+                    drag_state.begin(entity, transform, *body, world_pos);
+                    actions.send(UserAction::DragStart {
+                        position: world_pos,
+                    });
                 }
             }
         }
@@ -58,7 +107,8 @@ pub fn start_drag_system(
 }
 
 pub fn update_drag_system(
-    drag_state: Res<DragState>,
+    mut drag_state: ResMut<DragState>,
+    mut actions: EventWriter<UserAction>,
     mut draggable_query: Query<(&mut Transform, &mut RigidBody, &mut Velocity), With<Draggable>>,
     windows: Query<&Window>,
     camera_q: Query<(&Camera, &GlobalTransform)>,
@@ -66,14 +116,12 @@ pub fn update_drag_system(
     if let Some(entity) = drag_state.dragging_entity {
         if let Ok((mut transform, mut body, mut velocity)) = draggable_query.get_mut(entity) {
             if let Some(world_pos) = get_cursor_world_position(&windows, &camera_q) {
-                *body = RigidBody::KinematicPositionBased;
-
-                let target_pos = world_pos + drag_state.drag_offset;
-                transform.translation.x = target_pos.x;
-                transform.translation.y = target_pos.y;
+                if world_pos != drag_state.cursor_position {
+                    drag_state.cursor_position = world_pos;
+                    actions.send(UserAction::DragMove { position: world_pos });
+                }
 
-                velocity.linvel = Vec2::ZERO;
-                velocity.angvel = 0.0;
+                hold_dragged_body(&drag_state, &mut transform, &mut body, &mut velocity);
             }
         }
     }
@@ -81,20 +129,28 @@ pub fn update_drag_system(
 
 pub fn end_drag_system(
     mut drag_state: ResMut<DragState>,
-    mouse_button: Res<ButtonInput<MouseButton>>,
-    mut draggable_query: Query<&mut RigidBody, With<Draggable>>,
+    mut actions: EventWriter<UserAction>,
+    mut history: ResMut<EditHistory>,
+    input: ActionInput,
+    mut draggable_query: Query<(&Transform, &mut RigidBody), With<Draggable>>,
 ) {
-    if mouse_button.just_released(MouseButton::Left) {
-        if let Some(entity) = drag_state.dragging_entity {
-            if let Some(original_body) = drag_state.original_body_type {
-                if let Ok(mut body) = draggable_query.get_mut(entity) {
+    if input.just_released(InputAction::Drag) {
+        let start_position = drag_state.start_position;
+        if let Some((entity, original_body)) = drag_state.end() {
+            if let Ok((transform, mut body)) = draggable_query.get_mut(entity) {
+                if let Some(original_body) = original_body {
                     *body = original_body;
                 }
+
+                if transform.translation.truncate() != start_position {
+                    history.record(EditCommand::MoveTo {
+                        body: entity,
+                        position: start_position,
+                    });
+                }
             }
 
-            drag_state.dragging_entity = None;
-            drag_state.original_body_type = None;
-            drag_state.drag_offset = Vec2::ZERO;
+            actions.send(UserAction::DragEnd);
         }
     }
 }
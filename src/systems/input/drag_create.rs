@@ -1,12 +1,12 @@
 use bevy::prelude::*;
-use bevy_rapier2d::prelude::*;
 
-use crate::core::components::{Flammable, Health};
 use crate::core::utils::get_cursor_world_position;
-use crate::entities::obstacles::iron_block::IronBlock;
-use crate::entities::obstacles::wooden_box::WoodenBox;
-use crate::systems::damage::connection::Connectable;
-use crate::systems::input::drag::{Draggable, DragState};
+use crate::entities::obstacles::shape::BodyShape;
+use crate::entities::spawnable::{place_spawnable, Spawnable, SpawnableRegistry};
+use crate::systems::history::EditHistory;
+use crate::systems::input::action_map::{ActionInput, InputAction};
+use crate::systems::input::drag::DragState;
+use crate::systems::replay::UserAction;
 use crate::ui::ui_topbar::{ObjectType, SelectedObject};
 
 /// Resource to track the state of drag-to-create
@@ -15,6 +15,8 @@ pub struct CreateDragState {
     pub is_creating: bool,
     pub start_position: Vec2,
     pub preview_entity: Option<Entity>,
+    /// Shape picked for spawnables that can take one; the rest are dragged out as rectangles
+    pub shape: BodyShape,
 }
 
 /// Component marker for preview entities
@@ -24,33 +26,55 @@ pub struct PreviewEntity;
 const MIN_SIZE: f32 = 20.0;
 const MAX_SIZE: f32 = 500.0;
 
+/// The selected spawnable, if it is dragged out to a size
+fn selected_sized_spawnable<'a>(
+    selected_object: &SelectedObject,
+    registry: &'a SpawnableRegistry,
+) -> Option<&'a Spawnable> {
+    match selected_object.object_type {
+        ObjectType::Spawnable(id) => registry.get(id).filter(|spawnable| spawnable.is_sized()),
+        _ => None,
+    }
+}
+
+/// The shape `spawnable` is dragged out in
+fn drag_shape(create_drag_state: &CreateDragState, spawnable: &Spawnable) -> BodyShape {
+    if spawnable.is_shaped() {
+        create_drag_state.shape
+    } else {
+        BodyShape::Rectangle
+    }
+}
+
 /// Start drag-to-create when clicking on empty space
 pub fn start_create_drag_system(
     mut create_drag_state: ResMut<CreateDragState>,
     drag_state: Res<DragState>,
-    mouse_button: Res<ButtonInput<MouseButton>>,
+    input: ActionInput,
     selected_object: Res<SelectedObject>,
+    registry: Res<SpawnableRegistry>,
     windows: Query<&Window>,
     camera_q: Query<(&Camera, &GlobalTransform)>,
     mut commands: Commands,
 ) {
     // Only activate if not already dragging an existing object
-    if mouse_button.just_pressed(MouseButton::Left) 
+    if input.just_pressed(InputAction::Place)
         && drag_state.dragging_entity.is_none() 
         && !create_drag_state.is_creating 
     {
-        // Only create for WoodenBox and IronBlock types
-        if matches!(selected_object.object_type, ObjectType::WoodenBox | ObjectType::IronBlock) {
+        // Only create for spawnables that are dragged out to a size
+        if let Some(spawnable) = selected_sized_spawnable(&selected_object, &registry) {
             if let Some(world_pos) = get_cursor_world_position(&windows, &camera_q) {
                 create_drag_state.is_creating = true;
                 create_drag_state.start_position = world_pos;
                 
-                // Spawn initial preview entity
-                let color = match selected_object.object_type {
-                    ObjectType::WoodenBox => Color::srgba(0.6, 0.4, 0.2, 0.5),
-                    ObjectType::IronBlock => Color::srgba(0.6, 0.6, 0.65, 0.5),
-                    _ => Color::srgba(0.5, 0.5, 0.5, 0.5),
+                // Spawn initial preview entity; other shapes are outlined over a fainter box
+                let alpha = if drag_shape(&create_drag_state, spawnable) == BodyShape::Rectangle {
+                    0.5
+                } else {
+                    0.2
                 };
+                let color = spawnable.color.with_alpha(alpha);
                 
                 let preview = commands.spawn((
                     Sprite {
@@ -71,6 +95,8 @@ pub fn start_create_drag_system(
 /// Update preview entity size based on drag distance
 pub fn update_create_drag_system(
     create_drag_state: Res<CreateDragState>,
+    selected_object: Res<SelectedObject>,
+    registry: Res<SpawnableRegistry>,
     windows: Query<&Window>,
     camera_q: Query<(&Camera, &GlobalTransform)>,
     mut preview_query: Query<(&mut Transform, &mut Sprite), With<PreviewEntity>>,
@@ -79,19 +105,23 @@ pub fn update_create_drag_system(
         if let Some(preview_entity) = create_drag_state.preview_entity {
             if let Ok((mut transform, mut sprite)) = preview_query.get_mut(preview_entity) {
                 if let Some(current_pos) = get_cursor_world_position(&windows, &camera_q) {
-                    // Calculate the rectangle from start to current position
-                    let start = create_drag_state.start_position;
-                    let width = (current_pos.x - start.x).abs().max(MIN_SIZE).min(MAX_SIZE);
-                    let height = (current_pos.y - start.y).abs().max(MIN_SIZE).min(MAX_SIZE);
+                    let shape = selected_sized_spawnable(&selected_object, &registry)
+                        .map_or(BodyShape::Rectangle, |spawnable| {
+                            drag_shape(&create_drag_state, spawnable)
+                        });
+                    // Calculate the box around the shape from start to current position
+                    let (center, size) = shape.drag_bounds(
+                        create_drag_state.start_position,
+                        current_pos,
+                        MIN_SIZE,
+                        MAX_SIZE,
+                    );
                     
                     // Update sprite size
-                    sprite.custom_size = Some(Vec2::new(width, height));
+                    sprite.custom_size = Some(size);
                     
-                    // Center the preview between start and current position
-                    let center_x = (start.x + current_pos.x) / 2.0;
-                    let center_y = (start.y + current_pos.y) / 2.0;
-                    transform.translation.x = center_x;
-                    transform.translation.y = center_y;
+                    transform.translation.x = center.x;
+                    transform.translation.y = center.y;
                 }
             }
         }
@@ -101,12 +131,15 @@ pub fn update_create_drag_system(
 /// End drag-to-create and spawn the actual entity
 pub fn end_create_drag_system(
     mut create_drag_state: ResMut<CreateDragState>,
-    mouse_button: Res<ButtonInput<MouseButton>>,
+    mut actions: EventWriter<UserAction>,
+    mut history: ResMut<EditHistory>,
+    input: ActionInput,
     selected_object: Res<SelectedObject>,
+    registry: Res<SpawnableRegistry>,
     preview_query: Query<(&Transform, &Sprite), With<PreviewEntity>>,
     mut commands: Commands,
 ) {
-    if mouse_button.just_released(MouseButton::Left) && create_drag_state.is_creating {
+    if input.just_released(InputAction::Place) && create_drag_state.is_creating {
         // Get the final size from the preview entity
         if let Some(preview_entity) = create_drag_state.preview_entity {
             if let Ok((transform, sprite)) = preview_query.get(preview_entity) {
@@ -114,14 +147,16 @@ pub fn end_create_drag_system(
                 let size = sprite.custom_size.unwrap_or(Vec2::new(MIN_SIZE, MIN_SIZE));
                 
                 // Spawn the actual entity based on the selected type
-                match selected_object.object_type {
-                    ObjectType::WoodenBox => {
-                        spawn_wooden_box_with_size(&mut commands, position, size);
-                    }
-                    ObjectType::IronBlock => {
-                        spawn_iron_block_with_size(&mut commands, position, size);
-                    }
-                    _ => {}
+                if let Some(spawnable) = selected_sized_spawnable(&selected_object, &registry) {
+                    place_spawnable(
+                        &mut commands,
+                        &mut history,
+                        &mut actions,
+                        spawnable,
+                        position,
+                        Some(size),
+                        drag_shape(&create_drag_state, spawnable),
+                    );
                 }
             }
             
@@ -136,51 +171,32 @@ pub fn end_create_drag_system(
     }
 }
 
-fn spawn_wooden_box_with_size(commands: &mut Commands, position: Vec2, size: Vec2) {
-    commands.spawn((
-        Sprite {
-            color: Color::srgb(0.6, 0.4, 0.2),
-            custom_size: Some(size),
-            ..default()
-        },
-        Transform::from_xyz(position.x, position.y, 0.0),
-        RigidBody::Dynamic,
-        Collider::cuboid(size.x / 2.0, size.y / 2.0),
-        ColliderMassProperties::Density(0.8),
-        Restitution::coefficient(0.3),
-        Friction::coefficient(0.7),
-        ExternalImpulse::default(),
-        Velocity::zero(),  // Initialize with zero velocity for stable physics
-        WoodenBox,
-        Draggable,
-        Connectable,
-        Health {
-            current: 100.0 * (size.x * size.y) / (60.0 * 60.0), // Scale health with size
-            max: 100.0 * (size.x * size.y) / (60.0 * 60.0),
-        },
-        Flammable {
-            ignition_threshold: 0.5,
-        },
-    ));
-}
+/// Outline shapes other than rectangles over the box of their preview
+#[cfg(feature = "render")]
+pub fn draw_create_preview(
+    mut gizmos: Gizmos,
+    create_drag_state: Res<CreateDragState>,
+    selected_object: Res<SelectedObject>,
+    registry: Res<SpawnableRegistry>,
+    preview_query: Query<(&Transform, &Sprite), With<PreviewEntity>>,
+) {
+    let Some(spawnable) = selected_sized_spawnable(&selected_object, &registry) else {
+        return;
+    };
+    let shape = drag_shape(&create_drag_state, spawnable);
+    if shape == BodyShape::Rectangle {
+        return;
+    }
 
-fn spawn_iron_block_with_size(commands: &mut Commands, position: Vec2, size: Vec2) {
-    commands.spawn((
-        Sprite {
-            color: Color::srgb(0.6, 0.6, 0.65),
-            custom_size: Some(size),
-            ..default()
-        },
-        Transform::from_xyz(position.x, position.y, 0.0),
-        RigidBody::Dynamic,
-        Collider::cuboid(size.x / 2.0, size.y / 2.0),
-        ColliderMassProperties::Density(7.8),
-        Restitution::coefficient(0.2),
-        Friction::coefficient(0.5),
-        ExternalImpulse::default(),
-        Velocity::zero(),  // Initialize with zero velocity for stable physics
-        IronBlock,
-        Draggable,
-        Connectable,
-    ));
+    for (transform, sprite) in preview_query.iter() {
+        let Some(size) = sprite.custom_size else {
+            continue;
+        };
+        let center = transform.translation.truncate();
+        let mut outline: Vec<Vec2> = shape.outline(size).iter().map(|p| center + *p).collect();
+        if let Some(&first) = outline.first() {
+            outline.push(first);
+        }
+        gizmos.linestrip_2d(outline, spawnable.color);
+    }
 }
@@ -0,0 +1,95 @@
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use bevy::utils::HashSet;
+use bevy_rapier2d::prelude::*;
+
+use crate::core::components::RagdollPart;
+use crate::core::entity_finder::find_body_at_point;
+use crate::core::utils::CursorWorldPosition;
+use crate::systems::history::DeleteBodiesEvent;
+use crate::systems::input::action_map::{ActionInput, InputAction};
+use crate::systems::input::drag::{DragState, Draggable};
+use crate::systems::replay::UserAction;
+use crate::ui::ui_topbar::{ObjectType, SelectedObject};
+
+/// Bodies the eraser removes when clicking at `point`: the body under the cursor,
+/// or every part still jointed to it if it belongs to a ragdoll
+pub fn find_erase_targets(
+    context: &RapierContext,
+    point: Vec2,
+    draggable_query: &Query<Has<RagdollPart>, With<Draggable>>,
+    ragdoll_joints: &Query<(Entity, &ImpulseJoint), With<RagdollPart>>,
+) -> Vec<Entity> {
+    let Some(body) = find_body_at_point(context, point) else {
+        return Vec::new();
+    };
+    let Ok(is_ragdoll) = draggable_query.get(body) else {
+        return Vec::new();
+    };
+    if !is_ragdoll {
+        return vec![body];
+    }
+
+    // Ragdoll joints live on the child part and point at their parent part.
+    // Parts cut off by dismemberment are no longer jointed, so they stay.
+    let mut parts = HashSet::from([body]);
+    loop {
+        let mut grew = false;
+        for (part, joint) in ragdoll_joints.iter() {
+            let linked = parts.contains(&part) || parts.contains(&joint.parent);
+            if linked && (parts.insert(part) | parts.insert(joint.parent)) {
+                grew = true;
+            }
+        }
+        if !grew {
+            break;
+        }
+    }
+
+    parts.into_iter().collect()
+}
+
+/// Deletes erased bodies through the history and reports the erase to recordings
+#[derive(SystemParam)]
+pub struct Eraser<'w> {
+    delete_events: EventWriter<'w, DeleteBodiesEvent>,
+    actions: EventWriter<'w, UserAction>,
+    drag_state: Res<'w, DragState>,
+}
+
+impl Eraser<'_> {
+    fn erase(&mut self, bodies: Vec<Entity>, position: Vec2) {
+        self.delete_events.send(DeleteBodiesEvent { bodies });
+        self.actions.send(UserAction::Erase { position });
+    }
+}
+
+/// Delete the clicked body when the eraser is selected
+pub fn erase_body_on_click(
+    mut eraser: Eraser,
+    input: ActionInput,
+    selected_object: Res<SelectedObject>,
+    cursor: CursorWorldPosition,
+    draggable_query: Query<Has<RagdollPart>, With<Draggable>>,
+    ragdoll_joints: Query<(Entity, &ImpulseJoint), With<RagdollPart>>,
+    rapier_context: Query<&RapierContext>,
+) {
+    if selected_object.object_type != ObjectType::Eraser
+        || !input.just_released(InputAction::Place)
+        || eraser.drag_state.dragging_entity.is_some()
+    {
+        return;
+    }
+
+    let Some(world_pos) = cursor.get() else {
+        return;
+    };
+    let Ok(context) = rapier_context.get_single() else {
+        return;
+    };
+
+    let bodies = find_erase_targets(context, world_pos, &draggable_query, &ragdoll_joints);
+    if !bodies.is_empty() {
+        eraser.erase(bodies, world_pos);
+    }
+}
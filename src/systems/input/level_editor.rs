@@ -0,0 +1,388 @@
+//! Level editor: placing, moving, resizing and rotating static terrain
+//!
+//! While the editor is on, the pointer edits terrain instead of bodies. Drag
+//! on empty space to draw a new piece, drag a piece to select and move it, and
+//! drag a corner of the selected piece to resize it. Corners and the centres of
+//! moved pieces snap to the grid unless Shift is held. The rotate and delete selection actions apply
+//! to the selected piece.
+
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+use crate::core::setup::WorldBounds;
+use crate::core::utils::CursorWorldPosition;
+use crate::entities::terrain::{spawn_terrain, Terrain, TerrainPiece};
+use crate::systems::input::action_map::{ActionInput, InputAction};
+
+/// Angle a rotate action turns the selected piece by
+const ROTATION_STEP: f32 = std::f32::consts::PI / 12.0;
+/// Grid sizes the editor cycles through; `None` places terrain freely
+const GRID_SIZES: [Option<f32>; 4] = [Some(10.0), Some(20.0), Some(40.0), None];
+/// Pressing this close to a corner of the selected piece resizes it
+const CORNER_GRAB_DISTANCE: f32 = 12.0;
+/// Pieces are never thinner than this; shorter drags place nothing
+const MIN_TERRAIN_SIZE: f32 = 10.0;
+/// Corners of a piece, as multiples of its half size
+const CORNERS: [Vec2; 4] = [
+    Vec2::new(-1.0, -1.0),
+    Vec2::new(1.0, -1.0),
+    Vec2::new(1.0, 1.0),
+    Vec2::new(-1.0, 1.0),
+];
+
+#[derive(Resource)]
+pub struct LevelEditor {
+    pub is_active: bool,
+    /// Spacing of the grid terrain snaps to, or `None` for free placement
+    pub grid: Option<f32>,
+}
+
+impl Default for LevelEditor {
+    fn default() -> Self {
+        Self {
+            is_active: false,
+            grid: Some(20.0),
+        }
+    }
+}
+
+impl LevelEditor {
+    /// Switch to the next grid size, wrapping around through free placement
+    pub fn cycle_grid(&mut self) {
+        let next = GRID_SIZES
+            .iter()
+            .position(|&grid| grid == self.grid)
+            .map_or(0, |index| (index + 1) % GRID_SIZES.len());
+        self.grid = GRID_SIZES[next];
+    }
+
+    fn snap(&self, point: Vec2, free: bool) -> Vec2 {
+        match self.grid {
+            Some(grid) if !free => (point / grid).round() * grid,
+            _ => point,
+        }
+    }
+}
+
+#[derive(Default)]
+pub enum TerrainDrag {
+    #[default]
+    Idle,
+    /// Drawing a new piece from the corner at `start`
+    Draw { start: Vec2, preview: Entity },
+    /// Moving a piece that was at `origin` when grabbed at `grab`
+    Move {
+        entity: Entity,
+        origin: Vec2,
+        grab: Vec2,
+    },
+    /// Resizing a piece about the corner opposite the grabbed one, at `anchor`
+    Resize { entity: Entity, anchor: Vec2 },
+}
+
+/// The selected piece of terrain and what the pointer is doing to it
+#[derive(Resource, Default)]
+pub struct LevelEditorState {
+    pub selected: Option<Entity>,
+    pub drag: TerrainDrag,
+}
+
+/// Marker for the outline of a piece being drawn
+#[derive(Component)]
+pub struct TerrainPreview;
+
+/// The outline of a piece being drawn, apart from the terrain itself
+type PreviewFilter = (With<TerrainPreview>, Without<Terrain>);
+
+/// Run condition for the editor, and for the pointer tools it takes over from
+pub fn is_editing_level(editor: Res<LevelEditor>) -> bool {
+    editor.is_active
+}
+
+pub fn toggle_level_editor(input: ActionInput, mut editor: ResMut<LevelEditor>) {
+    if input.just_pressed(InputAction::ToggleLevelEditor) {
+        editor.is_active = !editor.is_active;
+    }
+}
+
+/// Drop the selection and any unfinished drag once the editor is off
+pub fn clear_level_editor_state(
+    mut commands: Commands,
+    editor: Res<LevelEditor>,
+    mut state: ResMut<LevelEditorState>,
+) {
+    if editor.is_active || (state.selected.is_none() && matches!(state.drag, TerrainDrag::Idle)) {
+        return;
+    }
+
+    if let TerrainDrag::Draw { preview, .. } = std::mem::take(&mut state.drag) {
+        commands.entity(preview).despawn();
+    }
+    state.selected = None;
+}
+
+/// Resize the selected piece when pressing on one of its corners, move a piece
+/// when pressing on it, and otherwise start drawing a new one
+pub fn start_terrain_drag(
+    mut commands: Commands,
+    mut state: ResMut<LevelEditorState>,
+    editor: Res<LevelEditor>,
+    input: ActionInput,
+    cursor: CursorWorldPosition,
+    terrain_query: Query<(Entity, &Transform, &Terrain)>,
+    interaction_query: Query<&Interaction>,
+) {
+    if !input.just_pressed(InputAction::Place) || !matches!(state.drag, TerrainDrag::Idle) {
+        return;
+    }
+    // Presses on the editor's own buttons are not edits
+    if interaction_query
+        .iter()
+        .any(|interaction| *interaction != Interaction::None)
+    {
+        return;
+    }
+    let Some(world_pos) = cursor.get() else {
+        return;
+    };
+    let free = input.shift_pressed();
+
+    let selected_piece = state
+        .selected
+        .and_then(|entity| terrain_query.get(entity).ok())
+        .map(|(entity, transform, terrain)| {
+            (entity, TerrainPiece::from_transform(transform, terrain))
+        });
+    if let Some((entity, piece)) = selected_piece {
+        let grabbed_corner = CORNERS.into_iter().find(|&corner| {
+            piece
+                .to_world(corner * piece.size / 2.0)
+                .distance(world_pos)
+                <= CORNER_GRAB_DISTANCE
+        });
+        if let Some(corner) = grabbed_corner {
+            state.drag = TerrainDrag::Resize {
+                entity,
+                anchor: piece.to_world(-corner * piece.size / 2.0),
+            };
+            return;
+        }
+    }
+
+    // The selected piece wins where pieces overlap, so it can always be moved
+    let grabbed = selected_piece
+        .filter(|(_, piece)| piece.contains(world_pos))
+        .or_else(|| {
+            terrain_query
+                .iter()
+                .map(|(entity, transform, terrain)| {
+                    (entity, TerrainPiece::from_transform(transform, terrain))
+                })
+                .find(|(_, piece)| piece.contains(world_pos))
+        });
+    if let Some((entity, piece)) = grabbed {
+        state.selected = Some(entity);
+        state.drag = TerrainDrag::Move {
+            entity,
+            origin: piece.position,
+            grab: world_pos,
+        };
+        return;
+    }
+
+    state.selected = None;
+    let start = editor.snap(world_pos, free);
+    let preview = commands
+        .spawn((
+            Sprite {
+                color: Color::srgba(0.3, 0.3, 0.3, 0.5),
+                custom_size: Some(Vec2::ZERO),
+                ..default()
+            },
+            Transform::from_xyz(start.x, start.y, 1.0),
+            TerrainPreview,
+        ))
+        .id();
+    state.drag = TerrainDrag::Draw { start, preview };
+}
+
+/// Follow the cursor with the piece being drawn, moved or resized
+pub fn update_terrain_drag(
+    state: Res<LevelEditorState>,
+    editor: Res<LevelEditor>,
+    input: ActionInput,
+    cursor: CursorWorldPosition,
+    mut preview_query: Query<(&mut Transform, &mut Sprite), PreviewFilter>,
+    mut terrain_query: Query<(&mut Transform, &mut Sprite, &mut Terrain)>,
+) {
+    let Some(world_pos) = cursor.get() else {
+        return;
+    };
+    let free = input.shift_pressed();
+    let snapped = editor.snap(world_pos, free);
+
+    match state.drag {
+        TerrainDrag::Idle => {}
+        TerrainDrag::Draw { start, preview } => {
+            if let Ok((mut transform, mut sprite)) = preview_query.get_mut(preview) {
+                let rect = Rect::from_corners(start, snapped);
+                sprite.custom_size = Some(rect.size());
+                transform.translation.x = rect.center().x;
+                transform.translation.y = rect.center().y;
+            }
+        }
+        TerrainDrag::Move {
+            entity,
+            origin,
+            grab,
+        } => {
+            if let Ok((mut transform, _, _)) = terrain_query.get_mut(entity) {
+                // Snap where the piece ends up, so pieces off the grid move onto it
+                let position = editor.snap(origin + world_pos - grab, free);
+                transform.translation.x = position.x;
+                transform.translation.y = position.y;
+            }
+        }
+        TerrainDrag::Resize { entity, anchor } => {
+            let Ok((mut transform, mut sprite, mut terrain)) = terrain_query.get_mut(entity) else {
+                return;
+            };
+            let rotation = transform.rotation.to_euler(EulerRot::XYZ).2;
+            // Work in the piece's own frame, so rotated pieces resize along their sides
+            let local = Vec2::from_angle(-rotation).rotate(snapped - anchor);
+            let size = local.abs().max(Vec2::splat(MIN_TERRAIN_SIZE));
+            let direction = Vec2::new(
+                if local.x < 0.0 { -1.0 } else { 1.0 },
+                if local.y < 0.0 { -1.0 } else { 1.0 },
+            );
+            let center = anchor + Vec2::from_angle(rotation).rotate(direction * size / 2.0);
+
+            transform.translation.x = center.x;
+            transform.translation.y = center.y;
+            sprite.custom_size = Some(size);
+            terrain.size = size;
+        }
+    }
+}
+
+/// Place the drawn piece, or give a resized piece its new collider. The world
+/// bounds grow to take in terrain placed outside them.
+pub fn end_terrain_drag(
+    mut commands: Commands,
+    mut state: ResMut<LevelEditorState>,
+    mut bounds: ResMut<WorldBounds>,
+    input: ActionInput,
+    preview_query: Query<(&Transform, &Sprite), With<TerrainPreview>>,
+    terrain_query: Query<(&Transform, &Terrain)>,
+) {
+    if !input.just_released(InputAction::Place) {
+        return;
+    }
+
+    match std::mem::take(&mut state.drag) {
+        TerrainDrag::Idle => {}
+        TerrainDrag::Draw { preview, .. } => {
+            if let Ok((transform, sprite)) = preview_query.get(preview) {
+                let size = sprite.custom_size.unwrap_or(Vec2::ZERO);
+                if size.min_element() >= MIN_TERRAIN_SIZE {
+                    let piece = TerrainPiece::new(transform.translation.truncate(), size);
+                    state.selected = Some(spawn_terrain(&mut commands, &piece));
+                    bounds.include(piece.position);
+                }
+            }
+            commands.entity(preview).despawn();
+        }
+        TerrainDrag::Move { entity, .. } => {
+            if let Ok((transform, _)) = terrain_query.get(entity) {
+                bounds.include(transform.translation.truncate());
+            }
+        }
+        TerrainDrag::Resize { entity, .. } => {
+            if let Ok((transform, terrain)) = terrain_query.get(entity) {
+                commands
+                    .entity(entity)
+                    .insert(Collider::cuboid(terrain.size.x / 2.0, terrain.size.y / 2.0));
+                bounds.include(transform.translation.truncate());
+            }
+        }
+    }
+}
+
+/// Rotate or delete the selected piece
+pub fn terrain_hotkeys(
+    mut commands: Commands,
+    mut state: ResMut<LevelEditorState>,
+    input: ActionInput,
+    mut terrain_query: Query<&mut Transform, With<Terrain>>,
+) {
+    let Some(entity) = state.selected else {
+        return;
+    };
+    if !matches!(state.drag, TerrainDrag::Idle) {
+        return;
+    }
+    let Ok(mut transform) = terrain_query.get_mut(entity) else {
+        // Despawned by loading a level
+        state.selected = None;
+        return;
+    };
+
+    if input.just_pressed(InputAction::RotateSelectionLeft) {
+        transform.rotation = Quat::from_rotation_z(ROTATION_STEP) * transform.rotation;
+    } else if input.just_pressed(InputAction::RotateSelectionRight) {
+        transform.rotation = Quat::from_rotation_z(-ROTATION_STEP) * transform.rotation;
+    }
+
+    if input.just_pressed(InputAction::DeleteSelection) {
+        commands.entity(entity).despawn();
+        state.selected = None;
+    }
+}
+
+/// Draw the grid over the world bounds, and outline every piece of terrain
+/// with handles on the corners of the selected one
+#[cfg(feature = "render")]
+pub fn draw_level_editor(
+    mut gizmos: Gizmos,
+    editor: Res<LevelEditor>,
+    state: Res<LevelEditorState>,
+    bounds: Res<WorldBounds>,
+    terrain_query: Query<(Entity, &Transform, &Terrain)>,
+) {
+    if let Some(grid) = editor.grid {
+        let margin = Vec2::splat(200.0);
+        let min = ((bounds.min - margin) / grid).floor() * grid;
+        let max = ((bounds.max + margin) / grid).ceil() * grid;
+        let color = Color::srgba(1.0, 1.0, 1.0, 0.06);
+
+        let mut x = min.x;
+        while x <= max.x {
+            gizmos.line_2d(Vec2::new(x, min.y), Vec2::new(x, max.y), color);
+            x += grid;
+        }
+        let mut y = min.y;
+        while y <= max.y {
+            gizmos.line_2d(Vec2::new(min.x, y), Vec2::new(max.x, y), color);
+            y += grid;
+        }
+    }
+
+    for (entity, transform, terrain) in terrain_query.iter() {
+        let piece = TerrainPiece::from_transform(transform, terrain);
+        let isometry = Isometry2d::new(piece.position, Rot2::radians(piece.rotation));
+        let is_selected = state.selected == Some(entity);
+        let color = if is_selected {
+            Color::srgb(0.4, 0.7, 1.0)
+        } else {
+            Color::srgb(0.8, 0.8, 0.8)
+        };
+        gizmos.rect_2d(isometry, piece.size, color);
+
+        if is_selected {
+            for corner in CORNERS {
+                let position = piece.to_world(corner * piece.size / 2.0);
+                gizmos.circle_2d(position, CORNER_GRAB_DISTANCE / 2.0, color);
+            }
+        }
+    }
+}
@@ -1,7 +1,34 @@
 //! Input handling systems for mouse dragging and interaction
 
+pub mod action_map;
+pub mod clipboard;
 pub mod drag;
 pub mod drag_create;
+pub mod eraser;
+pub mod level_editor;
+pub mod polygon_tool;
+pub mod selection;
 
+pub use action_map::{
+    capture_rebinding, is_rebinding, load_action_map, toggle_bindings_screen, ActionInput,
+    ActionMap, BindingTarget, InputAction, InputBinding, RebindState,
+};
+pub use clipboard::{clipboard_hotkeys, Clipboard};
 pub use drag::{end_drag_system, start_drag_system, update_drag_system, DragState};
+#[cfg(feature = "render")]
+pub use drag_create::draw_create_preview;
 pub use drag_create::{end_create_drag_system, start_create_drag_system, update_create_drag_system, CreateDragState};
+pub use eraser::{erase_body_on_click, find_erase_targets};
+#[cfg(feature = "render")]
+pub use level_editor::draw_level_editor;
+pub use level_editor::{
+    clear_level_editor_state, end_terrain_drag, is_editing_level, start_terrain_drag,
+    terrain_hotkeys, toggle_level_editor, update_terrain_drag, LevelEditor, LevelEditorState,
+};
+#[cfg(feature = "render")]
+pub use polygon_tool::draw_polygon_draft;
+pub use polygon_tool::{clear_polygon_draft, draw_polygon_outline, PolygonTool};
+pub use selection::{
+    end_selection_drag, prune_selection, selection_hotkeys, start_selection_drag,
+    update_selection_drag, update_selection_highlights, Selection, SelectionDragState,
+};
@@ -0,0 +1,197 @@
+//! Polygon tool: drawing custom rigid bodies
+//!
+//! Click to place the corners of an outline one by one, and click near the
+//! first corner to close it. Pressing on empty space and dragging draws the
+//! outline freehand instead, closing it on release. The outline is simplified,
+//! checked not to cross itself, and spawned as a body of the chosen material.
+
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+
+use crate::core::polygon::{centroid, is_simple, signed_area, simplify_outline};
+use crate::core::utils::get_cursor_world_position;
+use crate::entities::obstacles::polygon::{spawn_polygon_body, PolygonMaterial};
+use crate::systems::history::{EditCommand, EditHistory};
+use crate::systems::input::action_map::{ActionInput, InputAction};
+use crate::systems::replay::UserAction;
+use crate::ui::ui_topbar::{ObjectType, SelectedObject};
+
+/// Freehand strokes add a point each time the cursor moves this far
+const STROKE_SPACING: f32 = 8.0;
+/// Clicking this close to the first corner closes the outline
+const CLOSE_DISTANCE: f32 = 12.0;
+/// Points closer than this to the simplified outline are dropped
+const SIMPLIFY_TOLERANCE: f32 = 2.5;
+/// Outlines enclosing less area than this spawn nothing
+const MIN_POLYGON_AREA: f32 = 100.0;
+
+/// Material of the next polygon, and the outline being drawn
+#[derive(Resource, Default)]
+pub struct PolygonTool {
+    pub material: PolygonMaterial,
+    /// Corners placed so far, in world space
+    pub points: Vec<Vec2>,
+    /// Whether the outline is being drawn freehand with the button held
+    pub is_stroking: bool,
+}
+
+impl PolygonTool {
+    /// Whether clicking at `position` would close the outline
+    pub fn closes_at(&self, position: Vec2) -> bool {
+        self.points.len() >= 3 && self.points[0].distance(position) <= CLOSE_DISTANCE
+    }
+
+    fn clear(&mut self) {
+        self.points.clear();
+        self.is_stroking = false;
+    }
+}
+
+/// Simplify a drawn outline and turn it counter-clockwise around its centre of
+/// mass. Returns the centre and the outline relative to it, or `None` if the
+/// outline is too small or crosses itself.
+fn prepare_outline(points: &[Vec2]) -> Option<(Vec2, Vec<Vec2>)> {
+    let mut outline = simplify_outline(points, SIMPLIFY_TOLERANCE);
+    if outline.len() < 3 || !is_simple(&outline) {
+        return None;
+    }
+
+    let area = signed_area(&outline);
+    if area.abs() < MIN_POLYGON_AREA {
+        return None;
+    }
+    if area < 0.0 {
+        outline.reverse();
+    }
+
+    let center = centroid(&outline);
+    let local = outline.iter().map(|point| *point - center).collect();
+    Some((center, local))
+}
+
+/// Everything a finished outline is reported to
+#[derive(SystemParam)]
+pub struct PolygonSpawner<'w, 's> {
+    commands: Commands<'w, 's>,
+    history: ResMut<'w, EditHistory>,
+    actions: EventWriter<'w, UserAction>,
+}
+
+impl PolygonSpawner<'_, '_> {
+    /// Spawn the drawn outline as a body and clear it
+    fn finish(&mut self, tool: &mut PolygonTool) {
+        match prepare_outline(&tool.points) {
+            Some((position, points)) => {
+                let entity =
+                    spawn_polygon_body(&mut self.commands, position, &points, tool.material);
+                self.history.record(EditCommand::Despawn(vec![entity]));
+                self.actions.send(UserAction::SpawnPolygon {
+                    material: tool.material,
+                    points,
+                    position,
+                });
+            }
+            None => warn!("Discarding polygon that is too small or crosses itself"),
+        }
+        tool.clear();
+    }
+}
+
+/// Drop the unfinished outline once another tool is selected
+pub fn clear_polygon_draft(selected_object: Res<SelectedObject>, mut tool: ResMut<PolygonTool>) {
+    if selected_object.object_type != ObjectType::Polygon && !tool.points.is_empty() {
+        tool.clear();
+    }
+}
+
+/// Place corners on click, draw freehand while the button is held on an empty
+/// outline, and close the outline on release or on its first corner
+pub fn draw_polygon_outline(
+    mut spawner: PolygonSpawner,
+    mut tool: ResMut<PolygonTool>,
+    input: ActionInput,
+    selected_object: Res<SelectedObject>,
+    windows: Query<&Window>,
+    camera_q: Query<(&Camera, &GlobalTransform)>,
+    interaction_query: Query<&Interaction>,
+) {
+    if selected_object.object_type != ObjectType::Polygon {
+        return;
+    }
+
+    if input.just_pressed(InputAction::RemovePolygonPoint) && !tool.is_stroking {
+        tool.points.pop();
+    }
+
+    let Some(world_pos) = get_cursor_world_position(&windows, &camera_q) else {
+        return;
+    };
+
+    if input.just_pressed(InputAction::Place) {
+        // Presses on the tool's own buttons are not corners
+        if interaction_query
+            .iter()
+            .any(|interaction| *interaction != Interaction::None)
+        {
+            return;
+        }
+
+        if tool.closes_at(world_pos) {
+            spawner.finish(&mut tool);
+        } else {
+            // A press on an empty outline may become a freehand stroke
+            tool.is_stroking = tool.points.is_empty();
+            tool.points.push(world_pos);
+        }
+        return;
+    }
+
+    if !tool.is_stroking {
+        return;
+    }
+
+    if input.pressed(InputAction::Place) {
+        let is_far_enough = tool
+            .points
+            .last()
+            .is_some_and(|last| last.distance(world_pos) >= STROKE_SPACING);
+        if is_far_enough {
+            tool.points.push(world_pos);
+        }
+    } else {
+        // A click that barely moved leaves the first corner for clicking the rest
+        tool.is_stroking = false;
+        if tool.points.len() >= 3 {
+            spawner.finish(&mut tool);
+        }
+    }
+}
+
+/// Outline the polygon being drawn, with a line from its last corner to the cursor
+#[cfg(feature = "render")]
+pub fn draw_polygon_draft(
+    mut gizmos: Gizmos,
+    tool: Res<PolygonTool>,
+    windows: Query<&Window>,
+    camera_q: Query<(&Camera, &GlobalTransform)>,
+) {
+    let (Some(first), Some(last)) = (tool.points.first(), tool.points.last()) else {
+        return;
+    };
+    let color = tool.material.color().with_alpha(0.9);
+
+    gizmos.linestrip_2d(tool.points.iter().copied(), color);
+
+    if let Some(cursor) = get_cursor_world_position(&windows, &camera_q) {
+        if !tool.is_stroking {
+            gizmos.line_2d(*last, cursor, color.with_alpha(0.5));
+        }
+        // The first corner lights up when a click there would close the outline
+        let close_color = if tool.closes_at(cursor) {
+            Color::srgb(0.4, 1.0, 0.4)
+        } else {
+            color
+        };
+        gizmos.circle_2d(*first, CLOSE_DISTANCE / 2.0, close_color);
+    }
+}
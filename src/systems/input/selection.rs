@@ -0,0 +1,448 @@
+//! Rubber-band selection of several bodies and operations on the whole group
+//!
+//! Selected bodies are moved and rotated as one rigid piece: every body keeps
+//! its position and rotation relative to the others, so the local anchors of
+//! connections inside the group stay valid.
+
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+use crate::core::components::{Flammable, OnFire};
+use crate::core::entity_finder::find_body_at_point;
+use crate::core::tuning::FireTuning;
+use crate::core::utils::get_cursor_world_position;
+use crate::entities::obstacles::polygon::PolygonBody;
+use crate::entities::obstacles::shape::ShapedBody;
+use crate::systems::effects::ignite_entity;
+use crate::systems::history::{DeleteBodiesEvent, EditCommand, EditHistory};
+use crate::systems::input::action_map::{ActionInput, InputAction};
+use crate::systems::input::drag::Draggable;
+use crate::ui::ui_topbar::{ObjectType, SelectedObject};
+
+/// Angle a rotate action turns the selection by
+const ROTATION_STEP: f32 = std::f32::consts::PI / 12.0;
+/// Dragging less than this far is a click rather than a rubber band
+const CLICK_DISTANCE: f32 = 4.0;
+
+/// Bodies currently selected with the Select tool
+#[derive(Resource, Default)]
+pub struct Selection {
+    pub entities: Vec<Entity>,
+}
+
+impl Selection {
+    pub fn contains(&self, entity: Entity) -> bool {
+        self.entities.contains(&entity)
+    }
+
+    fn add(&mut self, entity: Entity) {
+        if !self.contains(entity) {
+            self.entities.push(entity);
+        }
+    }
+}
+
+#[derive(Default)]
+pub enum SelectionDrag {
+    #[default]
+    Idle,
+    /// Drawing a rubber band from `start`
+    Box { start: Vec2, preview: Entity },
+    /// Moving the selection, which was grabbed at `start`. Holds every body's
+    /// transform and body type from before the move.
+    Move {
+        start: Vec2,
+        bodies: Vec<(Entity, Transform, RigidBody)>,
+    },
+}
+
+#[derive(Resource, Default)]
+pub struct SelectionDragState {
+    pub drag: SelectionDrag,
+}
+
+/// Highlight following one selected body
+#[derive(Component)]
+pub struct SelectionHighlight {
+    pub target_entity: Entity,
+}
+
+/// Marker for the rubber band preview
+#[derive(Component)]
+pub struct SelectionBox;
+
+/// Marks a body pinned in place by freezing the selection
+#[derive(Component)]
+pub struct Frozen {
+    /// Body type to restore when unfreezing
+    pub original_body: RigidBody,
+}
+
+fn is_select_tool(selected_object: &SelectedObject) -> bool {
+    selected_object.object_type == ObjectType::Select
+}
+
+/// Where the cursor is in the world, and which body is under it
+#[derive(SystemParam)]
+pub struct SelectionCursor<'w, 's> {
+    windows: Query<'w, 's, &'static Window>,
+    camera_q: Query<'w, 's, (&'static Camera, &'static GlobalTransform)>,
+    rapier_context: Query<'w, 's, &'static RapierContext>,
+}
+
+impl SelectionCursor<'_, '_> {
+    fn world_position(&self) -> Option<Vec2> {
+        get_cursor_world_position(&self.windows, &self.camera_q)
+    }
+
+    fn body_at(&self, point: Vec2) -> Option<Entity> {
+        self.rapier_context
+            .get_single()
+            .ok()
+            .and_then(|context| find_body_at_point(context, point))
+    }
+}
+
+/// Group edits to the selection, recorded for undo
+#[derive(SystemParam)]
+pub struct SelectionEdits<'w, 's> {
+    commands: Commands<'w, 's>,
+    history: ResMut<'w, EditHistory>,
+    delete_events: EventWriter<'w, DeleteBodiesEvent>,
+}
+
+/// A selected body carried along by a move
+type MovedBody = (&'static mut Transform, &'static mut Velocity);
+
+/// A selected body turned, frozen or thawed by the hotkeys
+type ArrangedBody = (
+    &'static mut Transform,
+    &'static mut RigidBody,
+    Option<&'static Frozen>,
+    Option<&'static mut Velocity>,
+);
+
+/// Begin moving the selection when pressing on a selected body, otherwise begin a rubber band
+pub fn start_selection_drag(
+    mut commands: Commands,
+    mut drag_state: ResMut<SelectionDragState>,
+    input: ActionInput,
+    selected_object: Res<SelectedObject>,
+    selection: Res<Selection>,
+    cursor: SelectionCursor,
+    mut body_query: Query<(&Transform, &mut RigidBody), With<Draggable>>,
+) {
+    if !is_select_tool(&selected_object)
+        || !input.just_pressed(InputAction::Place)
+        || !matches!(drag_state.drag, SelectionDrag::Idle)
+    {
+        return;
+    }
+    let Some(world_pos) = cursor.world_position() else {
+        return;
+    };
+
+    let grabbed = cursor
+        .body_at(world_pos)
+        .filter(|&entity| selection.contains(entity));
+
+    if grabbed.is_some() && !input.shift_pressed() {
+        let mut bodies = Vec::with_capacity(selection.entities.len());
+        for &entity in &selection.entities {
+            if let Ok((transform, mut body)) = body_query.get_mut(entity) {
+                bodies.push((entity, *transform, *body));
+                *body = RigidBody::KinematicPositionBased;
+            }
+        }
+        drag_state.drag = SelectionDrag::Move {
+            start: world_pos,
+            bodies,
+        };
+        return;
+    }
+
+    let preview = commands
+        .spawn((
+            Sprite {
+                color: Color::srgba(0.4, 0.7, 1.0, 0.2),
+                custom_size: Some(Vec2::ZERO),
+                ..default()
+            },
+            Transform::from_xyz(world_pos.x, world_pos.y, 2.0),
+            SelectionBox,
+        ))
+        .id();
+    drag_state.drag = SelectionDrag::Box {
+        start: world_pos,
+        preview,
+    };
+}
+
+/// Resize the rubber band, or carry the selection along with the cursor
+pub fn update_selection_drag(
+    drag_state: Res<SelectionDragState>,
+    windows: Query<&Window>,
+    camera_q: Query<(&Camera, &GlobalTransform)>,
+    mut box_query: Query<(&mut Transform, &mut Sprite), With<SelectionBox>>,
+    mut body_query: Query<MovedBody, (With<Draggable>, Without<SelectionBox>)>,
+) {
+    let Some(world_pos) = get_cursor_world_position(&windows, &camera_q) else {
+        return;
+    };
+
+    match &drag_state.drag {
+        SelectionDrag::Idle => {}
+        SelectionDrag::Box { start, preview } => {
+            if let Ok((mut transform, mut sprite)) = box_query.get_mut(*preview) {
+                let rect = Rect::from_corners(*start, world_pos);
+                sprite.custom_size = Some(rect.size());
+                transform.translation.x = rect.center().x;
+                transform.translation.y = rect.center().y;
+            }
+        }
+        SelectionDrag::Move { start, bodies } => {
+            let offset = world_pos - *start;
+            for (entity, original, _) in bodies {
+                if let Ok((mut transform, mut velocity)) = body_query.get_mut(*entity) {
+                    transform.translation.x = original.translation.x + offset.x;
+                    transform.translation.y = original.translation.y + offset.y;
+                    *velocity = Velocity::zero();
+                }
+            }
+        }
+    }
+}
+
+/// Finish a move, or select what the rubber band covers. Shift adds to the selection.
+pub fn end_selection_drag(
+    mut commands: Commands,
+    mut drag_state: ResMut<SelectionDragState>,
+    mut selection: ResMut<Selection>,
+    mut history: ResMut<EditHistory>,
+    input: ActionInput,
+    cursor: SelectionCursor,
+    mut body_query: Query<(Entity, &Transform, &mut RigidBody), With<Draggable>>,
+) {
+    if !input.just_released(InputAction::Place) {
+        return;
+    }
+
+    match std::mem::take(&mut drag_state.drag) {
+        SelectionDrag::Idle => {}
+        SelectionDrag::Move { bodies, .. } => {
+            let mut moved_from = Vec::with_capacity(bodies.len());
+            for (entity, original, original_body) in bodies {
+                if let Ok((_, _, mut body)) = body_query.get_mut(entity) {
+                    *body = original_body;
+                    moved_from.push((entity, original));
+                }
+            }
+            if !moved_from.is_empty() {
+                history.record(EditCommand::Arrange(moved_from));
+            }
+        }
+        SelectionDrag::Box { start, preview } => {
+            commands.entity(preview).despawn();
+
+            let end = cursor.world_position().unwrap_or(start);
+            if !input.shift_pressed() {
+                selection.entities.clear();
+            }
+
+            if start.distance(end) < CLICK_DISTANCE {
+                let clicked = cursor
+                    .body_at(end)
+                    .filter(|&entity| body_query.contains(entity));
+                if let Some(entity) = clicked {
+                    selection.add(entity);
+                }
+            } else {
+                let rect = Rect::from_corners(start, end);
+                for (entity, transform, _) in body_query.iter() {
+                    if rect.contains(transform.translation.truncate()) {
+                        selection.add(entity);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Rotate, freeze, ignite or delete the whole selection
+pub fn selection_hotkeys(
+    mut edits: SelectionEdits,
+    mut selection: ResMut<Selection>,
+    drag_state: Res<SelectionDragState>,
+    input: ActionInput,
+    fire_tuning: Res<FireTuning>,
+    mut body_query: Query<ArrangedBody, With<Draggable>>,
+    flammable_query: Query<(), (With<Flammable>, Without<OnFire>)>,
+) {
+    // Leave the selection alone while it is being moved
+    if selection.entities.is_empty() || !matches!(drag_state.drag, SelectionDrag::Idle) {
+        return;
+    }
+
+    let rotation = if input.just_pressed(InputAction::RotateSelectionLeft) {
+        Some(ROTATION_STEP)
+    } else if input.just_pressed(InputAction::RotateSelectionRight) {
+        Some(-ROTATION_STEP)
+    } else {
+        None
+    };
+    if let Some(angle) = rotation {
+        let positions: Vec<Vec2> = selection
+            .entities
+            .iter()
+            .filter_map(|&entity| body_query.get(entity).ok())
+            .map(|(transform, ..)| transform.translation.truncate())
+            .collect();
+        if positions.is_empty() {
+            return;
+        }
+        let pivot = positions.iter().sum::<Vec2>() / positions.len() as f32;
+        let turn = Quat::from_rotation_z(angle);
+
+        let mut rotated_from = Vec::with_capacity(positions.len());
+        for &entity in &selection.entities {
+            let Ok((mut transform, _, _, velocity)) = body_query.get_mut(entity) else {
+                continue;
+            };
+            rotated_from.push((entity, *transform));
+
+            let offset = Vec2::from_angle(angle).rotate(transform.translation.truncate() - pivot);
+            transform.translation.x = pivot.x + offset.x;
+            transform.translation.y = pivot.y + offset.y;
+            transform.rotation = turn * transform.rotation;
+            if let Some(mut velocity) = velocity {
+                *velocity = Velocity::zero();
+            }
+        }
+        edits.history.record(EditCommand::Arrange(rotated_from));
+    }
+
+    if input.just_pressed(InputAction::FreezeSelection) {
+        // Freeze everything unless the whole selection is frozen already
+        let all_frozen = selection
+            .entities
+            .iter()
+            .filter_map(|&entity| body_query.get(entity).ok())
+            .all(|(_, _, frozen, _)| frozen.is_some());
+
+        for &entity in &selection.entities {
+            let Ok((_, mut body, frozen, _)) = body_query.get_mut(entity) else {
+                continue;
+            };
+            match (all_frozen, frozen) {
+                (true, Some(frozen)) => {
+                    *body = frozen.original_body;
+                    edits.commands.entity(entity).remove::<Frozen>();
+                }
+                (false, None) => {
+                    edits.commands.entity(entity).insert(Frozen {
+                        original_body: *body,
+                    });
+                    *body = RigidBody::Fixed;
+                }
+                _ => {}
+            }
+        }
+    }
+
+    if input.just_pressed(InputAction::IgniteSelection) {
+        for &entity in &selection.entities {
+            if flammable_query.contains(entity) {
+                ignite_entity(&mut edits.commands, entity, &fire_tuning);
+            }
+        }
+    }
+
+    if input.just_pressed(InputAction::DeleteSelection) {
+        edits.delete_events.send(DeleteBodiesEvent {
+            bodies: std::mem::take(&mut selection.entities),
+        });
+    }
+}
+
+/// Drop destroyed bodies from the selection, and clear it when switching tools
+pub fn prune_selection(
+    mut selection: ResMut<Selection>,
+    selected_object: Res<SelectedObject>,
+    body_query: Query<(), With<Draggable>>,
+) {
+    if selected_object.is_changed() && !is_select_tool(&selected_object) {
+        if !selection.entities.is_empty() {
+            selection.entities.clear();
+        }
+        return;
+    }
+
+    if selection
+        .entities
+        .iter()
+        .any(|&entity| !body_query.contains(entity))
+    {
+        selection
+            .entities
+            .retain(|&entity| body_query.contains(entity));
+    }
+}
+
+type HighlightedBody = (
+    &'static Transform,
+    Option<&'static Sprite>,
+    Option<&'static PolygonBody>,
+    Option<&'static ShapedBody>,
+);
+
+/// Keep one highlight on every selected body, like the connection tool's hover indicator
+pub fn update_selection_highlights(
+    mut commands: Commands,
+    selection: Res<Selection>,
+    mut highlight_query: Query<(Entity, &SelectionHighlight, &mut Transform)>,
+    body_query: Query<HighlightedBody, Without<SelectionHighlight>>,
+) {
+    let mut highlighted = Vec::new();
+    for (highlight_entity, highlight, mut transform) in highlight_query.iter_mut() {
+        let target = highlight.target_entity;
+        match body_query.get(target) {
+            Ok((target_transform, ..)) if selection.contains(target) => {
+                transform.translation = target_transform.translation + Vec3::new(0.0, 0.0, 0.5);
+                transform.rotation = target_transform.rotation;
+                highlighted.push(target);
+            }
+            _ => commands.entity(highlight_entity).despawn(),
+        }
+    }
+
+    for &entity in &selection.entities {
+        if highlighted.contains(&entity) {
+            continue;
+        }
+        let Ok((transform, sprite, polygon, shaped)) = body_query.get(entity) else {
+            continue;
+        };
+        let size = sprite
+            .and_then(|sprite| sprite.custom_size)
+            .or(polygon.map(PolygonBody::extent))
+            .or(shaped.map(|shaped| shaped.size))
+            .unwrap_or(Vec2::splat(50.0));
+
+        commands.spawn((
+            Sprite {
+                color: Color::srgba(0.4, 0.7, 1.0, 0.35),
+                custom_size: Some(size + Vec2::splat(10.0)),
+                ..default()
+            },
+            Transform {
+                translation: transform.translation + Vec3::new(0.0, 0.0, 0.5),
+                rotation: transform.rotation,
+                ..default()
+            },
+            SelectionHighlight {
+                target_entity: entity,
+            },
+        ));
+    }
+}
@@ -1,6 +1,13 @@
 //! Game systems organized by domain
 
+pub mod camera;
 pub mod damage;
+pub mod debug;
 pub mod effects;
+pub mod history;
 pub mod input;
+pub mod persistence;
 pub mod physics;
+pub mod replay;
+pub mod stats;
+pub mod time;
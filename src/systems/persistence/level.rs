@@ -0,0 +1,303 @@
+//! Levels: the static terrain and world bounds the sandbox is played in
+//!
+//! A level is a versioned RON file in the level directory. The level named by
+//! `CurrentLevel` is built at startup, or the built-in arena if none is named.
+//! Loading another level swaps the terrain and bounds; bodies stay where they are.
+
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::core::constants::LEVEL_DIR;
+use crate::core::setup::WorldBounds;
+use crate::entities::terrain::{spawn_terrain, Terrain, TerrainPiece};
+use crate::systems::replay::ReplayState;
+
+/// Current version of the level file format.
+/// Bump this whenever a change would stop older files from loading correctly.
+pub const LEVEL_FORMAT_VERSION: u32 = 1;
+
+/// File extension of level files, after the name
+const LEVEL_EXTENSION: &str = "ron";
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Level {
+    pub version: u32,
+    /// Debris falling below these bounds is removed
+    pub bounds: WorldBounds,
+    pub terrain: Vec<TerrainPiece>,
+}
+
+impl Level {
+    /// The built-in arena: a floor below the bounds and a wall on either side
+    pub fn arena(bounds: WorldBounds) -> Self {
+        let ground_thickness = 20.0;
+        let ground_width = 2000.0;
+        let floor = TerrainPiece::new(
+            Vec2::new(0.0, bounds.min.y - ground_thickness / 2.0),
+            Vec2::new(ground_width, ground_thickness),
+        );
+
+        let wall_thickness = 20.0;
+        // Walls reach as far below the origin as above it
+        let wall_size = Vec2::new(wall_thickness, bounds.max.y * 2.0);
+        let left_wall = TerrainPiece::new(
+            Vec2::new(bounds.min.x - wall_thickness / 2.0, 0.0),
+            wall_size,
+        );
+        let right_wall = TerrainPiece::new(
+            Vec2::new(bounds.max.x + wall_thickness / 2.0, 0.0),
+            wall_size,
+        );
+
+        Self {
+            version: LEVEL_FORMAT_VERSION,
+            bounds,
+            terrain: vec![floor, left_wall, right_wall],
+        }
+    }
+}
+
+/// Names of the levels in the level directory, sorted, with newly saved ones appended
+#[derive(Resource, Default)]
+pub struct LevelLibrary {
+    pub names: Vec<String>,
+}
+
+/// The level being played; `None` is the built-in arena. Insert it with a
+/// name before adding the plugins to start in that level.
+#[derive(Resource, Default, Clone, Debug)]
+pub struct CurrentLevel {
+    pub name: Option<String>,
+}
+
+/// Request to replace the terrain with a level's
+#[derive(Event)]
+pub struct LoadLevelEvent {
+    /// `None` loads the built-in arena
+    pub name: Option<String>,
+}
+
+/// Request to save the current terrain as a level
+#[derive(Event)]
+pub struct SaveLevelEvent {
+    pub name: String,
+}
+
+#[derive(Debug)]
+pub enum LevelError {
+    Io(std::io::Error),
+    Serialize(ron::Error),
+    Parse(ron::error::SpannedError),
+    UnsupportedVersion { found: u32, supported: u32 },
+}
+
+impl fmt::Display for LevelError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LevelError::Io(err) => write!(f, "i/o error: {err}"),
+            LevelError::Serialize(err) => write!(f, "could not serialize level: {err}"),
+            LevelError::Parse(err) => write!(f, "could not parse level: {err}"),
+            LevelError::UnsupportedVersion { found, supported } => write!(
+                f,
+                "format version {found} is newer than supported version {supported}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for LevelError {}
+
+impl From<std::io::Error> for LevelError {
+    fn from(err: std::io::Error) -> Self {
+        LevelError::Io(err)
+    }
+}
+
+pub fn level_path(name: &str) -> PathBuf {
+    Path::new(LEVEL_DIR).join(format!("{name}.{LEVEL_EXTENSION}"))
+}
+
+pub fn write_level_file(path: &Path, level: &Level) -> Result<(), LevelError> {
+    let contents = ron::ser::to_string_pretty(level, ron::ser::PrettyConfig::default())
+        .map_err(LevelError::Serialize)?;
+    fs::write(path, contents)?;
+    Ok(())
+}
+
+pub fn read_level_file(path: &Path) -> Result<Level, LevelError> {
+    let contents = fs::read_to_string(path)?;
+    let level: Level = ron::from_str(&contents).map_err(LevelError::Parse)?;
+
+    if level.version > LEVEL_FORMAT_VERSION {
+        return Err(LevelError::UnsupportedVersion {
+            found: level.version,
+            supported: LEVEL_FORMAT_VERSION,
+        });
+    }
+
+    Ok(level)
+}
+
+fn read_level_names() -> std::io::Result<Vec<String>> {
+    let mut names = Vec::new();
+    for entry in fs::read_dir(LEVEL_DIR)? {
+        let path = entry?.path();
+        if path.extension().is_some_and(|ext| ext == LEVEL_EXTENSION) {
+            if let Some(stem) = path.file_stem().and_then(|stem| stem.to_str()) {
+                names.push(stem.to_string());
+            }
+        }
+    }
+    names.sort();
+    Ok(names)
+}
+
+/// The current terrain as a level. Its bounds are the world bounds, grown to
+/// contain the centre of every piece.
+pub fn capture_level(bounds: &WorldBounds, terrain_query: &Query<(&Transform, &Terrain)>) -> Level {
+    let terrain: Vec<TerrainPiece> = terrain_query
+        .iter()
+        .map(|(transform, terrain)| TerrainPiece::from_transform(transform, terrain))
+        .collect();
+    let mut level_bounds = *bounds;
+    for piece in &terrain {
+        level_bounds.include(piece.position);
+    }
+
+    Level {
+        version: LEVEL_FORMAT_VERSION,
+        bounds: level_bounds,
+        terrain,
+    }
+}
+
+/// Despawn every piece of terrain and build the level's in its place
+pub fn replace_terrain(
+    commands: &mut Commands,
+    terrain_query: &Query<Entity, With<Terrain>>,
+    bounds: &mut WorldBounds,
+    level: &Level,
+) {
+    for entity in terrain_query.iter() {
+        commands.entity(entity).despawn();
+    }
+    for piece in &level.terrain {
+        spawn_terrain(commands, piece);
+    }
+    *bounds = level.bounds;
+}
+
+pub fn load_level_library(mut library: ResMut<LevelLibrary>) {
+    match read_level_names() {
+        Ok(names) => {
+            info!("Found {} levels in {LEVEL_DIR}", names.len());
+            library.names = names;
+        }
+        // No level has been saved yet
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+        Err(err) => error!("Failed to list levels in {LEVEL_DIR}: {err}"),
+    }
+}
+
+/// Build the current level, falling back to the built-in arena around the
+/// world bounds if it is not named or cannot be read
+pub fn spawn_current_level(
+    mut commands: Commands,
+    mut current: ResMut<CurrentLevel>,
+    mut bounds: ResMut<WorldBounds>,
+    terrain_query: Query<Entity, With<Terrain>>,
+) {
+    let level = match current.name.as_deref().map(|name| (name, level_path(name))) {
+        Some((name, path)) => match read_level_file(&path) {
+            Ok(level) => {
+                info!("Loaded level {name} from {}", path.display());
+                level
+            }
+            Err(err) => {
+                error!("Failed to load level from {}: {err}", path.display());
+                current.name = None;
+                Level::arena(*bounds)
+            }
+        },
+        None => Level::arena(*bounds),
+    };
+
+    replace_terrain(&mut commands, &terrain_query, &mut bounds, &level);
+}
+
+pub fn load_level_system(
+    mut commands: Commands,
+    mut events: EventReader<LoadLevelEvent>,
+    mut current: ResMut<CurrentLevel>,
+    mut bounds: ResMut<WorldBounds>,
+    terrain_query: Query<Entity, With<Terrain>>,
+    replay_state: Res<ReplayState>,
+) {
+    // A session plays out in the level it started in, which its replay records
+    if !matches!(*replay_state, ReplayState::Idle) {
+        if events.read().count() > 0 {
+            warn!("Loading a level is disabled while recording or playing back a replay");
+        }
+        return;
+    }
+
+    for event in events.read() {
+        let level = match &event.name {
+            Some(name) => {
+                let path = level_path(name);
+                match read_level_file(&path) {
+                    Ok(level) => level,
+                    Err(err) => {
+                        error!("Failed to load level from {}: {err}", path.display());
+                        continue;
+                    }
+                }
+            }
+            None => Level::arena(WorldBounds::default()),
+        };
+
+        replace_terrain(&mut commands, &terrain_query, &mut bounds, &level);
+        current.name = event.name.clone();
+        info!(
+            "Loaded level {} with {} pieces of terrain",
+            event.name.as_deref().unwrap_or("arena"),
+            level.terrain.len()
+        );
+    }
+}
+
+/// Save the terrain under the given name
+pub fn save_level_system(
+    mut events: EventReader<SaveLevelEvent>,
+    mut library: ResMut<LevelLibrary>,
+    mut current: ResMut<CurrentLevel>,
+    bounds: Res<WorldBounds>,
+    terrain_query: Query<(&Transform, &Terrain)>,
+) {
+    for event in events.read() {
+        let level = capture_level(&bounds, &terrain_query);
+
+        let path = level_path(&event.name);
+        let result = fs::create_dir_all(LEVEL_DIR)
+            .map_err(LevelError::from)
+            .and_then(|()| write_level_file(&path, &level));
+        match result {
+            Ok(()) => {
+                info!(
+                    "Saved level {} with {} pieces of terrain",
+                    event.name,
+                    level.terrain.len()
+                );
+                if !library.names.contains(&event.name) {
+                    library.names.push(event.name.clone());
+                }
+                current.name = Some(event.name.clone());
+            }
+            Err(err) => error!("Failed to save level to {}: {err}", path.display()),
+        }
+    }
+}
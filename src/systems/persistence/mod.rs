@@ -0,0 +1,16 @@
+//! Saving and loading sandbox scenes and levels to versioned file formats
+
+pub mod level;
+pub mod prefab;
+pub mod scene;
+pub mod scene_io;
+
+pub use level::{
+    load_level_library, load_level_system, save_level_system, spawn_current_level, CurrentLevel,
+    LevelLibrary, LoadLevelEvent, SaveLevelEvent,
+};
+pub use prefab::{
+    load_prefab_library, save_prefab_system, spawn_prefab_system, PrefabLibrary,
+    SavePrefabEvent, SpawnPrefabEvent,
+};
+pub use scene_io::{load_scene_system, save_scene_system, LoadSceneEvent, SaveSceneEvent};
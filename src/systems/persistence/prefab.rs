@@ -0,0 +1,143 @@
+//! Named prefabs: groups of bodies saved to their own scene files
+//!
+//! A prefab is an ordinary `SandboxScene` with its bodies placed around the
+//! origin, so spawning one at a point is `spawn_scene` offset by that point.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use bevy::prelude::*;
+
+use crate::core::constants::PREFAB_DIR;
+use crate::core::tuning::ConnectionTuning;
+use crate::systems::history::{EditCommand, EditHistory};
+use crate::systems::persistence::scene::SandboxScene;
+use crate::systems::persistence::scene_io::{
+    read_scene_file, spawn_scene, write_scene_file, SceneCapture, SceneError,
+};
+use crate::systems::replay::UserAction;
+
+/// File extension of prefab files, after the name
+const PREFAB_EXTENSION: &str = "ron";
+
+/// Names of the prefabs in the prefab directory. Found prefabs are sorted and
+/// newly saved ones appended, so a prefab keeps its index for the whole session.
+#[derive(Resource, Default)]
+pub struct PrefabLibrary {
+    pub names: Vec<String>,
+}
+
+/// Request to save `bodies`, with the connections between them, as a prefab
+#[derive(Event)]
+pub struct SavePrefabEvent {
+    pub name: String,
+    pub bodies: Vec<Entity>,
+}
+
+/// Request to spawn the named prefab centred on `position`
+#[derive(Event)]
+pub struct SpawnPrefabEvent {
+    pub name: String,
+    pub position: Vec2,
+}
+
+pub fn prefab_path(name: &str) -> PathBuf {
+    Path::new(PREFAB_DIR).join(format!("{name}.{PREFAB_EXTENSION}"))
+}
+
+fn read_prefab_names() -> std::io::Result<Vec<String>> {
+    let mut names = Vec::new();
+    for entry in fs::read_dir(PREFAB_DIR)? {
+        let path = entry?.path();
+        if path.extension().is_some_and(|ext| ext == PREFAB_EXTENSION) {
+            if let Some(stem) = path.file_stem().and_then(|stem| stem.to_str()) {
+                names.push(stem.to_string());
+            }
+        }
+    }
+    names.sort();
+    Ok(names)
+}
+
+fn write_prefab(name: &str, scene: &SandboxScene) -> Result<(), SceneError> {
+    fs::create_dir_all(PREFAB_DIR)?;
+    write_scene_file(&prefab_path(name), scene)
+}
+
+pub fn load_prefab_library(mut library: ResMut<PrefabLibrary>) {
+    match read_prefab_names() {
+        Ok(names) => {
+            info!("Found {} prefabs in {PREFAB_DIR}", names.len());
+            library.names = names;
+        }
+        // No prefab has been saved yet
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+        Err(err) => error!("Failed to list prefabs in {PREFAB_DIR}: {err}"),
+    }
+}
+
+pub fn save_prefab_system(
+    mut events: EventReader<SavePrefabEvent>,
+    mut library: ResMut<PrefabLibrary>,
+    capture: SceneCapture,
+) {
+    for event in events.read() {
+        let mut scene = capture.capture(|entity| event.bodies.contains(&entity));
+        if scene.bodies.is_empty() {
+            warn!("Not saving prefab {}: none of its bodies exist", event.name);
+            continue;
+        }
+
+        // Store the bodies around the origin, at rest and not burning
+        let center = scene.bodies.iter().map(|body| body.position).sum::<Vec2>()
+            / scene.bodies.len() as f32;
+        for body in &mut scene.bodies {
+            body.position -= center;
+            body.linvel = Vec2::ZERO;
+            body.angvel = 0.0;
+            body.fire = None;
+        }
+
+        match write_prefab(&event.name, &scene) {
+            Ok(()) => {
+                info!(
+                    "Saved prefab {} with {} bodies and {} connections",
+                    event.name,
+                    scene.bodies.len(),
+                    scene.connections.len()
+                );
+                if !library.names.contains(&event.name) {
+                    library.names.push(event.name.clone());
+                }
+            }
+            Err(err) => error!("Failed to save prefab {}: {err}", event.name),
+        }
+    }
+}
+
+pub fn spawn_prefab_system(
+    mut commands: Commands,
+    mut events: EventReader<SpawnPrefabEvent>,
+    mut actions: EventWriter<UserAction>,
+    mut history: ResMut<EditHistory>,
+    tuning: Res<ConnectionTuning>,
+) {
+    for event in events.read() {
+        let path = prefab_path(&event.name);
+        let scene = match read_scene_file(&path) {
+            Ok(scene) => scene,
+            Err(err) => {
+                error!("Failed to load prefab from {}: {err}", path.display());
+                continue;
+            }
+        };
+
+        let bodies = spawn_scene(&mut commands, &scene, event.position, &tuning);
+        history.record(EditCommand::Despawn(bodies));
+        // Recordings carry the prefab itself, so they play back without the prefab directory
+        actions.send(UserAction::SpawnPrefab {
+            scene,
+            position: event.position,
+        });
+    }
+}
@@ -0,0 +1,174 @@
+//! Serializable scene format
+//!
+//! Bodies are stored in a flat list and referenced by index from joints and
+//! connections, so a file can be rebuilt without knowing the original entity ids.
+
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::RigidBody;
+use serde::{Deserialize, Serialize};
+
+use crate::core::components::ConnectionKind;
+use crate::entities::obstacles::polygon::PolygonMaterial;
+use crate::entities::obstacles::shape::BodyShape;
+use crate::systems::damage::connection::ConnectionMaterial;
+
+/// Current version of the scene file format.
+/// Bump this whenever a change would stop older files from loading correctly.
+pub const SCENE_FORMAT_VERSION: u32 = 2;
+
+/// Version 1 break forces were never enforced, and were written in units
+/// 5000 times smaller than the ones connections are measured in since
+const V1_BREAK_FORCE_SCALE: f32 = 5000.0;
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct SandboxScene {
+    pub version: u32,
+    pub bodies: Vec<BodyRecord>,
+    pub ragdoll_joints: Vec<RagdollJointRecord>,
+    pub connections: Vec<ConnectionRecord>,
+}
+
+impl SandboxScene {
+    /// Bring a scene read from an older file up to the current format
+    pub fn upgrade(&mut self) {
+        if self.version < 2 {
+            for connection in &mut self.connections {
+                connection.break_force *= V1_BREAK_FORCE_SCALE;
+            }
+        }
+        self.version = SCENE_FORMAT_VERSION;
+    }
+}
+
+impl Default for SandboxScene {
+    fn default() -> Self {
+        Self {
+            version: SCENE_FORMAT_VERSION,
+            bodies: Vec::new(),
+            ragdoll_joints: Vec::new(),
+            connections: Vec::new(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub enum BodyKind {
+    RagdollPart {
+        color: Color,
+        density: f32,
+        linear_damping: f32,
+        angular_damping: f32,
+    },
+    WoodenBox,
+    IronBlock,
+    Bomb {
+        fuse_remaining: f32,
+    },
+    /// A drawn body; `points` is its counter-clockwise outline around `position`
+    Polygon {
+        material: PolygonMaterial,
+        points: Vec<Vec2>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct BodyRecord {
+    pub kind: BodyKind,
+    pub position: Vec2,
+    pub rotation: f32,
+    pub size: Vec2,
+    /// Shape of a wooden box or iron block; missing from files saved before
+    /// they could take other shapes than rectangles
+    #[serde(default)]
+    pub shape: BodyShape,
+    pub linvel: Vec2,
+    pub angvel: f32,
+    pub health: Option<HealthRecord>,
+    pub fire: Option<FireRecord>,
+    /// Properties that can be changed in the inspector; missing from files saved
+    /// before they were recorded, in which case the body keeps its kind's defaults
+    #[serde(default)]
+    pub physics: Option<PhysicsRecord>,
+    /// Body type to restore when unfreezing, if the body is frozen
+    #[serde(default)]
+    pub frozen: Option<BodyType>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+pub struct HealthRecord {
+    pub current: f32,
+    pub max: f32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+pub struct FireRecord {
+    pub intensity: f32,
+    pub remaining: f32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+pub struct PhysicsRecord {
+    pub density: f32,
+    pub friction: f32,
+    pub restitution: f32,
+    pub linear_damping: f32,
+    pub angular_damping: f32,
+    pub gravity_scale: f32,
+    /// `None` if the body cannot catch fire
+    pub ignition_threshold: Option<f32>,
+}
+
+/// Serializable stand-in for Rapier's `RigidBody`
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BodyType {
+    Dynamic,
+    Fixed,
+    KinematicPositionBased,
+    KinematicVelocityBased,
+}
+
+impl From<RigidBody> for BodyType {
+    fn from(body: RigidBody) -> Self {
+        match body {
+            RigidBody::Dynamic => BodyType::Dynamic,
+            RigidBody::Fixed => BodyType::Fixed,
+            RigidBody::KinematicPositionBased => BodyType::KinematicPositionBased,
+            RigidBody::KinematicVelocityBased => BodyType::KinematicVelocityBased,
+        }
+    }
+}
+
+impl From<BodyType> for RigidBody {
+    fn from(body: BodyType) -> Self {
+        match body {
+            BodyType::Dynamic => RigidBody::Dynamic,
+            BodyType::Fixed => RigidBody::Fixed,
+            BodyType::KinematicPositionBased => RigidBody::KinematicPositionBased,
+            BodyType::KinematicVelocityBased => RigidBody::KinematicVelocityBased,
+        }
+    }
+}
+
+/// Revolute joint holding two ragdoll parts together (indices into `bodies`)
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct RagdollJointRecord {
+    pub parent: usize,
+    pub child: usize,
+    pub parent_anchor: Vec2,
+    pub child_anchor: Vec2,
+    pub min_angle: f32,
+    pub max_angle: f32,
+    pub health: HealthRecord,
+}
+
+/// User-created connection (indices into `bodies`)
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ConnectionRecord {
+    pub a: usize,
+    pub b: usize,
+    pub anchor_a: Vec2,
+    pub anchor_b: Vec2,
+    pub kind: ConnectionKind,
+    pub material: ConnectionMaterial,
+    pub break_force: f32,
+}
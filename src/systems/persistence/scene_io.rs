@@ -0,0 +1,528 @@
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+use bevy_rapier2d::prelude::*;
+
+use crate::core::components::{Bomb, Connection, Flammable, Health, OnFire, RagdollPart};
+use crate::core::tuning::ConnectionTuning;
+use crate::entities::obstacles::iron_block::{spawn_iron_body, IronBlock};
+use crate::entities::obstacles::polygon::{spawn_polygon_body, PolygonBody};
+use crate::entities::obstacles::shape::ShapedBody;
+use crate::entities::obstacles::wooden_box::{spawn_wooden_body, WoodenBox};
+use crate::entities::ragdoll::body_parts::{create_joint, spawn_body_part, BodyPartConfig, JointConfig};
+use crate::entities::weapons::bomb::spawn_bomb_with_fuse;
+use crate::systems::damage::connection::{spawn_connection, JointMaterial};
+use crate::systems::damage::damage::{Fractured, JointHealth};
+use crate::systems::history::EditHistory;
+use crate::systems::input::drag::Draggable;
+use crate::systems::input::selection::Frozen;
+use crate::systems::persistence::scene::{
+    BodyKind, BodyRecord, ConnectionRecord, FireRecord, HealthRecord, PhysicsRecord,
+    RagdollJointRecord, SandboxScene, SCENE_FORMAT_VERSION,
+};
+use crate::systems::replay::ReplayState;
+
+/// Request to write the current sandbox to a scene file
+#[derive(Event)]
+pub struct SaveSceneEvent {
+    pub path: PathBuf,
+}
+
+/// Request to replace the current sandbox with the contents of a scene file
+#[derive(Event)]
+pub struct LoadSceneEvent {
+    pub path: PathBuf,
+}
+
+#[derive(Debug)]
+pub enum SceneError {
+    Io(std::io::Error),
+    Serialize(ron::Error),
+    Parse(ron::error::SpannedError),
+    UnsupportedVersion { found: u32, supported: u32 },
+}
+
+impl fmt::Display for SceneError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SceneError::Io(err) => write!(f, "i/o error: {err}"),
+            SceneError::Serialize(err) => write!(f, "could not serialize scene: {err}"),
+            SceneError::Parse(err) => write!(f, "could not parse scene: {err}"),
+            SceneError::UnsupportedVersion { found, supported } => write!(
+                f,
+                "format version {found} is newer than supported version {supported}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SceneError {}
+
+impl From<std::io::Error> for SceneError {
+    fn from(err: std::io::Error) -> Self {
+        SceneError::Io(err)
+    }
+}
+
+pub fn write_scene_file(path: &Path, scene: &SandboxScene) -> Result<(), SceneError> {
+    let contents = ron::ser::to_string_pretty(scene, ron::ser::PrettyConfig::default())
+        .map_err(SceneError::Serialize)?;
+    fs::write(path, contents)?;
+    Ok(())
+}
+
+pub fn read_scene_file(path: &Path) -> Result<SandboxScene, SceneError> {
+    let contents = fs::read_to_string(path)?;
+    let mut scene: SandboxScene = ron::from_str(&contents).map_err(SceneError::Parse)?;
+
+    if scene.version > SCENE_FORMAT_VERSION {
+        return Err(SceneError::UnsupportedVersion {
+            found: scene.version,
+            supported: SCENE_FORMAT_VERSION,
+        });
+    }
+
+    scene.upgrade();
+    Ok(scene)
+}
+
+/// Keep only characters that are safe in a file name on every platform, for
+/// prefab and level names
+pub fn sanitize_file_name(name: &str) -> String {
+    name.trim()
+        .chars()
+        .map(|c| if c == ' ' { '_' } else { c })
+        .filter(|c| c.is_ascii_alphanumeric() || *c == '_' || *c == '-')
+        .collect()
+}
+
+type BodyQueryData = (
+    Entity,
+    &'static Transform,
+    Option<&'static Sprite>,
+    Option<&'static PolygonBody>,
+    Option<&'static ShapedBody>,
+    Option<&'static Velocity>,
+    StateQueryData,
+    Option<&'static Bomb>,
+    Option<&'static ColliderMassProperties>,
+    Option<&'static Damping>,
+    PhysicsQueryData,
+    Has<RagdollPart>,
+    Has<WoodenBox>,
+    Has<IronBlock>,
+);
+
+/// Damage, fire and freezing, restored as they were
+type StateQueryData = (
+    Option<&'static Health>,
+    Option<&'static OnFire>,
+    Option<&'static Frozen>,
+);
+
+/// Inspector-editable components not already needed to tell body kinds apart
+type PhysicsQueryData = (
+    Option<&'static Friction>,
+    Option<&'static Restitution>,
+    Option<&'static GravityScale>,
+    Option<&'static Flammable>,
+);
+
+/// Read-only access to everything needed to turn live entities into a `SandboxScene`
+#[derive(SystemParam)]
+pub struct SceneCapture<'w, 's> {
+    bodies: Query<'w, 's, BodyQueryData, With<Draggable>>,
+    ragdoll_joints: Query<
+        'w,
+        's,
+        (Entity, &'static ImpulseJoint, Option<&'static JointHealth>),
+        With<RagdollPart>,
+    >,
+    connections: Query<'w, 's, (&'static Connection, Option<&'static JointMaterial>)>,
+}
+
+impl SceneCapture<'_, '_> {
+    /// Capture every body accepted by `include`, plus the joints and connections
+    /// whose both ends are included. Links to excluded bodies are dropped.
+    pub fn capture(&self, include: impl Fn(Entity) -> bool) -> SandboxScene {
+        self.capture_with_entities(include).0
+    }
+
+    /// Like `capture`, also returning the entity each body record was taken from
+    pub fn capture_with_entities(
+        &self,
+        include: impl Fn(Entity) -> bool,
+    ) -> (SandboxScene, Vec<Entity>) {
+        let mut scene = SandboxScene::default();
+        let mut entities = Vec::new();
+        let mut indices: HashMap<Entity, usize> = HashMap::default();
+
+        for (
+            entity,
+            transform,
+            sprite,
+            polygon,
+            shaped,
+            velocity,
+            (health, on_fire, frozen),
+            bomb,
+            mass_props,
+            damping,
+            (friction, restitution, gravity, flammable),
+            is_ragdoll,
+            is_wooden,
+            is_iron,
+        ) in self.bodies.iter()
+        {
+            if !include(entity) {
+                continue;
+            }
+
+            // Rapier's defaults stand in for components the body was spawned without
+            let density = match mass_props {
+                Some(ColliderMassProperties::Density(density)) => *density,
+                _ => 1.0,
+            };
+            let damping = damping.copied().unwrap_or_default();
+            let physics = PhysicsRecord {
+                density,
+                friction: friction.map_or(Friction::default().coefficient, |f| f.coefficient),
+                restitution: restitution
+                    .map_or(Restitution::default().coefficient, |r| r.coefficient),
+                linear_damping: damping.linear_damping,
+                angular_damping: damping.angular_damping,
+                gravity_scale: gravity.map_or(1.0, |g| g.0),
+                ignition_threshold: flammable.map(|flammable| flammable.ignition_threshold),
+            };
+
+            let kind = if let Some(polygon) = polygon {
+                // Drawn bodies carry the wood or iron markers too, so they are checked first
+                BodyKind::Polygon {
+                    material: polygon.material,
+                    points: polygon.points.clone(),
+                }
+            } else if is_ragdoll {
+                BodyKind::RagdollPart {
+                    color: sprite.map_or(Color::WHITE, |sprite| sprite.color),
+                    density,
+                    linear_damping: damping.linear_damping,
+                    angular_damping: damping.angular_damping,
+                }
+            } else if is_wooden {
+                BodyKind::WoodenBox
+            } else if is_iron {
+                BodyKind::IronBlock
+            } else if let Some(bomb) = bomb {
+                BodyKind::Bomb {
+                    fuse_remaining: bomb.timer.remaining_secs(),
+                }
+            } else {
+                continue;
+            };
+
+            let velocity = velocity.copied().unwrap_or_default();
+
+            indices.insert(entity, scene.bodies.len());
+            entities.push(entity);
+            scene.bodies.push(BodyRecord {
+                kind,
+                position: transform.translation.truncate(),
+                rotation: transform.rotation.to_euler(EulerRot::XYZ).2,
+                size: sprite
+                    .and_then(|sprite| sprite.custom_size)
+                    .or(polygon.map(PolygonBody::extent))
+                    .or(shaped.map(|shaped| shaped.size))
+                    .unwrap_or(Vec2::new(20.0, 20.0)),
+                shape: shaped.map(|shaped| shaped.shape).unwrap_or_default(),
+                linvel: velocity.linvel,
+                angvel: velocity.angvel,
+                health: health.map(|health| HealthRecord {
+                    current: health.current,
+                    max: health.max,
+                }),
+                fire: on_fire.map(|on_fire| FireRecord {
+                    intensity: on_fire.intensity,
+                    remaining: on_fire.duration.remaining_secs(),
+                }),
+                physics: Some(physics),
+                frozen: frozen.map(|frozen| frozen.original_body.into()),
+            });
+        }
+
+        for (child, joint, joint_health) in self.ragdoll_joints.iter() {
+            let (Some(&parent), Some(&child)) = (indices.get(&joint.parent), indices.get(&child))
+            else {
+                continue;
+            };
+            let TypedJoint::RevoluteJoint(revolute) = joint.data else {
+                continue;
+            };
+            let (min_angle, max_angle) = revolute
+                .limits()
+                .map(|limits| (limits.min, limits.max))
+                .unwrap_or((-std::f32::consts::PI, std::f32::consts::PI));
+
+            scene.ragdoll_joints.push(RagdollJointRecord {
+                parent,
+                child,
+                parent_anchor: revolute.local_anchor1(),
+                child_anchor: revolute.local_anchor2(),
+                min_angle,
+                max_angle,
+                health: joint_health
+                    .map(|health| HealthRecord {
+                        current: health.current,
+                        max: health.max,
+                    })
+                    .unwrap_or(HealthRecord {
+                        current: 100.0,
+                        max: 100.0,
+                    }),
+            });
+        }
+
+        for (connection, material) in self.connections.iter() {
+            let (Some(&a), Some(&b)) = (indices.get(&connection.a), indices.get(&connection.b))
+            else {
+                continue;
+            };
+
+            scene.connections.push(ConnectionRecord {
+                a,
+                b,
+                anchor_a: connection.anchor_a,
+                anchor_b: connection.anchor_b,
+                kind: connection.kind,
+                material: material.map(|material| material.0).unwrap_or_default(),
+                break_force: connection.break_force,
+            });
+        }
+
+        (scene, entities)
+    }
+}
+
+/// Despawn every body in the sandbox and spawn `scene` in its place
+pub fn replace_scene(
+    commands: &mut Commands,
+    existing_bodies: &Query<Entity, With<Draggable>>,
+    scene: &SandboxScene,
+    tuning: &ConnectionTuning,
+) -> Vec<Entity> {
+    // Connections are children of their first body, so this removes them too
+    for entity in existing_bodies.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+
+    spawn_scene(commands, scene, Vec2::ZERO, tuning)
+}
+
+/// Rebuild a scene, shifting every body by `offset`.
+/// Returns the spawned bodies in the same order as `scene.bodies`.
+pub fn spawn_scene(
+    commands: &mut Commands,
+    scene: &SandboxScene,
+    offset: Vec2,
+    tuning: &ConnectionTuning,
+) -> Vec<Entity> {
+    let entities: Vec<Entity> = scene
+        .bodies
+        .iter()
+        .map(|body| spawn_body(commands, body, offset))
+        .collect();
+
+    for joint in &scene.ragdoll_joints {
+        let (Some(&parent), Some(&child)) = (entities.get(joint.parent), entities.get(joint.child))
+        else {
+            continue;
+        };
+
+        create_joint(
+            commands,
+            JointConfig {
+                parent,
+                child,
+                parent_anchor: joint.parent_anchor,
+                child_anchor: joint.child_anchor,
+                min_angle: joint.min_angle,
+                max_angle: joint.max_angle,
+            },
+        );
+
+        let mut child_commands = commands.entity(child);
+        child_commands.insert(JointHealth {
+            current: joint.health.current,
+            max: joint.health.max,
+            parent_entity: parent,
+        });
+        if joint.health.current < joint.health.max * 0.5 {
+            child_commands.insert(Fractured {
+                severity: 1.0 - (joint.health.current / joint.health.max),
+            });
+        }
+    }
+
+    // Restore velocities after the joints, since joint creation zeroes them
+    for (body, &entity) in scene.bodies.iter().zip(&entities) {
+        commands.entity(entity).insert(Velocity {
+            linvel: body.linvel,
+            angvel: body.angvel,
+        });
+    }
+
+    for record in &scene.connections {
+        let (Some(&a), Some(&b)) = (entities.get(record.a), entities.get(record.b)) else {
+            continue;
+        };
+
+        spawn_connection(
+            commands,
+            Connection {
+                a,
+                b,
+                anchor_a: record.anchor_a,
+                anchor_b: record.anchor_b,
+                kind: record.kind,
+                break_force: record.break_force,
+                current_force: 0.0,
+            },
+            record.material,
+            tuning,
+        );
+    }
+
+    entities
+}
+
+fn spawn_body(commands: &mut Commands, body: &BodyRecord, offset: Vec2) -> Entity {
+    let position = body.position + offset;
+
+    let entity = match &body.kind {
+        BodyKind::RagdollPart {
+            color,
+            density,
+            linear_damping,
+            angular_damping,
+        } => spawn_body_part(
+            commands,
+            BodyPartConfig {
+                size: body.size,
+                position,
+                color: *color,
+                density: *density,
+                linear_damping: *linear_damping,
+                angular_damping: *angular_damping,
+                health: body.health.map(|health| health.max).unwrap_or(100.0),
+            },
+        ),
+        BodyKind::WoodenBox => spawn_wooden_body(commands, position, body.size, body.shape),
+        BodyKind::IronBlock => spawn_iron_body(commands, position, body.size, body.shape),
+        BodyKind::Bomb { fuse_remaining } => {
+            spawn_bomb_with_fuse(commands, position, fuse_remaining.max(0.0))
+        }
+        BodyKind::Polygon { material, points } => {
+            spawn_polygon_body(commands, position, points, *material)
+        }
+    };
+
+    let mut entity_commands = commands.entity(entity);
+    entity_commands.insert(
+        Transform::from_xyz(position.x, position.y, 0.0)
+            .with_rotation(Quat::from_rotation_z(body.rotation)),
+    );
+
+    if let Some(health) = body.health {
+        entity_commands.insert(Health {
+            current: health.current,
+            max: health.max,
+        });
+    }
+
+    if let Some(fire) = body.fire {
+        entity_commands.insert(OnFire::new(fire.intensity, fire.remaining));
+    }
+
+    if let Some(physics) = body.physics {
+        entity_commands.insert((
+            ColliderMassProperties::Density(physics.density),
+            Friction::coefficient(physics.friction),
+            Restitution::coefficient(physics.restitution),
+            Damping {
+                linear_damping: physics.linear_damping,
+                angular_damping: physics.angular_damping,
+            },
+            GravityScale(physics.gravity_scale),
+        ));
+        match physics.ignition_threshold {
+            Some(ignition_threshold) => {
+                entity_commands.insert(Flammable { ignition_threshold });
+            }
+            None => {
+                entity_commands.remove::<Flammable>();
+            }
+        }
+    }
+
+    if let Some(original_body) = body.frozen {
+        entity_commands.insert((
+            Frozen {
+                original_body: original_body.into(),
+            },
+            RigidBody::Fixed,
+        ));
+    }
+
+    entity
+}
+
+pub fn save_scene_system(mut events: EventReader<SaveSceneEvent>, capture: SceneCapture) {
+    for event in events.read() {
+        let scene = capture.capture(|_| true);
+
+        match write_scene_file(&event.path, &scene) {
+            Ok(()) => info!(
+                "Saved {} bodies and {} connections to {}",
+                scene.bodies.len(),
+                scene.connections.len(),
+                event.path.display()
+            ),
+            Err(err) => error!("Failed to save scene to {}: {err}", event.path.display()),
+        }
+    }
+}
+
+pub fn load_scene_system(
+    mut commands: Commands,
+    mut events: EventReader<LoadSceneEvent>,
+    existing_bodies: Query<Entity, With<Draggable>>,
+    tuning: Res<ConnectionTuning>,
+    replay_state: Res<ReplayState>,
+    mut history: ResMut<EditHistory>,
+) {
+    // Sessions respawn the scene they start from, and nothing replaces it midway
+    if !matches!(*replay_state, ReplayState::Idle) {
+        if events.read().count() > 0 {
+            warn!("Loading a scene is disabled while recording or playing back a replay");
+        }
+        return;
+    }
+
+    for event in events.read() {
+        let scene = match read_scene_file(&event.path) {
+            Ok(scene) => scene,
+            Err(err) => {
+                error!("Failed to load scene from {}: {err}", event.path.display());
+                continue;
+            }
+        };
+
+        let bodies = replace_scene(&mut commands, &existing_bodies, &scene, &tuning);
+        // The history refers to bodies that no longer exist
+        history.clear();
+        info!("Loaded {} bodies from {}", bodies.len(), event.path.display());
+    }
+}
@@ -2,5 +2,7 @@
 
 pub mod physics;
 pub mod physics_utils;
+pub mod spatial_index;
 
-pub use physics::{apply_explosion, cleanup_debris};
+pub use physics::{apply_explosion, cleanup_debris, DebrisOrder};
+pub use spatial_index::{rebuild_spatial_index, SpatialIndex};
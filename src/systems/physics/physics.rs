@@ -1,15 +1,26 @@
+use std::collections::VecDeque;
+
 use bevy::prelude::*;
 use bevy_rapier2d::prelude::*;
 
 use crate::core::components::{Debris, Explosion, Health, RagdollPart};
+use crate::core::events::{BodyDestroyed, DamageCause, Exploded};
+use crate::core::rng::{RngStream, SimRng};
+use crate::core::setup::WorldBounds;
+use crate::core::tuning::{DebrisTuning, SettledDebris};
 use crate::core::utils::fade_sprite_alpha;
+use crate::entities::obstacles::polygon::PolygonBody;
+use crate::entities::obstacles::shape::ShapedBody;
 use crate::entities::obstacles::wooden_box::WoodenBox;
 use crate::entities::weapons::explosion::spawn_object_fragments;
 use crate::systems::effects::blood::spawn_blood_particles;
-use crate::systems::physics::physics_utils::apply_radial_impulse;
+use crate::systems::physics::physics_utils::{apply_radial_impulse, fragment_source};
 
 pub fn apply_explosion(
     mut commands: Commands,
+    mut destroyed: EventWriter<BodyDestroyed>,
+    mut exploded: EventWriter<Exploded>,
+    mut sim_rng: ResMut<SimRng>,
     explosion_query: Query<(Entity, &Explosion)>,
     mut physics_query: Query<
         (
@@ -20,19 +31,40 @@ pub fn apply_explosion(
             Option<&RagdollPart>,
             Option<&WoodenBox>,
             Option<&Sprite>,
+            Option<&PolygonBody>,
+            Option<&ShapedBody>,
             Option<&Velocity>,
         ),
         With<RigidBody>,
     >,
 ) {
+    let rng = sim_rng.stream(RngStream::Explosion);
+
     for (explosion_entity, explosion) in explosion_query.iter() {
-        for (entity, transform, mut impulse, health_opt, ragdoll_opt, wooden_box_opt, sprite_opt, velocity_opt) in
-            physics_query.iter_mut()
+        exploded.send(Exploded {
+            position: explosion.position,
+            radius: explosion.radius,
+            pressure: explosion.force,
+        });
+
+        for (
+            entity,
+            transform,
+            mut impulse,
+            health_opt,
+            ragdoll_opt,
+            wooden_box_opt,
+            sprite_opt,
+            polygon_opt,
+            shaped_opt,
+            velocity_opt,
+        ) in physics_query.iter_mut()
         {
             let pos = transform.translation.truncate();
 
             let strength = apply_radial_impulse(
                 &mut impulse,
+                rng,
                 pos,
                 explosion.position,
                 explosion.radius,
@@ -44,23 +76,22 @@ pub fn apply_explosion(
                 if let Some(mut health) = health_opt {
                     if ragdoll_opt.is_some() || wooden_box_opt.is_some() {
                         let damage = strength * 0.002;
+                        let was_alive = health.current > 0.0;
                         health.current -= damage;
 
-                        if health.current <= 0.0 {
+                        if was_alive && health.current <= 0.0 {
                             let direction = (pos - explosion.position).normalize();
                             let current_velocity = velocity_opt
                                 .map(|v| v.linvel)
                                 .unwrap_or(direction * strength * 0.3);
                             
-                            if let Some(sprite) = sprite_opt {
-                                let size = sprite.custom_size.unwrap_or(Vec2::new(20.0, 20.0));
-                                let color = sprite.color;
-                                
+                            if let Some(source) =
+                                fragment_source(transform, sprite_opt, polygon_opt, shaped_opt)
+                            {
                                 spawn_object_fragments(
                                     &mut commands,
-                                    pos,
-                                    size,
-                                    color,
+                                    rng,
+                                    &source,
                                     current_velocity,
                                     wooden_box_opt.is_some(),
                                 );
@@ -75,6 +106,11 @@ pub fn apply_explosion(
                             }
                             
                             commands.entity(entity).despawn();
+                            destroyed.send(BodyDestroyed {
+                                entity,
+                                position: pos,
+                                cause: DamageCause::Explosion,
+                            });
                         }
                     }
                 }
@@ -85,21 +121,109 @@ pub fn apply_explosion(
     }
 }
 
+/// Debris in the order it was spawned, oldest first. Entries of pieces that
+/// are already gone are dropped when they reach the front.
+#[derive(Resource, Default)]
+pub struct DebrisOrder(VecDeque<Entity>);
+
+/// A piece of debris, and whether it still has a body
+type DebrisPiece = (
+    Entity,
+    &'static Transform,
+    &'static mut Sprite,
+    &'static mut Debris,
+    Option<&'static Velocity>,
+    Has<RigidBody>,
+);
+
+/// Apply the debris policy: evict the oldest pieces over the budget, remove
+/// pieces that fell out of the world or faded away, and settle resting ones
 pub fn cleanup_debris(
     mut commands: Commands,
     time: Res<Time>,
-    mut debris_query: Query<(Entity, &Transform, &mut Sprite), With<Debris>>,
+    tuning: Res<DebrisTuning>,
+    bounds: Res<WorldBounds>,
+    mut order: ResMut<DebrisOrder>,
+    mut debris_query: Query<DebrisPiece>,
 ) {
-    for (entity, transform, mut sprite) in debris_query.iter_mut() {
-        if transform.translation.y < -400.0 {
+    // Asking the mutable query keeps a second query from conflicting with it
+    for (entity, _, _, debris, _, _) in debris_query.iter_mut() {
+        if debris.is_added() {
+            order.0.push_back(entity);
+        }
+    }
+
+    let mut evicted = Vec::new();
+    let mut count = debris_query.iter().count();
+    while count > tuning.max_count {
+        let Some(entity) = order.0.pop_front() else {
+            break;
+        };
+        if debris_query.contains(entity) {
             commands.entity(entity).despawn();
+            evicted.push(entity);
+            count -= 1;
         }
+    }
 
-        fade_sprite_alpha(&mut sprite, time.delta_secs() * 0.3);
+    let kill_y = bounds.min.y - tuning.kill_depth;
+
+    for (entity, transform, mut sprite, mut debris, velocity, has_body) in debris_query.iter_mut() {
+        if evicted.contains(&entity) {
+            continue;
+        }
+
+        if transform.translation.y < kill_y {
+            commands.entity(entity).despawn();
+            continue;
+        }
+
+        fade_sprite_alpha(&mut sprite, time.delta_secs() * tuning.fade_per_sec);
 
         let Srgba { alpha, .. } = sprite.color.to_srgba();
         if alpha <= 0.0 {
             commands.entity(entity).despawn();
+            continue;
+        }
+
+        if !has_body {
+            continue;
+        }
+
+        let is_resting = velocity.is_some_and(|velocity| {
+            velocity.linvel.length() < tuning.settle_speed
+                && velocity.angvel.abs() < tuning.settle_speed * 0.1
+        });
+        if !is_resting {
+            debris.resting_secs = 0.0;
+            continue;
+        }
+
+        debris.resting_secs += time.delta_secs();
+        if debris.resting_secs >= tuning.settle_secs {
+            settle_debris(&mut commands, entity, tuning.settled);
+            // A sleeping piece starts counting again if something wakes it
+            debris.resting_secs = 0.0;
+        }
+    }
+}
+
+fn settle_debris(commands: &mut Commands, entity: Entity, settled: SettledDebris) {
+    match settled {
+        SettledDebris::Sleep => {
+            commands.entity(entity).insert(Sleeping {
+                sleeping: true,
+                ..default()
+            });
+        }
+        SettledDebris::Visual => {
+            commands.entity(entity).remove::<(
+                RigidBody,
+                Collider,
+                Velocity,
+                ExternalImpulse,
+                ColliderMassProperties,
+            )>();
         }
     }
 }
@@ -1,5 +1,10 @@
 use bevy::prelude::*;
 use bevy_rapier2d::prelude::*;
+use rand::Rng;
+
+use crate::entities::obstacles::polygon::PolygonBody;
+use crate::entities::obstacles::shape::{BodyShape, ShapedBody};
+use crate::entities::weapons::explosion::FragmentSource;
 
 pub struct RigidBodyConfig {
     pub position: Vec2,
@@ -62,8 +67,41 @@ pub fn spawn_physics_sprite(commands: &mut Commands, config: RigidBodyConfig) ->
     entity_commands.id()
 }
 
+/// Where a destroyed body was and what it looked like, for the fragments it
+/// breaks into: its sprite, its shape or, for drawn bodies, the box around its outline
+pub fn fragment_source(
+    transform: &Transform,
+    sprite: Option<&Sprite>,
+    polygon: Option<&PolygonBody>,
+    shaped: Option<&ShapedBody>,
+) -> Option<FragmentSource> {
+    let (size, color, shape) = match (sprite, polygon, shaped) {
+        (Some(sprite), _, _) => (
+            sprite.custom_size.unwrap_or(Vec2::new(20.0, 20.0)),
+            sprite.color,
+            BodyShape::Rectangle,
+        ),
+        (None, Some(polygon), _) => (
+            polygon.extent(),
+            polygon.material.color(),
+            BodyShape::Rectangle,
+        ),
+        (None, None, Some(shaped)) => (shaped.size, shaped.color, shaped.shape),
+        (None, None, None) => return None,
+    };
+
+    Some(FragmentSource {
+        position: transform.translation.truncate(),
+        rotation: transform.rotation.to_euler(EulerRot::XYZ).2,
+        size,
+        shape,
+        color,
+    })
+}
+
 pub fn apply_radial_impulse(
     impulse: &mut ExternalImpulse,
+    rng: &mut impl Rng,
     object_pos: Vec2,
     explosion_pos: Vec2,
     radius: f32,
@@ -81,7 +119,7 @@ pub fn apply_radial_impulse(
         impulse.impulse += force_vec;
 
         if apply_torque {
-            let torque = rand::random::<f32>() * 10000.0 - 5000.0;
+            let torque = rng.random::<f32>() * 10000.0 - 5000.0;
             let torque_scaled = torque * (1.0 - distance / radius);
             impulse.torque_impulse += torque_scaled;
         }
@@ -0,0 +1,127 @@
+//! Uniform grid of rigid body positions for proximity queries
+//!
+//! Fire spread, ignition and shockwaves only care about bodies near a point.
+//! The grid is rebuilt from every rigid body once per frame, so those systems
+//! look at a few cells instead of every body. Cells are visited in a fixed
+//! order and bodies in the order they were inserted, which keeps the order of
+//! random draws, and so replays, deterministic.
+
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+use bevy_rapier2d::prelude::*;
+
+use crate::core::constants::SPATIAL_CELL_SIZE;
+
+#[derive(Resource)]
+pub struct SpatialIndex {
+    cell_size: f32,
+    cells: HashMap<IVec2, Vec<(Entity, Vec2)>>,
+}
+
+impl Default for SpatialIndex {
+    fn default() -> Self {
+        Self::new(SPATIAL_CELL_SIZE)
+    }
+}
+
+impl SpatialIndex {
+    pub fn new(cell_size: f32) -> Self {
+        Self {
+            cell_size,
+            cells: HashMap::default(),
+        }
+    }
+
+    /// Remove every body, keeping the cells' storage for the next rebuild
+    pub fn clear(&mut self) {
+        for bodies in self.cells.values_mut() {
+            bodies.clear();
+        }
+    }
+
+    /// Forget the cells no body was inserted into since the last `clear`, so
+    /// cells a body only passed through don't pile up
+    pub fn drop_empty_cells(&mut self) {
+        self.cells.retain(|_, bodies| !bodies.is_empty());
+    }
+
+    pub fn insert(&mut self, entity: Entity, position: Vec2) {
+        let cell = self.cell_of(position);
+        self.cells.entry(cell).or_default().push((entity, position));
+    }
+
+    fn cell_of(&self, position: Vec2) -> IVec2 {
+        (position / self.cell_size).floor().as_ivec2()
+    }
+
+    /// Bodies in the cells that overlap the ring between `inner` and `outer`
+    /// around `center`, with their indexed positions. The cells cover more than
+    /// the ring, so callers still check the distance themselves.
+    pub fn candidates_in_ring(
+        &self,
+        center: Vec2,
+        inner: f32,
+        outer: f32,
+    ) -> impl Iterator<Item = (Entity, Vec2)> + '_ {
+        let min = self.cell_of(center - Vec2::splat(outer));
+        let max = self.cell_of(center + Vec2::splat(outer));
+        let inner = inner.max(0.0);
+
+        (min.y..=max.y)
+            .flat_map(move |y| (min.x..=max.x).map(move |x| IVec2::new(x, y)))
+            .filter(move |&cell| {
+                let cell_min = cell.as_vec2() * self.cell_size;
+                let cell_max = cell_min + Vec2::splat(self.cell_size);
+                let nearest = center.clamp(cell_min, cell_max);
+                let half_cell = self.cell_size / 2.0;
+                let cell_center = cell_min + Vec2::splat(half_cell);
+                let farthest = cell_center + (cell_center - center).signum() * half_cell;
+                // Skip cells entirely outside the ring, or entirely inside its hole
+                nearest.distance(center) <= outer && farthest.distance(center) >= inner
+            })
+            .filter_map(move |cell| self.cells.get(&cell))
+            .flat_map(|bodies| bodies.iter().copied())
+    }
+
+    /// Bodies in the cells that overlap the circle of `radius` around `center`
+    pub fn candidates_in_radius(
+        &self,
+        center: Vec2,
+        radius: f32,
+    ) -> impl Iterator<Item = (Entity, Vec2)> + '_ {
+        self.candidates_in_ring(center, 0.0, radius)
+    }
+
+    /// The body closest to `center`, within `max_distance`, that passes `filter`
+    pub fn closest(
+        &self,
+        center: Vec2,
+        max_distance: f32,
+        filter: impl Fn(Entity) -> bool,
+    ) -> Option<Entity> {
+        let mut closest_entity = None;
+        let mut closest_distance = f32::INFINITY;
+
+        for (entity, position) in self.candidates_in_radius(center, max_distance) {
+            let distance = position.distance(center);
+            if distance < max_distance && distance < closest_distance && filter(entity) {
+                closest_distance = distance;
+                closest_entity = Some(entity);
+            }
+        }
+
+        closest_entity
+    }
+}
+
+/// Index every rigid body where the last physics step left it
+pub fn rebuild_spatial_index(
+    mut index: ResMut<SpatialIndex>,
+    body_query: Query<(Entity, &Transform), With<RigidBody>>,
+) {
+    index.clear();
+    for (entity, transform) in body_query.iter() {
+        index.insert(entity, transform.translation.truncate());
+    }
+    index.drop_empty_cells();
+}
@@ -0,0 +1,11 @@
+//! Recording user actions and replaying them deterministically
+
+pub mod playback;
+pub mod recording;
+
+pub use playback::{
+    advance_replay_frame, handle_replay_controls, is_recording, is_replaying,
+    record_user_actions, replay_connect_actions, replay_drag_actions, replay_erase_actions,
+    replay_hotkeys, replay_spawn_actions, ReplayControl, ReplayState,
+};
+pub use recording::UserAction;
@@ -0,0 +1,468 @@
+//! Recording user actions and playing them back under a fixed time step
+//!
+//! Both recording and playback switch Rapier to `TimestepMode::Fixed` and make
+//! `Time` advance by the same amount every frame, so frame N of a playback sees
+//! exactly the simulation state frame N of the recording did. Recording starts
+//! by respawning the current scene and terrain from a capture, so the live run
+//! and every playback begin from identical, freshly created bodies.
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use bevy::time::TimeUpdateStrategy;
+use bevy_rapier2d::prelude::*;
+
+use crate::core::components::{Debris, Explosion, Flammable, OnFire, RagdollPart, ShockwaveRing};
+use crate::core::constants::{REPLAY_FILE_PATH, REPLAY_TIMESTEP};
+use crate::core::entity_finder::find_body_at_point;
+use crate::core::rng::SimRng;
+use crate::core::setup::WorldBounds;
+use crate::core::tuning::{ConnectionTuning, FireTuning};
+use crate::entities::obstacles::polygon::spawn_polygon_body;
+use crate::entities::spawnable::SpawnableRegistry;
+use crate::entities::terrain::Terrain;
+use crate::systems::damage::connection::{BodyConnector, Connectable};
+use crate::systems::effects::spawn_fire_from_ui;
+use crate::systems::history::{DeleteBodiesEvent, EditHistory};
+use crate::systems::input::action_map::{ActionInput, InputAction};
+use crate::systems::input::drag::{hold_dragged_body, DragState, Draggable};
+use crate::systems::input::eraser::find_erase_targets;
+use crate::systems::persistence::level::{capture_level, replace_terrain};
+use crate::systems::persistence::scene_io::{replace_scene, spawn_scene, SceneCapture};
+use crate::systems::persistence::CurrentLevel;
+use crate::systems::physics::spatial_index::SpatialIndex;
+use crate::systems::replay::recording::{
+    read_replay_file, write_replay_file, ReplayLevel, SandboxReplay, TimedAction, UserAction,
+    REPLAY_FORMAT_VERSION,
+};
+use crate::systems::time::TimeControls;
+
+/// Start or stop recording and playback
+#[derive(Event)]
+pub enum ReplayControl {
+    /// Start recording, or stop and write the recording to `path`
+    ToggleRecording { path: PathBuf },
+    /// Play back the replay stored in `path`, or stop the current playback
+    TogglePlayback { path: PathBuf },
+}
+
+pub struct ReplaySession {
+    /// Frames since the session started
+    frame: u64,
+    /// Index of the first action that has not been played back yet
+    next_action: usize,
+    replay: SandboxReplay,
+    /// Rapier time step to restore when the session ends
+    previous_timestep: TimestepMode,
+}
+
+#[derive(Resource, Default)]
+pub enum ReplayState {
+    #[default]
+    Idle,
+    Recording(ReplaySession),
+    Playing(ReplaySession),
+}
+
+impl ReplayState {
+    /// Recorded actions to play back on the current frame
+    pub fn due_actions(&self) -> impl Iterator<Item = &UserAction> {
+        let (pending, frame): (&[TimedAction], u64) = match self {
+            ReplayState::Playing(session) => {
+                (&session.replay.actions[session.next_action..], session.frame)
+            }
+            _ => (&[], 0),
+        };
+
+        pending
+            .iter()
+            .take_while(move |timed| timed.frame <= frame)
+            .map(|timed| &timed.action)
+    }
+}
+
+/// Run condition for the live input systems, which must stay out of the way of a playback
+pub fn is_replaying(state: Res<ReplayState>) -> bool {
+    matches!(*state, ReplayState::Playing(_))
+}
+
+/// Run condition for edits that are not part of the recorded actions
+pub fn is_recording(state: Res<ReplayState>) -> bool {
+    matches!(*state, ReplayState::Recording(_))
+}
+
+pub fn replay_hotkeys(input: ActionInput, mut controls: EventWriter<ReplayControl>) {
+    if input.just_pressed(InputAction::ToggleRecording) {
+        controls.send(ReplayControl::ToggleRecording {
+            path: REPLAY_FILE_PATH.into(),
+        });
+    }
+
+    if input.just_pressed(InputAction::TogglePlayback) {
+        controls.send(ReplayControl::TogglePlayback {
+            path: REPLAY_FILE_PATH.into(),
+        });
+    }
+}
+
+/// Debris and blasts left over from before a session, which its replay cannot rebuild
+type SessionLeftovers = Or<(With<Debris>, With<ShockwaveRing>, With<Explosion>)>;
+
+/// Everything a session touches when it starts or stops
+#[derive(SystemParam)]
+pub struct SessionControl<'w, 's> {
+    commands: Commands<'w, 's>,
+    state: ResMut<'w, ReplayState>,
+    sim_rng: ResMut<'w, SimRng>,
+    drag_state: ResMut<'w, DragState>,
+    timestep_mode: ResMut<'w, TimestepMode>,
+    time_update: ResMut<'w, TimeUpdateStrategy>,
+    connection_tuning: Res<'w, ConnectionTuning>,
+    time_controls: ResMut<'w, TimeControls>,
+    existing_bodies: Query<'w, 's, Entity, With<Draggable>>,
+    body_types: Query<'w, 's, &'static mut RigidBody, With<Draggable>>,
+    bounds: ResMut<'w, WorldBounds>,
+    current_level: ResMut<'w, CurrentLevel>,
+    terrain: Query<'w, 's, Entity, With<Terrain>>,
+    terrain_pieces: Query<'w, 's, (&'static Transform, &'static Terrain)>,
+    leftovers: Query<'w, 's, Entity, SessionLeftovers>,
+    history: ResMut<'w, EditHistory>,
+}
+
+impl SessionControl<'_, '_> {
+    /// The current level, to start a recording in
+    fn capture_level(&self) -> ReplayLevel {
+        ReplayLevel {
+            name: self.current_level.name.clone(),
+            level: capture_level(&self.bounds, &self.terrain_pieces),
+        }
+    }
+
+    fn begin(&mut self, replay: SandboxReplay, playing: bool) {
+        // Anything held from before the session refers to bodies about to be respawned
+        *self.drag_state = DragState::default();
+        // Sessions always run unpaused at 1x
+        *self.time_controls = TimeControls::default();
+
+        for entity in self.leftovers.iter() {
+            self.commands.entity(entity).despawn_recursive();
+        }
+        if let Some(level) = &replay.level {
+            replace_terrain(
+                &mut self.commands,
+                &self.terrain,
+                &mut self.bounds,
+                &level.level,
+            );
+            self.current_level.name = level.name.clone();
+        }
+        replace_scene(
+            &mut self.commands,
+            &self.existing_bodies,
+            &replay.scene,
+            &self.connection_tuning,
+        );
+        // The history refers to the bodies that were just replaced
+        self.history.clear();
+        self.sim_rng.reseed(replay.seed);
+
+        let previous_timestep = *self.timestep_mode;
+        *self.timestep_mode = TimestepMode::Fixed {
+            dt: replay.timestep,
+            substeps: 1,
+        };
+        *self.time_update =
+            TimeUpdateStrategy::ManualDuration(Duration::from_secs_f32(replay.timestep));
+
+        let session = ReplaySession {
+            frame: 0,
+            next_action: 0,
+            replay,
+            previous_timestep,
+        };
+        *self.state = if playing {
+            ReplayState::Playing(session)
+        } else {
+            ReplayState::Recording(session)
+        };
+    }
+
+    /// Return to real-time stepping and hand back the finished session
+    fn end(&mut self) -> Option<ReplaySession> {
+        let session = match std::mem::take(&mut *self.state) {
+            ReplayState::Idle => return None,
+            ReplayState::Recording(session) | ReplayState::Playing(session) => session,
+        };
+
+        *self.timestep_mode = session.previous_timestep;
+        *self.time_update = TimeUpdateStrategy::Automatic;
+
+        // Don't leave a body stuck as kinematic if the session ends mid-drag
+        if let Some((entity, Some(original_body))) = self.drag_state.end() {
+            if let Ok(mut body) = self.body_types.get_mut(entity) {
+                *body = original_body;
+            }
+        }
+
+        Some(session)
+    }
+}
+
+pub fn handle_replay_controls(
+    mut controls: EventReader<ReplayControl>,
+    mut session: SessionControl,
+    capture: SceneCapture,
+) {
+    for control in controls.read() {
+        match (control, &*session.state) {
+            (ReplayControl::ToggleRecording { .. }, ReplayState::Idle) => {
+                let seed = session.sim_rng.seed();
+                let level = session.capture_level();
+                session.begin(
+                    SandboxReplay {
+                        version: REPLAY_FORMAT_VERSION,
+                        seed,
+                        timestep: REPLAY_TIMESTEP,
+                        frames: 0,
+                        scene: capture.capture(|_| true),
+                        level: Some(level),
+                        actions: Vec::new(),
+                    },
+                    false,
+                );
+                info!("Recording started (seed {seed})");
+            }
+            (ReplayControl::ToggleRecording { path }, ReplayState::Recording(_)) => {
+                let Some(finished) = session.end() else {
+                    continue;
+                };
+                let mut replay = finished.replay;
+                replay.frames = finished.frame;
+
+                match write_replay_file(path, &replay) {
+                    Ok(()) => info!(
+                        "Recorded {} actions over {} frames to {}",
+                        replay.actions.len(),
+                        replay.frames,
+                        path.display()
+                    ),
+                    Err(err) => error!("Failed to save replay to {}: {err}", path.display()),
+                }
+            }
+            (ReplayControl::TogglePlayback { path }, ReplayState::Idle) => {
+                match read_replay_file(path) {
+                    Ok(replay) => {
+                        info!(
+                            "Playing back {} ({} frames, seed {})",
+                            path.display(),
+                            replay.frames,
+                            replay.seed
+                        );
+                        session.begin(replay, true);
+                    }
+                    Err(err) => error!("Failed to load replay from {}: {err}", path.display()),
+                }
+            }
+            (ReplayControl::TogglePlayback { .. }, ReplayState::Playing(_)) => {
+                if let Some(stopped) = session.end() {
+                    info!("Playback stopped at frame {}", stopped.frame);
+                }
+            }
+            (ReplayControl::ToggleRecording { .. }, ReplayState::Playing(_)) => {
+                warn!("Cannot start recording while a replay is playing");
+            }
+            (ReplayControl::TogglePlayback { .. }, ReplayState::Recording(_)) => {
+                warn!("Cannot play back a replay while recording");
+            }
+        }
+    }
+}
+
+pub fn record_user_actions(mut actions: EventReader<UserAction>, mut state: ResMut<ReplayState>) {
+    let ReplayState::Recording(session) = &mut *state else {
+        actions.clear();
+        return;
+    };
+
+    for action in actions.read() {
+        session.replay.actions.push(TimedAction {
+            frame: session.frame,
+            action: action.clone(),
+        });
+    }
+}
+
+/// Move every session on to the next frame, ending playbacks that ran out of frames
+pub fn advance_replay_frame(mut session: SessionControl) {
+    let finished = match &mut *session.state {
+        ReplayState::Idle => false,
+        ReplayState::Recording(recording) => {
+            recording.frame += 1;
+            false
+        }
+        ReplayState::Playing(playback) => {
+            let pending = &playback.replay.actions[playback.next_action..];
+            playback.next_action += pending
+                .iter()
+                .take_while(|timed| timed.frame <= playback.frame)
+                .count();
+            playback.frame += 1;
+
+            playback.next_action == playback.replay.actions.len()
+                && playback.frame >= playback.replay.frames
+        }
+    };
+
+    if finished {
+        if let Some(playback) = session.end() {
+            info!("Playback finished after {} frames", playback.frame);
+        }
+    }
+}
+
+pub fn replay_spawn_actions(
+    mut commands: Commands,
+    state: Res<ReplayState>,
+    registry: Res<SpawnableRegistry>,
+    fire_tuning: Res<FireTuning>,
+    connection_tuning: Res<ConnectionTuning>,
+    spatial_index: Res<SpatialIndex>,
+    flammable_query: Query<(Entity, &Transform, &Flammable), Without<OnFire>>,
+) {
+    for action in state.due_actions() {
+        match *action {
+            UserAction::Spawn {
+                ref id,
+                position,
+                size,
+                shape,
+            } => match registry.find(id) {
+                Some(spawnable) => {
+                    spawnable.spawn(&mut commands, position, size, shape);
+                }
+                None => warn!("Skipping spawn of unregistered object {id}"),
+            },
+            UserAction::Ignite { position } => {
+                spawn_fire_from_ui(
+                    &mut commands,
+                    position,
+                    &fire_tuning,
+                    &spatial_index,
+                    &flammable_query,
+                );
+            }
+            UserAction::SpawnPrefab {
+                ref scene,
+                position,
+            } => {
+                spawn_scene(&mut commands, scene, position, &connection_tuning);
+            }
+            UserAction::SpawnPolygon {
+                material,
+                ref points,
+                position,
+            } => {
+                spawn_polygon_body(&mut commands, position, points, material);
+            }
+            _ => {}
+        }
+    }
+}
+
+pub fn replay_drag_actions(
+    state: Res<ReplayState>,
+    mut drag_state: ResMut<DragState>,
+    mut draggable_query: Query<(&mut Transform, &mut RigidBody, &mut Velocity), With<Draggable>>,
+    rapier_context: Query<&RapierContext>,
+) {
+    for action in state.due_actions() {
+        match *action {
+            UserAction::DragStart { position } => {
+                let Ok(context) = rapier_context.get_single() else {
+                    continue;
+                };
+                if let Some(entity) = find_body_at_point(context, position) {
+                    if let Ok((transform, body, _)) = draggable_query.get(entity) {
+                        drag_state.begin(entity, transform, *body, position);
+                    }
+                }
+            }
+            UserAction::DragMove { position } => drag_state.cursor_position = position,
+            UserAction::DragEnd => {
+                if let Some((entity, Some(original_body))) = drag_state.end() {
+                    if let Ok((_, mut body, _)) = draggable_query.get_mut(entity) {
+                        *body = original_body;
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    // The live drag system holds the body under the cursor every frame, not only when it moves
+    if let Some(entity) = drag_state.dragging_entity {
+        if let Ok((mut transform, mut body, mut velocity)) = draggable_query.get_mut(entity) {
+            hold_dragged_body(&drag_state, &mut transform, &mut body, &mut velocity);
+        }
+    }
+}
+
+pub fn replay_connect_actions(
+    mut connector: BodyConnector,
+    state: Res<ReplayState>,
+    connectable_query: Query<(), With<Connectable>>,
+    rapier_context: Query<&RapierContext>,
+) {
+    let Ok(context) = rapier_context.get_single() else {
+        return;
+    };
+
+    for action in state.due_actions() {
+        let UserAction::Connect {
+            start,
+            end,
+            kind,
+            material,
+        } = *action
+        else {
+            continue;
+        };
+
+        let connectable_at = |point| {
+            find_body_at_point(context, point).filter(|&entity| connectable_query.get(entity).is_ok())
+        };
+        let Some(start_entity) = connectable_at(start) else {
+            continue;
+        };
+        let Some(end_entity) = connectable_at(end).filter(|&entity| entity != start_entity) else {
+            continue;
+        };
+
+        connector.connect((start_entity, start), (end_entity, end), kind, material);
+    }
+}
+
+pub fn replay_erase_actions(
+    mut delete_events: EventWriter<DeleteBodiesEvent>,
+    state: Res<ReplayState>,
+    draggable_query: Query<Has<RagdollPart>, With<Draggable>>,
+    ragdoll_joints: Query<(Entity, &ImpulseJoint), With<RagdollPart>>,
+    rapier_context: Query<&RapierContext>,
+) {
+    let Ok(context) = rapier_context.get_single() else {
+        return;
+    };
+
+    for action in state.due_actions() {
+        let UserAction::Erase { position } = *action else {
+            continue;
+        };
+
+        let bodies = find_erase_targets(context, position, &draggable_query, &ragdoll_joints);
+        if !bodies.is_empty() {
+            delete_events.send(DeleteBodiesEvent { bodies });
+        }
+    }
+}
@@ -0,0 +1,136 @@
+//! Serializable replay format
+//!
+//! A replay is the scene at the moment recording started, the simulation seed,
+//! and every user action tagged with the frame it happened on. Positions are
+//! world-space cursor positions, so bodies are looked up again on playback the
+//! same way the input systems found them.
+
+use std::fs;
+use std::path::Path;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::core::components::ConnectionKind;
+use crate::entities::obstacles::polygon::PolygonMaterial;
+use crate::entities::obstacles::shape::BodyShape;
+use crate::systems::damage::connection::ConnectionMaterial;
+use crate::systems::persistence::level::Level;
+use crate::systems::persistence::scene::{SandboxScene, SCENE_FORMAT_VERSION};
+use crate::systems::persistence::scene_io::SceneError;
+
+/// Current version of the replay file format.
+/// Bump this whenever a change would stop older files from playing back correctly.
+pub const REPLAY_FORMAT_VERSION: u32 = 2;
+
+/// Something the user did to the sandbox, reported by the input systems
+#[derive(Event, Serialize, Deserialize, Clone, Debug)]
+pub enum UserAction {
+    /// The registered spawnable `id` was placed at `position`, dragged out to
+    /// `size` or at its default size, in `shape` if it can take one
+    Spawn {
+        id: String,
+        position: Vec2,
+        size: Option<Vec2>,
+        /// Missing from recordings made before spawnables had shapes
+        #[serde(default)]
+        shape: BodyShape,
+    },
+    Ignite {
+        position: Vec2,
+    },
+    /// A body was grabbed at `position`
+    DragStart {
+        position: Vec2,
+    },
+    /// The cursor holding the grabbed body moved to `position`
+    DragMove {
+        position: Vec2,
+    },
+    DragEnd,
+    /// Two bodies were joined by dragging from `start` to `end`
+    Connect {
+        start: Vec2,
+        end: Vec2,
+        kind: ConnectionKind,
+        material: ConnectionMaterial,
+    },
+    /// The body at `position` was erased, with the rest of its ragdoll
+    Erase {
+        position: Vec2,
+    },
+    /// A prefab was spawned centred on `position`
+    SpawnPrefab {
+        scene: SandboxScene,
+        position: Vec2,
+    },
+    /// A polygon was drawn, with the simplified counter-clockwise outline
+    /// `points` around its centre at `position`
+    SpawnPolygon {
+        material: PolygonMaterial,
+        points: Vec<Vec2>,
+        position: Vec2,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct TimedAction {
+    /// Frames since the recording started
+    pub frame: u64,
+    pub action: UserAction,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct SandboxReplay {
+    pub version: u32,
+    /// Seed `SimRng` was reset to when recording started
+    pub seed: u64,
+    /// Physics and game time advanced per frame, in seconds
+    pub timestep: f32,
+    /// Length of the recording in frames
+    pub frames: u64,
+    /// Sandbox contents when recording started
+    pub scene: SandboxScene,
+    /// Missing from recordings made before levels were recorded, which play
+    /// back in whatever level is loaded
+    #[serde(default)]
+    pub level: Option<ReplayLevel>,
+    pub actions: Vec<TimedAction>,
+}
+
+/// Level a recording was made in, with its terrain as it was when recording
+/// started, so playback does not depend on the level file
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ReplayLevel {
+    /// `None` is the built-in arena
+    pub name: Option<String>,
+    pub level: Level,
+}
+
+pub fn write_replay_file(path: &Path, replay: &SandboxReplay) -> Result<(), SceneError> {
+    let contents = ron::ser::to_string_pretty(replay, ron::ser::PrettyConfig::default())
+        .map_err(SceneError::Serialize)?;
+    fs::write(path, contents)?;
+    Ok(())
+}
+
+pub fn read_replay_file(path: &Path) -> Result<SandboxReplay, SceneError> {
+    let contents = fs::read_to_string(path)?;
+    let mut replay: SandboxReplay = ron::from_str(&contents).map_err(SceneError::Parse)?;
+
+    if replay.version > REPLAY_FORMAT_VERSION {
+        return Err(SceneError::UnsupportedVersion {
+            found: replay.version,
+            supported: REPLAY_FORMAT_VERSION,
+        });
+    }
+    if replay.scene.version > SCENE_FORMAT_VERSION {
+        return Err(SceneError::UnsupportedVersion {
+            found: replay.scene.version,
+            supported: SCENE_FORMAT_VERSION,
+        });
+    }
+
+    replay.scene.upgrade();
+    Ok(replay)
+}
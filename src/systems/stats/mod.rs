@@ -0,0 +1,8 @@
+//! Live entity counts and session statistics
+
+pub mod session_stats;
+
+pub use session_stats::{
+    count_live_entities, count_session_events, export_summary_on_exit, LiveCounts,
+    SessionStats, SessionSummary, StatsError,
+};
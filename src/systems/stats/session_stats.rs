@@ -0,0 +1,220 @@
+//! Live entity counts and session counters, with a summary written on exit
+//!
+//! Counters are fed by the gameplay events, so they see every destruction,
+//! joint break, explosion and fire exactly once. When the app exits, the
+//! session is written to a JSON file, and appended as one row to a CSV file
+//! that collects every run.
+
+use std::fmt;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use bevy::prelude::*;
+use bevy::time::Real;
+use bevy::utils::HashSet;
+use bevy_rapier2d::prelude::*;
+use serde::Serialize;
+
+use crate::core::components::{Debris, RagdollPart};
+use crate::core::constants::{STATS_CSV_PATH, STATS_JSON_PATH};
+use crate::core::events::{
+    BodyDestroyed, ConnectionBreakCause, ConnectionBroken, Exploded, Ignited, JointBroken,
+};
+use crate::systems::effects::particles::Particle;
+use crate::systems::input::drag::Draggable;
+
+/// How many of each kind of entity exist right now
+#[derive(Resource, Default, Clone, Copy, Debug)]
+pub struct LiveCounts {
+    /// Groups of ragdoll parts still joined together
+    pub ragdolls: usize,
+    /// Bodies that can be dragged: ragdoll parts, bombs, boxes and blocks
+    pub bodies: usize,
+    pub debris: usize,
+    pub particles: usize,
+}
+
+/// Running totals since the app started
+#[derive(Resource, Default, Clone, Copy, Debug)]
+pub struct SessionStats {
+    /// Ragdoll joints and connections that broke under load
+    pub joints_broken: u32,
+    /// Ragdoll parts that came off the part they were joined to
+    pub limbs_severed: u32,
+    pub objects_destroyed: u32,
+    pub explosions: u32,
+    pub fires: u32,
+    pub peak_bodies: usize,
+    pub peak_particles: usize,
+}
+
+/// One session, as exported to the summary files
+#[derive(Serialize, Clone, Debug)]
+pub struct SessionSummary {
+    /// Seconds since the Unix epoch when the session ended
+    pub ended_at: u64,
+    /// Wall clock length of the session
+    pub real_secs: f32,
+    /// Simulated time, which stands still while paused
+    pub simulated_secs: f32,
+    pub joints_broken: u32,
+    pub limbs_severed: u32,
+    pub objects_destroyed: u32,
+    pub explosions: u32,
+    pub fires: u32,
+    pub peak_bodies: usize,
+    pub peak_particles: usize,
+}
+
+impl SessionSummary {
+    const CSV_HEADER: &'static str = "ended_at,real_secs,simulated_secs,joints_broken,\
+        limbs_severed,objects_destroyed,explosions,fires,peak_bodies,peak_particles";
+
+    fn csv_row(&self) -> String {
+        format!(
+            "{},{:.2},{:.2},{},{},{},{},{},{},{}",
+            self.ended_at,
+            self.real_secs,
+            self.simulated_secs,
+            self.joints_broken,
+            self.limbs_severed,
+            self.objects_destroyed,
+            self.explosions,
+            self.fires,
+            self.peak_bodies,
+            self.peak_particles
+        )
+    }
+}
+
+#[derive(Debug)]
+pub enum StatsError {
+    Io(std::io::Error),
+    Serialize(serde_json::Error),
+}
+
+impl fmt::Display for StatsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StatsError::Io(err) => write!(f, "i/o error: {err}"),
+            StatsError::Serialize(err) => write!(f, "could not serialize summary: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for StatsError {}
+
+impl From<std::io::Error> for StatsError {
+    fn from(err: std::io::Error) -> Self {
+        StatsError::Io(err)
+    }
+}
+
+pub fn write_summary_json(path: &Path, summary: &SessionSummary) -> Result<(), StatsError> {
+    let contents = serde_json::to_string_pretty(summary).map_err(StatsError::Serialize)?;
+    fs::write(path, contents)?;
+    Ok(())
+}
+
+/// Append the summary as a row, writing the header first if the file is new
+pub fn append_summary_csv(path: &Path, summary: &SessionSummary) -> Result<(), StatsError> {
+    let is_new = !path.exists();
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    if is_new {
+        writeln!(file, "{}", SessionSummary::CSV_HEADER)?;
+    }
+    writeln!(file, "{}", summary.csv_row())?;
+    Ok(())
+}
+
+pub fn count_live_entities(
+    mut counts: ResMut<LiveCounts>,
+    mut stats: ResMut<SessionStats>,
+    ragdoll_query: Query<(Entity, Has<ImpulseJoint>), With<RagdollPart>>,
+    ragdoll_joint_query: Query<&ImpulseJoint, With<RagdollPart>>,
+    body_query: Query<(), With<Draggable>>,
+    debris_query: Query<(), With<Debris>>,
+    particle_query: Query<&Visibility, With<Particle>>,
+) {
+    // A ragdoll is a part that is not hanging off another one but still holds some
+    let joint_parents: HashSet<Entity> = ragdoll_joint_query
+        .iter()
+        .map(|joint| joint.parent)
+        .collect();
+    let ragdolls = ragdoll_query
+        .iter()
+        .filter(|&(entity, has_parent)| !has_parent && joint_parents.contains(&entity))
+        .count();
+
+    *counts = LiveCounts {
+        ragdolls,
+        bodies: body_query.iter().count(),
+        debris: debris_query.iter().count(),
+        // Pooled particles are hidden while they wait to be reused
+        particles: particle_query
+            .iter()
+            .filter(|&&visibility| visibility != Visibility::Hidden)
+            .count(),
+    };
+    stats.peak_bodies = stats.peak_bodies.max(counts.bodies);
+    stats.peak_particles = stats.peak_particles.max(counts.particles);
+}
+
+pub fn count_session_events(
+    mut stats: ResMut<SessionStats>,
+    mut destroyed: EventReader<BodyDestroyed>,
+    mut joint_broken: EventReader<JointBroken>,
+    mut connection_broken: EventReader<ConnectionBroken>,
+    mut exploded: EventReader<Exploded>,
+    mut ignited: EventReader<Ignited>,
+) {
+    let limbs_severed = joint_broken.read().count() as u32;
+    let connections_snapped = connection_broken
+        .read()
+        .filter(|event| event.cause == ConnectionBreakCause::Overload)
+        .count() as u32;
+
+    stats.limbs_severed += limbs_severed;
+    stats.joints_broken += limbs_severed + connections_snapped;
+    stats.objects_destroyed += destroyed.read().count() as u32;
+    stats.explosions += exploded.read().count() as u32;
+    stats.fires += ignited.read().count() as u32;
+}
+
+/// Write the session summary when the app is about to exit
+pub fn export_summary_on_exit(
+    mut exit_events: EventReader<AppExit>,
+    stats: Res<SessionStats>,
+    real_time: Res<Time<Real>>,
+    virtual_time: Res<Time<Virtual>>,
+) {
+    if exit_events.read().next().is_none() {
+        return;
+    }
+
+    let ended_at = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |since_epoch| since_epoch.as_secs());
+    let summary = SessionSummary {
+        ended_at,
+        real_secs: real_time.elapsed_secs(),
+        simulated_secs: virtual_time.elapsed_secs(),
+        joints_broken: stats.joints_broken,
+        limbs_severed: stats.limbs_severed,
+        objects_destroyed: stats.objects_destroyed,
+        explosions: stats.explosions,
+        fires: stats.fires,
+        peak_bodies: stats.peak_bodies,
+        peak_particles: stats.peak_particles,
+    };
+
+    match write_summary_json(Path::new(STATS_JSON_PATH), &summary) {
+        Ok(()) => info!("Saved session summary to {STATS_JSON_PATH}"),
+        Err(err) => error!("Failed to save session summary to {STATS_JSON_PATH}: {err}"),
+    }
+    if let Err(err) = append_summary_csv(Path::new(STATS_CSV_PATH), &summary) {
+        error!("Failed to append session summary to {STATS_CSV_PATH}: {err}");
+    }
+}
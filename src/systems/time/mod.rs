@@ -0,0 +1,8 @@
+//! Simulation speed controls
+
+pub mod time_controls;
+
+pub use time_controls::{
+    apply_time_controls, handle_time_control_events, time_control_hotkeys, TimeControlEvent,
+    TimeControls,
+};
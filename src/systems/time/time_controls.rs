@@ -0,0 +1,165 @@
+//! Pausing, single-stepping and slow motion
+//!
+//! Gameplay timers (bomb fuses, fire, shockwaves, particles) all tick with
+//! `Res<Time>`, which follows `Time<Virtual>` in the main schedules, so pausing
+//! and scaling virtual time scales them together. Rapier is kept in step by
+//! switching its pipeline off while paused and scaling the cap on its
+//! variable time step, which would otherwise clamp fast-forward back to 1x.
+
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+use crate::systems::input::action_map::{ActionInput, InputAction};
+use crate::systems::replay::ReplayState;
+
+/// Speeds offered by the speed up and slow down controls
+pub const TIME_SCALE_PRESETS: [f32; 6] = [0.1, 0.25, 0.5, 1.0, 1.5, 2.0];
+
+/// Rapier's default cap on a variable time step, at 1x speed
+const PHYSICS_MAX_DT: f32 = 1.0 / 60.0;
+
+#[derive(Event, Clone, Copy, Debug)]
+pub enum TimeControlEvent {
+    TogglePause,
+    /// Advance a paused simulation by a single frame
+    Step,
+    SpeedUp,
+    SlowDown,
+    /// Set the speed directly, clamped to the preset range
+    SetScale(f32),
+}
+
+#[derive(Resource, Clone, Debug)]
+pub struct TimeControls {
+    pub paused: bool,
+    /// Simulation speed relative to real time
+    pub scale: f32,
+    /// Frames to run before pausing again
+    pending_steps: u32,
+}
+
+impl Default for TimeControls {
+    fn default() -> Self {
+        Self {
+            paused: false,
+            scale: 1.0,
+            pending_steps: 0,
+        }
+    }
+}
+
+impl TimeControls {
+    fn set_scale(&mut self, scale: f32) {
+        let min = TIME_SCALE_PRESETS[0];
+        let max = TIME_SCALE_PRESETS[TIME_SCALE_PRESETS.len() - 1];
+        self.scale = scale.clamp(min, max);
+    }
+
+    fn apply(&mut self, event: TimeControlEvent) {
+        match event {
+            TimeControlEvent::TogglePause => {
+                self.paused = !self.paused;
+                self.pending_steps = 0;
+            }
+            TimeControlEvent::Step => {
+                if self.paused {
+                    self.pending_steps += 1;
+                }
+            }
+            TimeControlEvent::SpeedUp => {
+                let faster = TIME_SCALE_PRESETS
+                    .into_iter()
+                    .find(|&preset| preset > self.scale + f32::EPSILON);
+                self.set_scale(faster.unwrap_or(self.scale));
+            }
+            TimeControlEvent::SlowDown => {
+                let slower = TIME_SCALE_PRESETS
+                    .into_iter()
+                    .rev()
+                    .find(|&preset| preset < self.scale - f32::EPSILON);
+                self.set_scale(slower.unwrap_or(self.scale));
+            }
+            TimeControlEvent::SetScale(scale) => self.set_scale(scale),
+        }
+    }
+}
+
+pub fn time_control_hotkeys(input: ActionInput, mut events: EventWriter<TimeControlEvent>) {
+    if input.just_pressed(InputAction::TogglePause) {
+        events.send(TimeControlEvent::TogglePause);
+    }
+    if input.just_pressed(InputAction::StepFrame) {
+        events.send(TimeControlEvent::Step);
+    }
+    if input.just_pressed(InputAction::SpeedUp) {
+        events.send(TimeControlEvent::SpeedUp);
+    }
+    if input.just_pressed(InputAction::SlowDown) {
+        events.send(TimeControlEvent::SlowDown);
+    }
+}
+
+pub fn handle_time_control_events(
+    mut events: EventReader<TimeControlEvent>,
+    mut controls: ResMut<TimeControls>,
+    replay_state: Res<ReplayState>,
+) {
+    // Replays pin the time step, and recorded frames must match played back ones
+    if !matches!(*replay_state, ReplayState::Idle) {
+        if events.read().count() > 0 {
+            warn!("Time controls are disabled while recording or playing back a replay");
+        }
+        return;
+    }
+
+    for &event in events.read() {
+        controls.apply(event);
+    }
+}
+
+/// Set up virtual time and Rapier for the next frame
+pub fn apply_time_controls(
+    mut controls: ResMut<TimeControls>,
+    mut virtual_time: ResMut<Time<Virtual>>,
+    mut timestep_mode: ResMut<TimestepMode>,
+    mut rapier_config: Query<&mut RapierConfiguration>,
+) {
+    let running = !controls.paused || controls.pending_steps > 0;
+    if controls.paused && controls.pending_steps > 0 {
+        controls.pending_steps -= 1;
+    }
+
+    if running == virtual_time.is_paused() {
+        if running {
+            virtual_time.unpause();
+        } else {
+            virtual_time.pause();
+        }
+    }
+    if virtual_time.relative_speed() != controls.scale {
+        virtual_time.set_relative_speed(controls.scale);
+    }
+
+    for mut config in rapier_config.iter_mut() {
+        if config.physics_pipeline_active != running {
+            config.physics_pipeline_active = running;
+        }
+    }
+
+    // Fixed steps belong to replay sessions, which run at 1x
+    if let TimestepMode::Variable {
+        max_dt,
+        time_scale,
+        substeps,
+    } = *timestep_mode
+    {
+        let scaled_max_dt = PHYSICS_MAX_DT * controls.scale;
+        if max_dt != scaled_max_dt {
+            *timestep_mode = TimestepMode::Variable {
+                max_dt: scaled_max_dt,
+                time_scale,
+                substeps,
+            };
+        }
+    }
+}
@@ -1,5 +1,28 @@
 //! User interface components and systems
 
+pub mod ui_bindings;
+pub mod ui_debug;
+pub mod ui_inspector;
+pub mod ui_levels;
+pub mod ui_name_prompt;
+pub mod ui_polygon;
+pub mod ui_prefabs;
+pub mod ui_shapes;
+pub mod ui_stats;
+pub mod ui_time_controls;
 pub mod ui_topbar;
 
-pub use ui_topbar::{handle_button_clicks, setup_ui_topbar, spawn_selected_object_on_click, sync_selection_with_connection_system, SelectedObject};
+pub use ui_bindings::{handle_binding_buttons, handle_bindings_menu_buttons, update_bindings_screen};
+pub use ui_debug::{handle_debug_layer_buttons, update_debug_menu};
+pub use ui_inspector::{
+    handle_inspector_edit_buttons, select_inspected_body, setup_inspector, update_inspector,
+    update_inspector_values, InspectorState,
+};
+pub use ui_levels::{handle_level_buttons, update_level_panel};
+pub use ui_name_prompt::{type_prompt_name, update_name_prompt, NamePrompt};
+pub use ui_polygon::{handle_polygon_material_buttons, update_polygon_panel};
+pub use ui_prefabs::{handle_prefab_save_button, update_prefab_palette};
+pub use ui_shapes::{handle_shape_buttons, update_shape_panel};
+pub use ui_stats::{update_stats_hud, StatsHud};
+pub use ui_time_controls::{handle_time_control_buttons, setup_time_controls, update_time_scale_label};
+pub use ui_topbar::{handle_action_buttons, handle_button_clicks, setup_ui_topbar, spawn_selected_object_on_click, sync_selection_with_connection_system, SelectedObject};
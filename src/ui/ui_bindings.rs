@@ -0,0 +1,273 @@
+use bevy::prelude::*;
+use bevy::ui::FocusPolicy;
+
+use crate::entities::spawnable::SpawnableRegistry;
+use crate::systems::input::action_map::{
+    reset_bindings, ActionMap, BindingTarget, InputAction, RebindState,
+};
+
+/// Root node of the bindings screen overlay
+#[derive(Component)]
+pub struct BindingsScreen;
+
+/// Button that waits for a new binding for `target` when pressed
+#[derive(Component)]
+pub struct BindingButton {
+    pub target: BindingTarget,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum BindingsMenuAction {
+    ResetDefaults,
+    Close,
+}
+
+#[derive(Component)]
+pub struct BindingsMenuButton {
+    pub action: BindingsMenuAction,
+}
+
+/// Rebuild the bindings screen whenever it is opened, closed or a binding changes
+pub fn update_bindings_screen(
+    mut commands: Commands,
+    state: Res<RebindState>,
+    map: Res<ActionMap>,
+    registry: Res<SpawnableRegistry>,
+    screen_query: Query<Entity, With<BindingsScreen>>,
+) {
+    if !state.is_changed() && !map.is_changed() {
+        return;
+    }
+
+    for entity in screen_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+
+    if state.is_open {
+        spawn_bindings_screen(&mut commands, &state, &map, &registry);
+    }
+}
+
+fn spawn_bindings_screen(
+    commands: &mut Commands,
+    state: &RebindState,
+    map: &ActionMap,
+    registry: &SpawnableRegistry,
+) {
+    let conflicts = map.conflicts();
+    let is_conflicting = |target: &BindingTarget| {
+        conflicts
+            .iter()
+            .any(|(first, second)| first == target || second == target)
+    };
+    let spawn_targets = registry
+        .iter()
+        .map(|(_, spawnable)| BindingTarget::Spawn(spawnable.id.to_string()));
+    let targets: Vec<_> = spawn_targets
+        .chain(InputAction::ALL.map(BindingTarget::Action))
+        .collect();
+
+    commands
+        .spawn((
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                position_type: PositionType::Absolute,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.6)),
+            FocusPolicy::Block,
+            GlobalZIndex(10),
+            BindingsScreen,
+        ))
+        .with_children(|parent| {
+            parent
+                .spawn((
+                    Node {
+                        flex_direction: FlexDirection::Column,
+                        padding: UiRect::all(Val::Px(20.0)),
+                        row_gap: Val::Px(6.0),
+                        ..default()
+                    },
+                    BackgroundColor(Color::srgb(0.15, 0.15, 0.15)),
+                ))
+                .with_children(|panel| {
+                    spawn_label(panel, "Key Bindings", 22.0, Color::srgb(0.9, 0.9, 0.9));
+
+                    // Two columns, so every action fits on a 720 pixel high window
+                    panel
+                        .spawn(Node {
+                            flex_direction: FlexDirection::Column,
+                            flex_wrap: FlexWrap::Wrap,
+                            max_height: Val::Px(400.0),
+                            row_gap: Val::Px(6.0),
+                            column_gap: Val::Px(24.0),
+                            ..default()
+                        })
+                        .with_children(|list| {
+                            for target in targets {
+                                let is_capturing = state.capturing.as_ref() == Some(&target);
+                                let color = if is_capturing {
+                                    Color::srgb(0.3, 0.5, 0.7)
+                                } else if is_conflicting(&target) {
+                                    Color::srgb(0.7, 0.2, 0.2)
+                                } else {
+                                    Color::srgb(0.25, 0.25, 0.25)
+                                };
+                                let binding = match map.target_binding(&target) {
+                                    _ if is_capturing => "...".to_string(),
+                                    Some(binding) => binding.to_string(),
+                                    None => "Unbound".to_string(),
+                                };
+
+                                let label = target.label(registry);
+                                spawn_binding_row(list, target, &label, &binding, color);
+                            }
+                        });
+
+                    if let Some(message) = &state.message {
+                        spawn_label(panel, message, 14.0, Color::srgb(0.9, 0.8, 0.4));
+                    } else if !conflicts.is_empty() {
+                        spawn_label(
+                            panel,
+                            "Actions marked red share a binding",
+                            14.0,
+                            Color::srgb(0.9, 0.4, 0.4),
+                        );
+                    }
+
+                    panel
+                        .spawn(Node {
+                            column_gap: Val::Px(12.0),
+                            margin: UiRect::top(Val::Px(8.0)),
+                            ..default()
+                        })
+                        .with_children(|row| {
+                            let color = Color::srgb(0.25, 0.25, 0.25);
+                            spawn_button(
+                                row,
+                                BindingsMenuButton {
+                                    action: BindingsMenuAction::ResetDefaults,
+                                },
+                                "Reset",
+                                100.0,
+                                color,
+                            );
+                            spawn_button(
+                                row,
+                                BindingsMenuButton {
+                                    action: BindingsMenuAction::Close,
+                                },
+                                "Close",
+                                100.0,
+                                color,
+                            );
+                        });
+                });
+        });
+}
+
+fn spawn_binding_row(
+    parent: &mut ChildBuilder,
+    target: BindingTarget,
+    label: &str,
+    binding: &str,
+    color: Color,
+) {
+    parent
+        .spawn(Node {
+            column_gap: Val::Px(12.0),
+            align_items: AlignItems::Center,
+            ..default()
+        })
+        .with_children(|row| {
+            row.spawn(Node {
+                width: Val::Px(180.0),
+                ..default()
+            })
+            .with_children(|cell| {
+                spawn_label(cell, label, 16.0, Color::srgb(0.8, 0.8, 0.8));
+            });
+            spawn_button(row, BindingButton { target }, binding, 140.0, color);
+        });
+}
+
+fn spawn_label(parent: &mut ChildBuilder, text: &str, font_size: f32, color: Color) {
+    parent.spawn((
+        Text::new(text),
+        TextFont {
+            font_size,
+            ..default()
+        },
+        TextColor(color),
+    ));
+}
+
+fn spawn_button(
+    parent: &mut ChildBuilder,
+    marker: impl Component,
+    label: &str,
+    width: f32,
+    color: Color,
+) {
+    parent
+        .spawn((
+            Button,
+            Node {
+                width: Val::Px(width),
+                height: Val::Px(30.0),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            BackgroundColor(color),
+            marker,
+        ))
+        .with_children(|parent| {
+            spawn_label(parent, label, 16.0, Color::srgb(0.9, 0.9, 0.9));
+        });
+}
+
+/// Start waiting for a new binding when an action's button is pressed.
+/// While waiting, clicks are captured as the new binding instead.
+pub fn handle_binding_buttons(
+    interaction_query: Query<(&Interaction, &BindingButton), Changed<Interaction>>,
+    mut state: ResMut<RebindState>,
+    registry: Res<SpawnableRegistry>,
+) {
+    for (interaction, button) in interaction_query.iter() {
+        if *interaction == Interaction::Pressed && state.capturing.is_none() {
+            state.capturing = Some(button.target.clone());
+            state.message = Some(format!(
+                "Press a key or mouse button for {} (Esc to cancel)",
+                button.target.label(&registry)
+            ));
+        }
+    }
+}
+
+pub fn handle_bindings_menu_buttons(
+    interaction_query: Query<(&Interaction, &BindingsMenuButton), Changed<Interaction>>,
+    mut state: ResMut<RebindState>,
+    mut map: ResMut<ActionMap>,
+    registry: Res<SpawnableRegistry>,
+) {
+    for (interaction, button) in interaction_query.iter() {
+        if *interaction != Interaction::Pressed || state.capturing.is_some() {
+            continue;
+        }
+
+        match button.action {
+            BindingsMenuAction::ResetDefaults => {
+                reset_bindings(&mut map, &registry);
+                state.message = Some("Bindings reset to defaults".to_string());
+            }
+            BindingsMenuAction::Close => {
+                state.is_open = false;
+                state.message = None;
+            }
+        }
+    }
+}
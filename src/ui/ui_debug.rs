@@ -0,0 +1,98 @@
+use bevy::prelude::*;
+
+use crate::systems::debug::{DebugLayer, DebugOverlay};
+use crate::systems::input::action_map::{ActionMap, InputAction};
+use crate::ui::ui_prefabs::create_palette_button;
+
+/// Column of overlay toggles on the left, shown while the debug menu is open
+#[derive(Component)]
+pub struct DebugMenu;
+
+#[derive(Component)]
+pub struct DebugLayerButton {
+    pub layer: DebugLayer,
+    pub is_enabled: bool,
+}
+
+/// Rebuild the menu when it is opened or closed, or a layer is switched
+pub fn update_debug_menu(
+    mut commands: Commands,
+    overlay: Res<DebugOverlay>,
+    map: Res<ActionMap>,
+    menu_query: Query<Entity, With<DebugMenu>>,
+) {
+    if !overlay.is_changed() && !map.is_changed() {
+        return;
+    }
+
+    for entity in menu_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+
+    if !overlay.is_menu_open {
+        return;
+    }
+
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                // Right of the statistics HUD, which starts at the same height
+                top: Val::Px(130.0),
+                left: Val::Px(220.0),
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(6.0),
+                padding: UiRect::all(Val::Px(5.0)),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.1, 0.1, 0.1, 0.9)),
+            DebugMenu,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new(format!("Debug ({})", map.binding(InputAction::ToggleDebugMenu))),
+                TextFont {
+                    font_size: 14.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(0.9, 0.9, 0.9)),
+            ));
+
+            for layer in DebugLayer::ALL {
+                let is_enabled = overlay.is_enabled(layer);
+                create_palette_button(
+                    parent,
+                    DebugLayerButton { layer, is_enabled },
+                    layer.label(),
+                    is_enabled,
+                );
+            }
+        });
+}
+
+pub fn handle_debug_layer_buttons(
+    mut interaction_query: Query<
+        (&Interaction, &DebugLayerButton, &mut BackgroundColor),
+        Changed<Interaction>,
+    >,
+    mut overlay: ResMut<DebugOverlay>,
+) {
+    for (interaction, button, mut bg_color) in interaction_query.iter_mut() {
+        match *interaction {
+            Interaction::Pressed => {
+                *bg_color = BackgroundColor(Color::srgb(0.3, 0.5, 0.7));
+                overlay.toggle(button.layer);
+            }
+            Interaction::Hovered => {
+                *bg_color = BackgroundColor(Color::srgb(0.35, 0.35, 0.35));
+            }
+            Interaction::None => {
+                *bg_color = if button.is_enabled {
+                    BackgroundColor(Color::srgb(0.3, 0.5, 0.7))
+                } else {
+                    BackgroundColor(Color::srgb(0.25, 0.25, 0.25))
+                };
+            }
+        }
+    }
+}
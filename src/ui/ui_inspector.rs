@@ -0,0 +1,565 @@
+use std::fmt::Write;
+
+use bevy::ecs::query::QueryItem;
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+use crate::core::components::{Bomb, Connection, Flammable, Health, OnFire, RagdollPart};
+use crate::core::entity_finder::find_body_at_point;
+use crate::core::utils::get_cursor_world_position;
+use crate::entities::obstacles::iron_block::IronBlock;
+use crate::entities::obstacles::polygon::{PolygonBody, PolygonMaterial};
+use crate::entities::obstacles::shape::{BodyShape, ShapedBody};
+use crate::entities::obstacles::wooden_box::WoodenBox;
+use crate::systems::damage::damage::{Fractured, JointHealth};
+use crate::systems::input::action_map::{ActionInput, InputAction};
+use crate::systems::input::drag::Draggable;
+use crate::systems::replay::ReplayState;
+
+/// Body shown in the inspector panel
+#[derive(Resource, Default)]
+pub struct InspectorState {
+    pub entity: Option<Entity>,
+}
+
+#[derive(Component)]
+pub struct InspectorPanel;
+
+#[derive(Component)]
+pub struct InspectorText;
+
+/// Physical properties that can be changed on the inspected body
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum InspectorProperty {
+    Density,
+    Friction,
+    Restitution,
+    LinearDamping,
+    AngularDamping,
+    GravityScale,
+    MaxHealth,
+    Flammable,
+}
+
+impl InspectorProperty {
+    pub const ALL: [InspectorProperty; 8] = [
+        InspectorProperty::Density,
+        InspectorProperty::Friction,
+        InspectorProperty::Restitution,
+        InspectorProperty::LinearDamping,
+        InspectorProperty::AngularDamping,
+        InspectorProperty::GravityScale,
+        InspectorProperty::MaxHealth,
+        InspectorProperty::Flammable,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            InspectorProperty::Density => "Density",
+            InspectorProperty::Friction => "Friction",
+            InspectorProperty::Restitution => "Restitution",
+            InspectorProperty::LinearDamping => "Linear damping",
+            InspectorProperty::AngularDamping => "Angular damping",
+            InspectorProperty::GravityScale => "Gravity scale",
+            InspectorProperty::MaxHealth => "Max health",
+            InspectorProperty::Flammable => "Flammable",
+        }
+    }
+
+    /// The value one step up (`direction` 1) or down (`direction` -1) from `value`
+    fn step(self, value: f32, direction: f32) -> f32 {
+        match self {
+            // Densities span iron (7.8) to debris (0.3), so step by ratio
+            InspectorProperty::Density => (value * 1.25_f32.powf(direction)).clamp(0.05, 50.0),
+            InspectorProperty::Friction => (value + 0.1 * direction).clamp(0.0, 2.0),
+            InspectorProperty::Restitution => (value + 0.1 * direction).clamp(0.0, 1.0),
+            InspectorProperty::LinearDamping | InspectorProperty::AngularDamping => {
+                (value + 0.1 * direction).clamp(0.0, 10.0)
+            }
+            InspectorProperty::GravityScale => (value + 0.25 * direction).clamp(-2.0, 4.0),
+            InspectorProperty::MaxHealth => (value + 10.0 * direction).clamp(10.0, 1000.0),
+            InspectorProperty::Flammable => direction.clamp(0.0, 1.0),
+        }
+    }
+
+    fn format(self, value: f32) -> String {
+        match self {
+            InspectorProperty::MaxHealth => format!("{value:.0}"),
+            InspectorProperty::Flammable if value > 0.0 => "yes".to_string(),
+            InspectorProperty::Flammable => "no".to_string(),
+            _ => format!("{value:.2}"),
+        }
+    }
+}
+
+/// Button stepping `property` of the inspected body up or down
+#[derive(Component)]
+pub struct InspectorEditButton {
+    pub property: InspectorProperty,
+    /// 1.0 to increase, -1.0 to decrease
+    pub direction: f32,
+}
+
+/// Text showing the current value of `property`
+#[derive(Component)]
+pub struct InspectorValueText {
+    pub property: InspectorProperty,
+}
+
+/// Ignition threshold given to bodies made flammable from the inspector,
+/// the same as wooden boxes and ragdoll parts spawn with
+const DEFAULT_IGNITION_THRESHOLD: f32 = 0.5;
+
+type EditableBody = (
+    Option<&'static ColliderMassProperties>,
+    Option<&'static Friction>,
+    Option<&'static Restitution>,
+    Option<&'static Damping>,
+    Option<&'static GravityScale>,
+    Option<&'static Health>,
+    Has<Flammable>,
+);
+
+/// Current value of `property`, using Rapier's defaults for components the body was spawned without
+fn property_value(property: InspectorProperty, body: &QueryItem<EditableBody>) -> Option<f32> {
+    let &(mass_props, friction, restitution, damping, gravity, health, is_flammable) = body;
+    let value = match property {
+        InspectorProperty::Density => match mass_props {
+            Some(ColliderMassProperties::Density(density)) => *density,
+            None => 1.0,
+            // Bodies with an explicit mass have no density to edit
+            Some(_) => return None,
+        },
+        InspectorProperty::Friction => {
+            friction.map_or(Friction::default().coefficient, |f| f.coefficient)
+        }
+        InspectorProperty::Restitution => {
+            restitution.map_or(Restitution::default().coefficient, |r| r.coefficient)
+        }
+        InspectorProperty::LinearDamping => damping.map_or(0.0, |d| d.linear_damping),
+        InspectorProperty::AngularDamping => damping.map_or(0.0, |d| d.angular_damping),
+        InspectorProperty::GravityScale => gravity.map_or(1.0, |g| g.0),
+        InspectorProperty::MaxHealth => health?.max,
+        InspectorProperty::Flammable => {
+            if is_flammable {
+                1.0
+            } else {
+                0.0
+            }
+        }
+    };
+    Some(value)
+}
+
+type InspectedBody = (
+    Option<&'static Health>,
+    Option<&'static JointHealth>,
+    Option<&'static Fractured>,
+    Option<&'static OnFire>,
+    Option<&'static ReadMassProperties>,
+    Option<&'static Velocity>,
+    Option<&'static PolygonBody>,
+    Option<&'static ShapedBody>,
+    Has<RagdollPart>,
+    Has<Bomb>,
+    Has<WoodenBox>,
+    Has<IronBlock>,
+);
+
+/// Spawn the inspector in the bottom left corner, hidden until a body is inspected
+pub fn setup_inspector(mut commands: Commands) {
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                bottom: Val::Px(10.0),
+                left: Val::Px(10.0),
+                width: Val::Px(320.0),
+                padding: UiRect::all(Val::Px(10.0)),
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(4.0),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.1, 0.1, 0.1, 0.9)),
+            Visibility::Hidden,
+            InspectorPanel,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new(""),
+                TextFont {
+                    font_size: 14.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(0.9, 0.9, 0.9)),
+                InspectorText,
+            ));
+
+            for property in InspectorProperty::ALL {
+                create_property_row(parent, property);
+            }
+        });
+}
+
+fn create_property_row(parent: &mut ChildBuilder, property: InspectorProperty) {
+    parent
+        .spawn(Node {
+            align_items: AlignItems::Center,
+            column_gap: Val::Px(6.0),
+            ..default()
+        })
+        .with_children(|row| {
+            row.spawn(Node {
+                width: Val::Px(130.0),
+                ..default()
+            })
+            .with_children(|cell| {
+                cell.spawn((
+                    Text::new(property.label()),
+                    TextFont {
+                        font_size: 14.0,
+                        ..default()
+                    },
+                    TextColor(Color::srgb(0.8, 0.8, 0.8)),
+                ));
+            });
+
+            create_edit_button(row, property, -1.0, "-");
+            row.spawn(Node {
+                width: Val::Px(60.0),
+                justify_content: JustifyContent::Center,
+                ..default()
+            })
+            .with_children(|cell| {
+                cell.spawn((
+                    Text::new(""),
+                    TextFont {
+                        font_size: 14.0,
+                        ..default()
+                    },
+                    TextColor(Color::srgb(0.9, 0.9, 0.9)),
+                    InspectorValueText { property },
+                ));
+            });
+            create_edit_button(row, property, 1.0, "+");
+        });
+}
+
+fn create_edit_button(
+    parent: &mut ChildBuilder,
+    property: InspectorProperty,
+    direction: f32,
+    label: &str,
+) {
+    parent
+        .spawn((
+            Button,
+            Node {
+                width: Val::Px(30.0),
+                height: Val::Px(22.0),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            BackgroundColor(Color::srgb(0.25, 0.25, 0.25)),
+            InspectorEditButton {
+                property,
+                direction,
+            },
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new(label),
+                TextFont {
+                    font_size: 14.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(0.9, 0.9, 0.9)),
+            ));
+        });
+}
+
+/// Inspect the body under the cursor, or close the inspector when clicking empty space
+pub fn select_inspected_body(
+    input: ActionInput,
+    mut state: ResMut<InspectorState>,
+    windows: Query<&Window>,
+    camera_q: Query<(&Camera, &GlobalTransform)>,
+    draggable_query: Query<(), With<Draggable>>,
+    rapier_context: Query<&RapierContext>,
+) {
+    if !input.just_pressed(InputAction::Inspect) {
+        return;
+    }
+
+    state.entity = get_cursor_world_position(&windows, &camera_q)
+        .zip(rapier_context.get_single().ok())
+        .and_then(|(world_pos, context)| find_body_at_point(context, world_pos))
+        .filter(|&entity| draggable_query.contains(entity));
+}
+
+/// Refresh the inspector text every frame while a body is inspected
+pub fn update_inspector(
+    state: Res<InspectorState>,
+    body_query: Query<InspectedBody>,
+    connection_query: Query<&Connection>,
+    mut panel_query: Query<&mut Visibility, With<InspectorPanel>>,
+    mut text_query: Query<&mut Text, With<InspectorText>>,
+) {
+    let shown = if state.entity.is_some() {
+        Visibility::Inherited
+    } else {
+        Visibility::Hidden
+    };
+    for mut visibility in panel_query.iter_mut() {
+        visibility.set_if_neq(shown);
+    }
+
+    let Some(entity) = state.entity else {
+        return;
+    };
+
+    let report = match body_query.get(entity) {
+        Ok(body) => describe_body(entity, body, &connection_query),
+        // Stay open, so a destroyed body reads differently from closing the inspector
+        Err(_) => format!("Body {entity} no longer exists"),
+    };
+
+    for mut text in text_query.iter_mut() {
+        if text.0 != report {
+            text.0 = report.clone();
+        }
+    }
+}
+
+fn describe_body(
+    entity: Entity,
+    body: QueryItem<InspectedBody>,
+    connection_query: &Query<&Connection>,
+) -> String {
+    let (
+        health,
+        joint_health,
+        fractured,
+        on_fire,
+        mass,
+        velocity,
+        polygon,
+        shaped,
+        is_ragdoll,
+        is_bomb,
+        is_wooden,
+        is_iron,
+    ) = body;
+
+    let kind = if let Some(polygon) = polygon {
+        match polygon.material {
+            PolygonMaterial::Wood => "Wooden polygon",
+            PolygonMaterial::Iron => "Iron polygon",
+            PolygonMaterial::Static => "Static polygon",
+        }
+    } else if is_ragdoll {
+        "Ragdoll part"
+    } else if is_bomb {
+        "Bomb"
+    } else if is_wooden {
+        match shaped.map(|shaped| shaped.shape) {
+            Some(BodyShape::Circle) => "Wooden circle",
+            Some(BodyShape::Triangle) => "Wooden triangle",
+            Some(BodyShape::Capsule) => "Wooden capsule",
+            Some(BodyShape::Rectangle) | None => "Wooden box",
+        }
+    } else if is_iron {
+        match shaped.map(|shaped| shaped.shape) {
+            Some(BodyShape::Circle) => "Iron circle",
+            Some(BodyShape::Triangle) => "Iron triangle",
+            Some(BodyShape::Capsule) => "Iron capsule",
+            Some(BodyShape::Rectangle) | None => "Iron block",
+        }
+    } else {
+        "Body"
+    };
+
+    let mut report = format!("{kind} {entity}");
+
+    if let Some(health) = health {
+        let _ = write!(
+            report,
+            "\nHealth: {:.1} / {:.1}",
+            health.current, health.max
+        );
+    }
+    if let Some(joint) = joint_health {
+        let _ = write!(
+            report,
+            "\nJoint to {}: {:.1} / {:.1}",
+            joint.parent_entity, joint.current, joint.max
+        );
+    }
+    if let Some(fractured) = fractured {
+        let _ = write!(report, "\nFractured: severity {:.2}", fractured.severity);
+    }
+    if let Some(fire) = on_fire {
+        let _ = write!(
+            report,
+            "\nOn fire: intensity {:.2}, {:.1}s left",
+            fire.intensity,
+            fire.duration.remaining_secs()
+        );
+    }
+    match mass {
+        Some(mass) => {
+            let _ = write!(report, "\nMass: {:.2}", mass.get().mass);
+        }
+        None => report.push_str("\nMass: unknown"),
+    }
+    if let Some(velocity) = velocity {
+        let _ = write!(
+            report,
+            "\nVelocity: ({:.0}, {:.0}), {:.0} px/s, spin {:.2} rad/s",
+            velocity.linvel.x,
+            velocity.linvel.y,
+            velocity.linvel.length(),
+            velocity.angvel
+        );
+    }
+
+    for connection in connection_query.iter() {
+        let other = if connection.a == entity {
+            connection.b
+        } else if connection.b == entity {
+            connection.a
+        } else {
+            continue;
+        };
+        let _ = write!(
+            report,
+            "\n{:?} connection to {other}: force {} / {} ({:.0}%)",
+            connection.kind,
+            format_force(connection.current_force),
+            format_force(connection.break_force),
+            100.0 * connection.current_force / connection.break_force.max(f32::EPSILON)
+        );
+    }
+
+    report
+}
+
+/// Joint forces run into the millions, so they are shortened like 2.8M
+fn format_force(force: f32) -> String {
+    if force >= 1_000_000.0 {
+        format!("{:.1}M", force / 1_000_000.0)
+    } else if force >= 1_000.0 {
+        format!("{:.1}k", force / 1_000.0)
+    } else {
+        format!("{force:.0}")
+    }
+}
+
+/// Step a property of the inspected body, writing it straight to the Rapier components
+pub fn handle_inspector_edit_buttons(
+    mut commands: Commands,
+    mut interaction_query: Query<
+        (&Interaction, &InspectorEditButton, &mut BackgroundColor),
+        Changed<Interaction>,
+    >,
+    state: Res<InspectorState>,
+    body_query: Query<EditableBody>,
+    replay_state: Res<ReplayState>,
+) {
+    for (interaction, button, mut bg_color) in interaction_query.iter_mut() {
+        match *interaction {
+            Interaction::Pressed => {
+                *bg_color = BackgroundColor(Color::srgb(0.3, 0.5, 0.7));
+            }
+            Interaction::Hovered => {
+                *bg_color = BackgroundColor(Color::srgb(0.35, 0.35, 0.35));
+                continue;
+            }
+            Interaction::None => {
+                *bg_color = BackgroundColor(Color::srgb(0.25, 0.25, 0.25));
+                continue;
+            }
+        }
+
+        // Edits are not part of the recorded actions, so a replay could not repeat them
+        if !matches!(*replay_state, ReplayState::Idle) {
+            warn!("Inspector edits are disabled while recording or playing back a replay");
+            continue;
+        }
+
+        let Some(entity) = state.entity else {
+            continue;
+        };
+        let Ok(body) = body_query.get(entity) else {
+            continue;
+        };
+        let Some(value) = property_value(button.property, &body) else {
+            continue;
+        };
+        let value = button.property.step(value, button.direction);
+
+        let (_, _, _, damping, _, health, _) = body;
+        let mut entity_commands = commands.entity(entity);
+        match button.property {
+            InspectorProperty::Density => {
+                entity_commands.insert(ColliderMassProperties::Density(value));
+            }
+            InspectorProperty::Friction => {
+                entity_commands.insert(Friction::coefficient(value));
+            }
+            InspectorProperty::Restitution => {
+                entity_commands.insert(Restitution::coefficient(value));
+            }
+            InspectorProperty::LinearDamping => {
+                let mut damping = damping.copied().unwrap_or_default();
+                damping.linear_damping = value;
+                entity_commands.insert(damping);
+            }
+            InspectorProperty::AngularDamping => {
+                let mut damping = damping.copied().unwrap_or_default();
+                damping.angular_damping = value;
+                entity_commands.insert(damping);
+            }
+            InspectorProperty::GravityScale => {
+                entity_commands.insert(GravityScale(value));
+            }
+            InspectorProperty::MaxHealth => {
+                if let Some(health) = health {
+                    entity_commands.insert(Health {
+                        current: health.current.min(value),
+                        max: value,
+                    });
+                }
+            }
+            InspectorProperty::Flammable => {
+                if value > 0.0 {
+                    entity_commands.insert(Flammable {
+                        ignition_threshold: DEFAULT_IGNITION_THRESHOLD,
+                    });
+                } else {
+                    entity_commands.remove::<Flammable>();
+                }
+            }
+        }
+    }
+}
+
+pub fn update_inspector_values(
+    state: Res<InspectorState>,
+    body_query: Query<EditableBody>,
+    mut text_query: Query<(&mut Text, &InspectorValueText)>,
+) {
+    let body = state.entity.and_then(|entity| body_query.get(entity).ok());
+
+    for (mut text, value_text) in text_query.iter_mut() {
+        let value = body
+            .as_ref()
+            .and_then(|body| property_value(value_text.property, body))
+            .map(|value| value_text.property.format(value))
+            .unwrap_or_else(|| "-".to_string());
+        if text.0 != value {
+            text.0 = value;
+        }
+    }
+}
@@ -0,0 +1,150 @@
+use bevy::prelude::*;
+
+use crate::systems::input::action_map::{ActionMap, InputAction};
+use crate::systems::input::level_editor::LevelEditor;
+use crate::systems::persistence::{CurrentLevel, LevelLibrary, LoadLevelEvent};
+use crate::ui::ui_name_prompt::{NamePrompt, PromptPurpose};
+use crate::ui::ui_prefabs::create_palette_button;
+
+/// Column of level buttons left of the prefab palette
+#[derive(Component)]
+pub struct LevelPanel;
+
+#[derive(Clone, PartialEq, Debug)]
+pub enum LevelPanelAction {
+    ToggleEditor,
+    CycleGrid,
+    SaveLevel,
+    /// Load the named level, or the built-in arena for `None`
+    Load(Option<String>),
+}
+
+#[derive(Component)]
+pub struct LevelButton {
+    pub action: LevelPanelAction,
+    pub is_selected: bool,
+}
+
+/// Rebuild the panel when the editor is switched, a level is loaded or saved,
+/// or the grid changes
+pub fn update_level_panel(
+    mut commands: Commands,
+    editor: Res<LevelEditor>,
+    library: Res<LevelLibrary>,
+    current: Res<CurrentLevel>,
+    map: Res<ActionMap>,
+    panel_query: Query<Entity, With<LevelPanel>>,
+) {
+    if !editor.is_changed() && !library.is_changed() && !current.is_changed() && !map.is_changed()
+    {
+        return;
+    }
+
+    for entity in panel_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Px(120.0),
+                right: Val::Px(170.0),
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(6.0),
+                padding: UiRect::all(Val::Px(5.0)),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.1, 0.1, 0.1, 0.9)),
+            LevelPanel,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new("Levels"),
+                TextFont {
+                    font_size: 14.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(0.9, 0.9, 0.9)),
+            ));
+
+            let toggle_label = format!("Edit ({})", map.binding(InputAction::ToggleLevelEditor));
+            create_level_button(
+                parent,
+                LevelPanelAction::ToggleEditor,
+                &toggle_label,
+                editor.is_active,
+            );
+
+            if editor.is_active {
+                let grid_label = match editor.grid {
+                    Some(grid) => format!("Grid: {grid}px"),
+                    None => "Grid: off".to_string(),
+                };
+                create_level_button(parent, LevelPanelAction::CycleGrid, &grid_label, false);
+                create_level_button(parent, LevelPanelAction::SaveLevel, "Save level", false);
+            }
+
+            create_level_button(
+                parent,
+                LevelPanelAction::Load(None),
+                "Arena",
+                current.name.is_none(),
+            );
+            for name in &library.names {
+                let is_selected = current.name.as_ref() == Some(name);
+                let action = LevelPanelAction::Load(Some(name.clone()));
+                create_level_button(parent, action, name, is_selected);
+            }
+        });
+}
+
+fn create_level_button(
+    parent: &mut ChildBuilder,
+    action: LevelPanelAction,
+    label: &str,
+    is_selected: bool,
+) {
+    create_palette_button(parent, LevelButton { action, is_selected }, label, is_selected);
+}
+
+pub fn handle_level_buttons(
+    mut interaction_query: Query<
+        (&Interaction, &LevelButton, &mut BackgroundColor),
+        Changed<Interaction>,
+    >,
+    mut editor: ResMut<LevelEditor>,
+    mut prompt: ResMut<NamePrompt>,
+    mut load_events: EventWriter<LoadLevelEvent>,
+    current: Res<CurrentLevel>,
+) {
+    for (interaction, button, mut bg_color) in interaction_query.iter_mut() {
+        match *interaction {
+            Interaction::Pressed => {
+                *bg_color = BackgroundColor(Color::srgb(0.3, 0.5, 0.7));
+                match &button.action {
+                    LevelPanelAction::ToggleEditor => editor.is_active = !editor.is_active,
+                    LevelPanelAction::CycleGrid => editor.cycle_grid(),
+                    LevelPanelAction::SaveLevel => {
+                        // Saving again overwrites the level being edited unless renamed
+                        let name = current.name.as_deref().unwrap_or_default();
+                        prompt.open(PromptPurpose::Level, name);
+                    }
+                    LevelPanelAction::Load(name) => {
+                        load_events.send(LoadLevelEvent { name: name.clone() });
+                    }
+                }
+            }
+            Interaction::Hovered => {
+                *bg_color = BackgroundColor(Color::srgb(0.35, 0.35, 0.35));
+            }
+            Interaction::None => {
+                *bg_color = if button.is_selected {
+                    BackgroundColor(Color::srgb(0.3, 0.5, 0.7))
+                } else {
+                    BackgroundColor(Color::srgb(0.25, 0.25, 0.25))
+                };
+            }
+        }
+    }
+}
@@ -0,0 +1,175 @@
+//! Text prompt for the name a prefab or level is saved under
+
+use bevy::input::keyboard::{Key, KeyboardInput};
+use bevy::input::ButtonState;
+use bevy::prelude::*;
+use bevy::ui::FocusPolicy;
+
+use crate::systems::input::selection::Selection;
+use crate::systems::persistence::prefab::SavePrefabEvent;
+use crate::systems::persistence::scene_io::sanitize_file_name;
+use crate::systems::persistence::SaveLevelEvent;
+
+/// Names longer than this are cut off while typing
+const MAX_NAME_LEN: usize = 32;
+
+/// What the typed name is for
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum PromptPurpose {
+    /// Save the selection as a prefab
+    #[default]
+    Prefab,
+    /// Save the terrain as a level
+    Level,
+}
+
+/// Name being typed for the prefab or level about to be saved
+#[derive(Resource, Default)]
+pub struct NamePrompt {
+    pub is_open: bool,
+    pub name: String,
+    pub purpose: PromptPurpose,
+}
+
+impl NamePrompt {
+    /// Open the prompt with `name` already typed
+    pub fn open(&mut self, purpose: PromptPurpose, name: &str) {
+        self.is_open = true;
+        self.purpose = purpose;
+        self.name = name.to_string();
+    }
+}
+
+impl PromptPurpose {
+    fn noun(&self) -> &'static str {
+        match self {
+            PromptPurpose::Prefab => "prefab",
+            PromptPurpose::Level => "level",
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct NamePromptOverlay;
+
+/// Type the name while the prompt is open. Runs right after input is
+/// collected and swallows keyboard and mouse, so nothing else reacts meanwhile.
+pub fn type_prompt_name(
+    mut key_events: EventReader<KeyboardInput>,
+    mut keyboard: ResMut<ButtonInput<KeyCode>>,
+    mut mouse: ResMut<ButtonInput<MouseButton>>,
+    mut prompt: ResMut<NamePrompt>,
+    mut prefab_events: EventWriter<SavePrefabEvent>,
+    mut level_events: EventWriter<SaveLevelEvent>,
+    selection: Res<Selection>,
+) {
+    if !prompt.is_open {
+        key_events.clear();
+        return;
+    }
+
+    for event in key_events.read() {
+        if event.state != ButtonState::Pressed {
+            continue;
+        }
+
+        match &event.logical_key {
+            Key::Enter => {
+                let name = sanitize_file_name(&prompt.name);
+                if !name.is_empty() {
+                    match prompt.purpose {
+                        PromptPurpose::Prefab => {
+                            prefab_events.send(SavePrefabEvent {
+                                name,
+                                bodies: selection.entities.clone(),
+                            });
+                        }
+                        PromptPurpose::Level => {
+                            level_events.send(SaveLevelEvent { name });
+                        }
+                    }
+                    prompt.is_open = false;
+                }
+            }
+            Key::Escape => prompt.is_open = false,
+            Key::Backspace => {
+                prompt.name.pop();
+            }
+            Key::Space => prompt.name.push('_'),
+            Key::Character(text) => {
+                let typed = sanitize_file_name(text);
+                let room = MAX_NAME_LEN.saturating_sub(prompt.name.len());
+                prompt.name.extend(typed.chars().take(room));
+            }
+            _ => {}
+        }
+    }
+
+    keyboard.reset_all();
+    mouse.reset_all();
+}
+
+/// Show the prompt overlay with the name typed so far
+pub fn update_name_prompt(
+    mut commands: Commands,
+    prompt: Res<NamePrompt>,
+    overlay_query: Query<Entity, With<NamePromptOverlay>>,
+) {
+    if !prompt.is_changed() {
+        return;
+    }
+
+    for entity in overlay_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+
+    if !prompt.is_open {
+        return;
+    }
+
+    commands
+        .spawn((
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                position_type: PositionType::Absolute,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.6)),
+            FocusPolicy::Block,
+            GlobalZIndex(10),
+            NamePromptOverlay,
+        ))
+        .with_children(|parent| {
+            parent
+                .spawn((
+                    Node {
+                        flex_direction: FlexDirection::Column,
+                        padding: UiRect::all(Val::Px(20.0)),
+                        row_gap: Val::Px(8.0),
+                        ..default()
+                    },
+                    BackgroundColor(Color::srgb(0.15, 0.15, 0.15)),
+                ))
+                .with_children(|panel| {
+                    panel.spawn((
+                        Text::new(format!("Save {} as: {}_", prompt.purpose.noun(), prompt.name)),
+                        TextFont {
+                            font_size: 20.0,
+                            ..default()
+                        },
+                        TextColor(Color::srgb(0.9, 0.9, 0.9)),
+                    ));
+                    panel.spawn((
+                        Text::new("Enter to save, Esc to cancel"),
+                        TextFont {
+                            font_size: 14.0,
+                            ..default()
+                        },
+                        TextColor(Color::srgb(0.7, 0.7, 0.7)),
+                    ));
+                });
+        });
+}
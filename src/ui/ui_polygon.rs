@@ -0,0 +1,104 @@
+use bevy::prelude::*;
+
+use crate::entities::obstacles::polygon::PolygonMaterial;
+use crate::systems::input::polygon_tool::PolygonTool;
+use crate::ui::ui_prefabs::create_palette_button;
+use crate::ui::ui_topbar::{ObjectType, SelectedObject};
+
+/// Row of material buttons below the top bar, shown while the polygon tool is selected
+#[derive(Component)]
+pub struct PolygonPanel;
+
+#[derive(Component)]
+pub struct PolygonMaterialButton {
+    pub material: PolygonMaterial,
+    pub is_selected: bool,
+}
+
+/// Show the panel while the polygon tool is selected, rebuilt when the material changes
+pub fn update_polygon_panel(
+    mut commands: Commands,
+    selected_object: Res<SelectedObject>,
+    tool: Res<PolygonTool>,
+    panel_query: Query<Entity, With<PolygonPanel>>,
+    mut shown: Local<Option<PolygonMaterial>>,
+) {
+    let wanted = (selected_object.object_type == ObjectType::Polygon).then_some(tool.material);
+    if wanted == *shown {
+        return;
+    }
+    *shown = wanted;
+
+    for entity in panel_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+
+    let Some(current) = wanted else {
+        return;
+    };
+
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Px(70.0),
+                left: Val::Px(10.0),
+                align_items: AlignItems::Center,
+                column_gap: Val::Px(10.0),
+                padding: UiRect::all(Val::Px(5.0)),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.1, 0.1, 0.1, 0.9)),
+            PolygonPanel,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new("Material:"),
+                TextFont {
+                    font_size: 14.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(0.9, 0.9, 0.9)),
+            ));
+
+            for material in PolygonMaterial::ALL {
+                let is_selected = material == current;
+                create_palette_button(
+                    parent,
+                    PolygonMaterialButton {
+                        material,
+                        is_selected,
+                    },
+                    material.label(),
+                    is_selected,
+                );
+            }
+        });
+}
+
+pub fn handle_polygon_material_buttons(
+    mut interaction_query: Query<
+        (&Interaction, &PolygonMaterialButton, &mut BackgroundColor),
+        Changed<Interaction>,
+    >,
+    mut tool: ResMut<PolygonTool>,
+) {
+    for (interaction, button, mut bg_color) in interaction_query.iter_mut() {
+        match *interaction {
+            Interaction::Pressed => {
+                *bg_color = BackgroundColor(Color::srgb(0.3, 0.5, 0.7));
+                tool.material = button.material;
+            }
+            Interaction::Hovered => {
+                *bg_color = BackgroundColor(Color::srgb(0.35, 0.35, 0.35));
+            }
+            Interaction::None => {
+                *bg_color = if button.is_selected {
+                    BackgroundColor(Color::srgb(0.3, 0.5, 0.7))
+                } else {
+                    BackgroundColor(Color::srgb(0.25, 0.25, 0.25))
+                };
+            }
+        }
+    }
+}
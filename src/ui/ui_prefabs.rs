@@ -0,0 +1,134 @@
+use bevy::prelude::*;
+
+use crate::systems::input::selection::Selection;
+use crate::systems::persistence::prefab::PrefabLibrary;
+use crate::ui::ui_name_prompt::{NamePrompt, PromptPurpose};
+use crate::ui::ui_topbar::{ObjectButton, ObjectType, SelectedObject};
+
+/// Column of prefab buttons below the time controls
+#[derive(Component)]
+pub struct PrefabPalette;
+
+#[derive(Component)]
+pub struct PrefabSaveButton;
+
+/// Rebuild the palette when a prefab is found or saved
+pub fn update_prefab_palette(
+    mut commands: Commands,
+    library: Res<PrefabLibrary>,
+    mut selected_object: ResMut<SelectedObject>,
+    palette_query: Query<Entity, With<PrefabPalette>>,
+) {
+    if !library.is_changed() {
+        return;
+    }
+
+    for entity in palette_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+
+    if let ObjectType::Prefab(index) = selected_object.object_type {
+        if index >= library.names.len() {
+            *selected_object = SelectedObject::default();
+        }
+    }
+
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Px(120.0),
+                right: Val::Px(10.0),
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(6.0),
+                padding: UiRect::all(Val::Px(5.0)),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.1, 0.1, 0.1, 0.9)),
+            PrefabPalette,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new("Prefabs"),
+                TextFont {
+                    font_size: 14.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(0.9, 0.9, 0.9)),
+            ));
+
+            create_palette_button(parent, PrefabSaveButton, "Save selection", false);
+
+            for (index, name) in library.names.iter().enumerate() {
+                let object_type = ObjectType::Prefab(index);
+                let is_selected = selected_object.object_type == object_type;
+                create_palette_button(parent, ObjectButton { object_type }, name, is_selected);
+            }
+        });
+}
+
+pub(crate) fn create_palette_button(
+    parent: &mut ChildBuilder,
+    marker: impl Component,
+    label: &str,
+    is_selected: bool,
+) {
+    let bg_color = if is_selected {
+        Color::srgb(0.3, 0.5, 0.7)
+    } else {
+        Color::srgb(0.25, 0.25, 0.25)
+    };
+
+    parent
+        .spawn((
+            Button,
+            Node {
+                width: Val::Px(140.0),
+                height: Val::Px(30.0),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            BackgroundColor(bg_color),
+            marker,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new(label),
+                TextFont {
+                    font_size: 14.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(0.9, 0.9, 0.9)),
+            ));
+        });
+}
+
+/// The save button, when the pointer's interaction with it changes
+type SaveButtonChange = (Changed<Interaction>, With<PrefabSaveButton>);
+
+/// Ask for a prefab name when the selection is to be saved
+pub fn handle_prefab_save_button(
+    mut interaction_query: Query<(&Interaction, &mut BackgroundColor), SaveButtonChange>,
+    selection: Res<Selection>,
+    mut prompt: ResMut<NamePrompt>,
+) {
+    for (interaction, mut bg_color) in interaction_query.iter_mut() {
+        match *interaction {
+            Interaction::Pressed => {
+                *bg_color = BackgroundColor(Color::srgb(0.3, 0.5, 0.7));
+                if selection.entities.is_empty() {
+                    warn!("Select bodies with the Select tool before saving a prefab");
+                } else {
+                    prompt.open(PromptPurpose::Prefab, "");
+                }
+            }
+            Interaction::Hovered => {
+                *bg_color = BackgroundColor(Color::srgb(0.35, 0.35, 0.35));
+            }
+            Interaction::None => {
+                *bg_color = BackgroundColor(Color::srgb(0.25, 0.25, 0.25));
+            }
+        }
+    }
+}
@@ -0,0 +1,108 @@
+use bevy::prelude::*;
+
+use crate::entities::obstacles::shape::BodyShape;
+use crate::entities::spawnable::SpawnableRegistry;
+use crate::systems::input::drag_create::CreateDragState;
+use crate::ui::ui_prefabs::create_palette_button;
+use crate::ui::ui_topbar::{ObjectType, SelectedObject};
+
+/// Row of shape buttons below the top bar, shown while a spawnable that can
+/// be dragged out in other shapes is selected
+#[derive(Component)]
+pub struct ShapePanel;
+
+#[derive(Component)]
+pub struct ShapeButton {
+    pub shape: BodyShape,
+    pub is_selected: bool,
+}
+
+/// Show the panel while a shaped spawnable is selected, rebuilt when the shape changes
+pub fn update_shape_panel(
+    mut commands: Commands,
+    selected_object: Res<SelectedObject>,
+    registry: Res<SpawnableRegistry>,
+    create_drag_state: Res<CreateDragState>,
+    panel_query: Query<Entity, With<ShapePanel>>,
+    mut shown: Local<Option<BodyShape>>,
+) {
+    let is_shaped = match selected_object.object_type {
+        ObjectType::Spawnable(id) => registry.get(id).is_some_and(|s| s.is_shaped()),
+        _ => false,
+    };
+    let wanted = is_shaped.then_some(create_drag_state.shape);
+    if wanted == *shown {
+        return;
+    }
+    *shown = wanted;
+
+    for entity in panel_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+
+    let Some(current) = wanted else {
+        return;
+    };
+
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Px(70.0),
+                left: Val::Px(10.0),
+                align_items: AlignItems::Center,
+                column_gap: Val::Px(10.0),
+                padding: UiRect::all(Val::Px(5.0)),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.1, 0.1, 0.1, 0.9)),
+            ShapePanel,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new("Shape:"),
+                TextFont {
+                    font_size: 14.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(0.9, 0.9, 0.9)),
+            ));
+
+            for shape in BodyShape::ALL {
+                let is_selected = shape == current;
+                create_palette_button(
+                    parent,
+                    ShapeButton { shape, is_selected },
+                    shape.label(),
+                    is_selected,
+                );
+            }
+        });
+}
+
+pub fn handle_shape_buttons(
+    mut interaction_query: Query<
+        (&Interaction, &ShapeButton, &mut BackgroundColor),
+        Changed<Interaction>,
+    >,
+    mut create_drag_state: ResMut<CreateDragState>,
+) {
+    for (interaction, button, mut bg_color) in interaction_query.iter_mut() {
+        match *interaction {
+            Interaction::Pressed => {
+                *bg_color = BackgroundColor(Color::srgb(0.3, 0.5, 0.7));
+                create_drag_state.shape = button.shape;
+            }
+            Interaction::Hovered => {
+                *bg_color = BackgroundColor(Color::srgb(0.35, 0.35, 0.35));
+            }
+            Interaction::None => {
+                *bg_color = if button.is_selected {
+                    BackgroundColor(Color::srgb(0.3, 0.5, 0.7))
+                } else {
+                    BackgroundColor(Color::srgb(0.25, 0.25, 0.25))
+                };
+            }
+        }
+    }
+}
@@ -0,0 +1,34 @@
+use bevy::prelude::*;
+
+use crate::systems::stats::{LiveCounts, SessionStats};
+
+/// Text listing the live counts and the session counters
+#[derive(Component)]
+pub struct StatsHud;
+
+pub fn update_stats_hud(
+    counts: Res<LiveCounts>,
+    stats: Res<SessionStats>,
+    mut hud_query: Query<&mut Text, With<StatsHud>>,
+) {
+    let contents = format!(
+        "Ragdolls: {}\nBodies: {}\nDebris: {}\nParticles: {}\n\n\
+         Joints broken: {}\nLimbs severed: {}\nDestroyed: {}\nExplosions: {}\nFires: {}",
+        counts.ragdolls,
+        counts.bodies,
+        counts.debris,
+        counts.particles,
+        stats.joints_broken,
+        stats.limbs_severed,
+        stats.objects_destroyed,
+        stats.explosions,
+        stats.fires
+    );
+
+    for mut text in hud_query.iter_mut() {
+        // Only touch the text when it changed, so the layout is not redone every frame
+        if text.0 != contents {
+            text.0 = contents.clone();
+        }
+    }
+}
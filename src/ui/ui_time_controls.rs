@@ -0,0 +1,123 @@
+use bevy::prelude::*;
+
+use crate::systems::time::{TimeControlEvent, TimeControls};
+
+#[derive(Component)]
+pub struct TimeControlButton {
+    pub event: TimeControlEvent,
+}
+
+/// Text showing the current simulation speed
+#[derive(Component)]
+pub struct TimeScaleLabel;
+
+/// Spawn the pause, step and speed widgets under the right end of the top bar
+pub fn setup_time_controls(mut commands: Commands) {
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Px(70.0),
+                right: Val::Px(10.0),
+                height: Val::Px(40.0),
+                align_items: AlignItems::Center,
+                column_gap: Val::Px(6.0),
+                padding: UiRect::all(Val::Px(5.0)),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.1, 0.1, 0.1, 0.9)),
+        ))
+        .with_children(|parent| {
+            create_time_button(parent, TimeControlEvent::TogglePause, "Pause");
+            create_time_button(parent, TimeControlEvent::Step, "Step");
+            create_time_button(parent, TimeControlEvent::SlowDown, "-");
+
+            parent
+                .spawn(Node {
+                    width: Val::Px(70.0),
+                    justify_content: JustifyContent::Center,
+                    ..default()
+                })
+                .with_children(|parent| {
+                    parent.spawn((
+                        Text::new("1x"),
+                        TextFont {
+                            font_size: 14.0,
+                            ..default()
+                        },
+                        TextColor(Color::srgb(0.9, 0.9, 0.9)),
+                        TimeScaleLabel,
+                    ));
+                });
+
+            create_time_button(parent, TimeControlEvent::SpeedUp, "+");
+        });
+}
+
+fn create_time_button(parent: &mut ChildBuilder, event: TimeControlEvent, label: &str) {
+    parent
+        .spawn((
+            Button,
+            Node {
+                width: Val::Px(55.0),
+                height: Val::Px(30.0),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            BackgroundColor(Color::srgb(0.25, 0.25, 0.25)),
+            TimeControlButton { event },
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new(label),
+                TextFont {
+                    font_size: 14.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(0.9, 0.9, 0.9)),
+            ));
+        });
+}
+
+pub fn handle_time_control_buttons(
+    mut interaction_query: Query<
+        (&Interaction, &TimeControlButton, &mut BackgroundColor),
+        Changed<Interaction>,
+    >,
+    mut events: EventWriter<TimeControlEvent>,
+) {
+    for (interaction, button, mut bg_color) in interaction_query.iter_mut() {
+        match *interaction {
+            Interaction::Pressed => {
+                *bg_color = BackgroundColor(Color::srgb(0.3, 0.5, 0.7));
+                events.send(button.event);
+            }
+            Interaction::Hovered => {
+                *bg_color = BackgroundColor(Color::srgb(0.35, 0.35, 0.35));
+            }
+            Interaction::None => {
+                *bg_color = BackgroundColor(Color::srgb(0.25, 0.25, 0.25));
+            }
+        }
+    }
+}
+
+pub fn update_time_scale_label(
+    controls: Res<TimeControls>,
+    mut label_query: Query<&mut Text, With<TimeScaleLabel>>,
+) {
+    if !controls.is_changed() {
+        return;
+    }
+
+    let label = if controls.paused {
+        "Paused".to_string()
+    } else {
+        format!("{}x", controls.scale)
+    };
+
+    for mut text in label_query.iter_mut() {
+        text.0 = label.clone();
+    }
+}
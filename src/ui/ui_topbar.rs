@@ -1,22 +1,38 @@
 use bevy::prelude::*;
 
 use crate::core::components::{Flammable, OnFire};
+use crate::core::constants::SCENE_FILE_PATH;
+use crate::core::tuning::FireTuning;
 use crate::core::utils::get_cursor_world_position;
-use crate::entities::ragdoll::spawn_ragdoll_from_ui;
-use crate::entities::weapons::spawn_bomb_from_ui;
+use crate::entities::obstacles::shape::BodyShape;
+use crate::entities::spawnable::{place_spawnable, SpawnableId, SpawnableRegistry};
 use crate::systems::damage::connection::{ConstraintType, SelectionState, ConnectionMaterial};
+use crate::systems::debug::DebugOverlay;
 use crate::systems::effects::spawn_fire_from_ui;
+use crate::systems::history::{EditHistory, RedoEvent, UndoEvent};
+use crate::systems::input::action_map::{ActionInput, ActionMap, InputAction, RebindState};
 use crate::systems::input::drag::DragState;
+use crate::systems::persistence::{
+    LoadSceneEvent, PrefabLibrary, SaveSceneEvent, SpawnPrefabEvent,
+};
+use crate::systems::physics::spatial_index::SpatialIndex;
+use crate::systems::replay::UserAction;
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub enum ObjectType {
-    Ragdoll,
-    Bomb,
-    WoodenBox,
-    IronBlock,
+    /// An object from the `SpawnableRegistry`
+    Spawnable(SpawnableId),
     Fire,
     FixedConstraint,
     HingeConstraint,
+    /// Delete the clicked body, or the whole ragdoll it belongs to
+    Eraser,
+    /// Select bodies to move, rotate, freeze, ignite or delete together
+    Select,
+    /// Draw the outline of a custom body
+    Polygon,
+    /// Spawn the prefab at this index in the `PrefabLibrary`
+    Prefab(usize),
     // Material selection for connections
     MaterialWood,
     MaterialMetal,
@@ -24,15 +40,27 @@ pub enum ObjectType {
     MaterialPlastic,
 }
 
+/// One-shot actions triggered from the top bar, as opposed to tool selection
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum TopBarAction {
+    SaveScene,
+    LoadScene,
+    Undo,
+    Redo,
+    KeyBindings,
+    DebugMenu,
+}
+
 #[derive(Resource)]
 pub struct SelectedObject {
     pub object_type: ObjectType,
 }
 
 impl Default for SelectedObject {
+    /// The first registered spawnable, the ragdoll unless the registry was changed
     fn default() -> Self {
         Self {
-            object_type: ObjectType::Ragdoll,
+            object_type: ObjectType::Spawnable(SpawnableId(0)),
         }
     }
 }
@@ -42,13 +70,25 @@ pub struct ObjectButton {
     pub object_type: ObjectType,
 }
 
+#[derive(Component)]
+pub struct ActionButton {
+    pub action: TopBarAction,
+}
+
 #[derive(Component)]
 pub struct TopBarUI;
 
 #[derive(Component)]
 pub struct MaterialButton;
 
-pub fn setup_ui_topbar(mut commands: Commands) {
+/// Build the top bar: a button per registered spawnable, grouped by category,
+/// followed by the tools and actions
+pub fn setup_ui_topbar(
+    mut commands: Commands,
+    registry: Res<SpawnableRegistry>,
+    map: Res<ActionMap>,
+    selected_object: Res<SelectedObject>,
+) {
     commands
         .spawn((
             Node {
@@ -57,27 +97,50 @@ pub fn setup_ui_topbar(mut commands: Commands) {
                 justify_content: JustifyContent::Center,
                 align_items: AlignItems::Center,
                 padding: UiRect::all(Val::Px(10.0)),
-                column_gap: Val::Px(10.0),
+                column_gap: Val::Px(8.0),
                 ..default()
             },
             BackgroundColor(Color::srgba(0.15, 0.15, 0.15, 0.9)),
             TopBarUI,
         ))
         .with_children(|parent| {
-            create_object_button(parent, ObjectType::Ragdoll, "Ragdoll (R)", true);
-            create_object_button(parent, ObjectType::Bomb, "Bomb (B)", false);
-            create_object_button(parent, ObjectType::WoodenBox, "Box (W)", false);
-            create_object_button(parent, ObjectType::IronBlock, "Iron (I)", false);
-            create_object_button(parent, ObjectType::Fire, "Fire (F)", false);
-            create_object_button(parent, ObjectType::FixedConstraint, "Fixed (X)", false);
-            create_object_button(parent, ObjectType::HingeConstraint, "Hinge (H)", false);
+            for (id, spawnable) in registry.by_category() {
+                let object_type = ObjectType::Spawnable(id);
+                let label = match map.spawn_binding(spawnable.id) {
+                    Some(binding) => format!("{} ({binding})", spawnable.name),
+                    None => spawnable.name.to_string(),
+                };
+                create_object_button(
+                    parent,
+                    object_type,
+                    &label,
+                    Some(spawnable.color),
+                    selected_object.object_type == object_type,
+                );
+            }
+            // Fixed and hinge have no hotkey; fire shares the ignite key
+            let fire_label = format!("Fire ({})", map.binding(InputAction::Ignite));
+            create_object_button(parent, ObjectType::Fire, &fire_label, None, false);
+            create_object_button(parent, ObjectType::FixedConstraint, "Fixed", None, false);
+            create_object_button(parent, ObjectType::HingeConstraint, "Hinge", None, false);
+            create_object_button(parent, ObjectType::Eraser, "Eraser", None, false);
+            create_object_button(parent, ObjectType::Select, "Select", None, false);
+            create_object_button(parent, ObjectType::Polygon, "Polygon", None, false);
+            create_action_button(parent, TopBarAction::SaveScene, "Save");
+            create_action_button(parent, TopBarAction::LoadScene, "Load");
+            create_action_button(parent, TopBarAction::Undo, "Undo");
+            create_action_button(parent, TopBarAction::Redo, "Redo");
+            create_action_button(parent, TopBarAction::KeyBindings, "Keys");
+            create_action_button(parent, TopBarAction::DebugMenu, "Debug");
         });
 }
 
+/// A tool button; spawnables get a stripe of their colour along the bottom
 fn create_object_button(
     parent: &mut ChildBuilder,
     object_type: ObjectType,
     label: &str,
+    color: Option<Color>,
     is_selected: bool,
 ) {
     let bg_color = if is_selected {
@@ -90,20 +153,48 @@ fn create_object_button(
         .spawn((
             Button,
             Node {
-                width: Val::Px(120.0),
+                width: Val::Px(90.0),
                 height: Val::Px(40.0),
                 justify_content: JustifyContent::Center,
                 align_items: AlignItems::Center,
+                border: UiRect::bottom(Val::Px(if color.is_some() { 3.0 } else { 0.0 })),
                 ..default()
             },
             BackgroundColor(bg_color),
+            BorderColor(color.unwrap_or(Color::NONE)),
             ObjectButton { object_type },
         ))
         .with_children(|parent| {
             parent.spawn((
                 Text::new(label),
                 TextFont {
-                    font_size: 16.0,
+                    font_size: 14.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(0.9, 0.9, 0.9)),
+            ));
+        });
+}
+
+fn create_action_button(parent: &mut ChildBuilder, action: TopBarAction, label: &str) {
+    parent
+        .spawn((
+            Button,
+            Node {
+                width: Val::Px(60.0),
+                height: Val::Px(40.0),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            BackgroundColor(Color::srgb(0.25, 0.25, 0.25)),
+            ActionButton { action },
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new(label),
+                TextFont {
+                    font_size: 14.0,
                     ..default()
                 },
                 TextColor(Color::srgb(0.9, 0.9, 0.9)),
@@ -147,27 +238,115 @@ pub fn handle_button_clicks(
     }
 }
 
+pub fn handle_action_buttons(
+    mut interaction_query: Query<
+        (&Interaction, &ActionButton, &mut BackgroundColor),
+        Changed<Interaction>,
+    >,
+    mut save_events: EventWriter<SaveSceneEvent>,
+    mut load_events: EventWriter<LoadSceneEvent>,
+    mut undo_events: EventWriter<UndoEvent>,
+    mut redo_events: EventWriter<RedoEvent>,
+    mut rebind_state: ResMut<RebindState>,
+    mut debug_overlay: ResMut<DebugOverlay>,
+) {
+    for (interaction, button, mut bg_color) in interaction_query.iter_mut() {
+        match *interaction {
+            Interaction::Pressed => {
+                *bg_color = BackgroundColor(Color::srgb(0.3, 0.5, 0.7));
+                match button.action {
+                    TopBarAction::SaveScene => {
+                        save_events.send(SaveSceneEvent {
+                            path: SCENE_FILE_PATH.into(),
+                        });
+                    }
+                    TopBarAction::LoadScene => {
+                        load_events.send(LoadSceneEvent {
+                            path: SCENE_FILE_PATH.into(),
+                        });
+                    }
+                    TopBarAction::Undo => {
+                        undo_events.send(UndoEvent);
+                    }
+                    TopBarAction::Redo => {
+                        redo_events.send(RedoEvent);
+                    }
+                    TopBarAction::KeyBindings => {
+                        rebind_state.is_open = true;
+                    }
+                    TopBarAction::DebugMenu => {
+                        debug_overlay.is_menu_open = !debug_overlay.is_menu_open;
+                    }
+                }
+            }
+            Interaction::Hovered => {
+                *bg_color = BackgroundColor(Color::srgb(0.35, 0.35, 0.35));
+            }
+            Interaction::None => {
+                *bg_color = BackgroundColor(Color::srgb(0.25, 0.25, 0.25));
+            }
+        }
+    }
+}
+
 pub fn spawn_selected_object_on_click(
     mut commands: Commands,
-    mouse_button: Res<ButtonInput<MouseButton>>,
+    mut actions: EventWriter<UserAction>,
+    mut history: ResMut<EditHistory>,
+    mut prefab_events: EventWriter<SpawnPrefabEvent>,
+    input: ActionInput,
     selected_object: Res<SelectedObject>,
+    registry: Res<SpawnableRegistry>,
+    prefab_library: Res<PrefabLibrary>,
     drag_state: Res<DragState>,
     windows: Query<&Window>,
     camera_q: Query<(&Camera, &GlobalTransform)>,
+    fire_tuning: Res<FireTuning>,
+    spatial_index: Res<SpatialIndex>,
     flammable_query: Query<(Entity, &Transform, &Flammable), Without<OnFire>>,
 ) {
-    if mouse_button.just_released(MouseButton::Left) && drag_state.dragging_entity.is_none() {
+    if input.just_released(InputAction::Place) && drag_state.dragging_entity.is_none() {
         if let Some(world_pos) = get_cursor_world_position(&windows, &camera_q) {
             match selected_object.object_type {
-                ObjectType::Ragdoll => spawn_ragdoll_from_ui(&mut commands, world_pos),
-                ObjectType::Bomb => spawn_bomb_from_ui(&mut commands, world_pos),
-                // WoodenBox and IronBlock now use drag-to-create, so skip here
-                ObjectType::WoodenBox => {},
-                ObjectType::IronBlock => {},
-                ObjectType::Fire => spawn_fire_from_ui(&mut commands, world_pos, &flammable_query),
+                ObjectType::Spawnable(id) => {
+                    // Sized spawnables use drag-to-create, so skip them here
+                    if let Some(spawnable) = registry.get(id).filter(|s| !s.is_sized()) {
+                        place_spawnable(
+                            &mut commands,
+                            &mut history,
+                            &mut actions,
+                            spawnable,
+                            world_pos,
+                            None,
+                            BodyShape::Rectangle,
+                        );
+                    }
+                }
+                ObjectType::Fire => {
+                    spawn_fire_from_ui(
+                        &mut commands,
+                        world_pos,
+                        &fire_tuning,
+                        &spatial_index,
+                        &flammable_query,
+                    );
+                    actions.send(UserAction::Ignite { position: world_pos });
+                }
                 // FixedConstraint and HingeConstraint are handled by the connection system
                 ObjectType::FixedConstraint => {},
                 ObjectType::HingeConstraint => {},
+                // The eraser, selection and polygon tools have their own input systems
+                ObjectType::Eraser => {},
+                ObjectType::Select => {},
+                ObjectType::Polygon => {},
+                ObjectType::Prefab(index) => {
+                    if let Some(name) = prefab_library.names.get(index) {
+                        prefab_events.send(SpawnPrefabEvent {
+                            name: name.clone(),
+                            position: world_pos,
+                        });
+                    }
+                }
                 // Material selection buttons don't spawn objects
                 ObjectType::MaterialWood => {},
                 ObjectType::MaterialMetal => {},
@@ -0,0 +1,24 @@
+//! Runs the plugin group headlessly, the way tools and tests drive the sandbox
+//!
+//! Conflicting queries and missing resources only show up when a system is
+//! first run, so this catches what `cargo check` cannot. The group leaves out
+//! the members that need a window unless asked for them, with or without the
+//! `render` feature.
+
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+use bevy_sandbox_ragdoll::SandboxPlugins;
+
+const FRAMES: usize = 120;
+
+#[test]
+fn sandbox_plugins_run_headless() {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, TransformPlugin, HierarchyPlugin))
+        .add_plugins(RapierPhysicsPlugin::<NoUserData>::pixels_per_meter(100.0))
+        .add_plugins(SandboxPlugins);
+
+    for _ in 0..FRAMES {
+        app.update();
+    }
+}